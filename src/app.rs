@@ -1,16 +1,22 @@
-use crate::config::{AppConfig, BellNotification, SessionFolder, Theme};
+use crate::bell;
+use crate::clipboard::AppClipboard;
+use crate::config::{AppConfig, BellNotification, HostSpec, SessionConfig, SessionKind, SessionFolder, Theme, MAX_FONT_SIZE, MAX_LINE_HEIGHT, MIN_BELL_MIN_INTERVAL_MS, MIN_FONT_SIZE, MIN_LINE_HEIGHT};
 use crate::config_dialog::{ConfigDialog, DialogMode, DialogResult};
 use crate::debug;
-use crate::input::{InputHandler, InputResult};
+use crate::import::{self, ImportSource};
+use crate::input::{describe_key_input, mouse_button_code, mouse_report, InputHandler, InputResult, MOUSE_NO_BUTTON, MOUSE_WHEEL_DOWN, MOUSE_WHEEL_UP};
 use crate::options_dialog::{OptionsDialog, OptionsResult};
 use crate::persistence::{
-    load_app_config, load_open_sessions, save_app_config, save_open_sessions, PersistenceManager,
+    load_app_config, load_open_sessions, save_app_config, save_open_sessions, DeletedItems, PersistenceManager,
 };
-use crate::selection::SelectionManager;
+use crate::search::SearchState;
+use crate::selection::{SelectionManager, SelectionMove};
 use crate::session_manager::{SessionManagerAction, SessionManagerUi};
-use crate::ssh::manager::SessionManager;
+use crate::ssh::connection::SIGNALS;
+use crate::ssh::manager::{ManagedSession, Notice, SessionManager};
 use crate::tabs::{TabAction, TabBar};
-use arboard::Clipboard;
+use crate::terminal::vt100::MouseTracking;
+use crate::terminal::renderer::{StyledFaces, TERMINAL_BOLD_FAMILY, TERMINAL_BOLD_ITALIC_FAMILY, TERMINAL_ITALIC_FAMILY};
 use egui::{CentralPanel, Color32, Context, TopBottomPanel, FontDefinitions, FontData, FontFamily};
 use uuid::Uuid;
 
@@ -29,13 +35,31 @@ const MIN_SIDEBAR_WIDTH: f32 = 170.0;
 const MAX_SIDEBAR_WIDTH: f32 = 400.0;
 const DEFAULT_SIDEBAR_WIDTH: f32 = 130.0;
 
-// Windows API constants for bell sound
-#[cfg(windows)]
-const MB_ICONASTERISK: u32 = 0x00000040;
-
 // Bell notification constants
 const BELL_BLINK_DURATION_MS: u64 = 100;
 
+// Number of lines shown in the multi-line paste confirmation
+const PASTE_PREVIEW_LINES: usize = 5;
+
+// Initial tint offered when enabling a folder's tab color
+const DEFAULT_FOLDER_COLOR: [u8; 3] = [70, 130, 180];
+
+// Find bar placement
+const SEARCH_BAR_MARGIN: f32 = 20.0;
+const SEARCH_BAR_TOP_OFFSET: f32 = 60.0;
+const SEARCH_FIELD_WIDTH: f32 = 200.0;
+
+// Scroll lock indicator inset from the terminal's top-right corner
+const INDICATOR_MARGIN: f32 = 6.0;
+
+// Reconnect toasts in the bottom-right corner
+const TOAST_DURATION_SECS: u64 = 4;
+const TOAST_MARGIN: f32 = 12.0;
+const TOAST_ERROR_COLOR: Color32 = Color32::from_rgb(244, 67, 54);
+const TOAST_SUCCESS_COLOR: Color32 = Color32::from_rgb(0, 200, 83);
+// How long a delete can be undone
+const UNDO_DURATION_SECS: u64 = 10;
+
 
 // Welcome screen spacing
 const WELCOME_SCREEN_TOP_MARGIN: f32 = 100.0;
@@ -81,18 +105,37 @@ pub struct YasshApp {
     options_dialog: OptionsDialog,
     input_handler: InputHandler,
     selection_managers: std::collections::HashMap<Uuid, SelectionManager>,
-    clipboard: Option<Clipboard>,
-    sidebar_visible: bool,
-    folder_rename_dialog: Option<(Uuid, String)>,
-    confirm_delete_session: Option<Uuid>,
+    clipboard: AppClipboard,
+    folder_rename_dialog: Option<(Uuid, String, Option<[u8; 3]>)>,
+    confirm_delete_session: Option<Vec<Uuid>>,
     confirm_delete_folder: Option<Uuid>,
+    confirm_paste: Option<(Uuid, String)>,
+    // Files dropped onto a terminal, waiting for the user to pick upload or insert-path
+    dropped_files: Option<(Uuid, Vec<std::path::PathBuf>)>,
+    // Session whose keyboard-interactive prompts are shown, and the answers typed so far
+    auth_prompt: Option<(Uuid, Vec<String>)>,
+    // Outcome of the last session import, shown until dismissed
+    import_message: Option<String>,
+    toasts: Vec<(std::time::Instant, Notice)>,
+    // Last session/folder delete, offered for undo until it expires
+    undo_delete: Option<(std::time::Instant, DeletedItems)>,
     terminal_focus_id: egui::Id,
     show_about_dialog: bool,
+    show_diagnostics: bool,
+    // Write each key's outgoing bytes to the debug log (toggled in Terminal Diagnostics)
+    log_key_input: bool,
+    search: Option<SearchState>,
+    search_has_focus: bool,
+    search_request_focus: bool,
     theme_applied: bool,
     frame_count: u64,
     last_sidebar_width: f32,
     current_font: String,
+    styled_faces: StyledFaces,
     bell_blink_timer: Option<(std::time::Instant, BellNotification)>,
+    last_bell: Option<std::time::Instant>,
+    // Last cell a motion report was sent for, so moving within a cell doesn't repeat it
+    mouse_report_cell: Option<(usize, usize)>,
 }
 
 
@@ -100,20 +143,35 @@ pub struct YasshApp {
 const TERMINAL_FONT_NAME: &str = "terminal_mono";
 
 fn load_system_font(font_name: &str) -> Option<Vec<u8>> {
+    load_system_font_face(font_name, Weight::NORMAL, Style::Normal)
+}
+
+/// Load a specific face of a font family; None if the family has no face of that weight/style
+fn load_system_font_face(font_name: &str, weight: Weight, style: Style) -> Option<Vec<u8>> {
     let source = SystemSource::new();
     // Try to find the font by family name
     let handle = source.select_best_match(
         &[FamilyName::Title(font_name.to_string())],
-        &Properties::new().weight(Weight::NORMAL).style(Style::Normal),
+        &Properties::new().weight(weight).style(style),
     ).ok()?;
     let font = handle.load().ok()?;
+    // The best match may be the regular face when the family has no styled variant;
+    // the regular face itself is taken at whatever weight the family has
+    let properties = font.properties();
+    let too_light = weight > Weight::NORMAL && properties.weight < weight;
+    if too_light || (style != Style::Normal && properties.style == Style::Normal) {
+        return None;
+    }
     font.copy_font_data().map(|arc| (*arc).clone())
 }
 
 const SYMBOL_FONT_NAME: &str = "symbol_fallback";
 
-fn setup_terminal_font(ctx: &Context, font_name: &str) {
+const FALLBACK_FONT_NAME: &str = "emoji_fallback";
+
+fn setup_terminal_font(ctx: &Context, font_name: &str, fallback_font: &str) -> StyledFaces {
     let mut fonts = FontDefinitions::default();
+    let mut faces = StyledFaces::default();
     // Try to load the requested font
     if let Some(font_data) = load_system_font(font_name) {
         fonts.font_data.insert(
@@ -129,37 +187,221 @@ fn setup_terminal_font(ctx: &Context, font_name: &str) {
     } else {
         log::warn!("Could not load font '{}', using default monospace", font_name);
     }
+    // Glyphs missing from the terminal font (emoji, icons) fall through to the fallback font
+    if !fallback_font.is_empty() {
+        if let Some(fallback_data) = load_system_font(fallback_font) {
+            fonts.font_data.insert(
+                FALLBACK_FONT_NAME.to_owned(),
+                Arc::new(FontData::from_owned(fallback_data)),
+            );
+            fonts.families
+                .entry(FontFamily::Monospace)
+                .or_default()
+                .push(FALLBACK_FONT_NAME.to_owned());
+            log::info!("Added {} as terminal fallback font", fallback_font);
+        } else {
+            log::warn!("Could not load fallback font '{}'", fallback_font);
+        }
+    }
     // Add Segoe UI Symbol as fallback for geometric shapes (arrows, etc.)
     if let Some(symbol_data) = load_system_font("Segoe UI Symbol") {
         fonts.font_data.insert(
             SYMBOL_FONT_NAME.to_owned(),
             Arc::new(FontData::from_owned(symbol_data)),
         );
-        // Add as fallback for proportional (UI) font
-        fonts.families
-            .entry(FontFamily::Proportional)
-            .or_default()
-            .push(SYMBOL_FONT_NAME.to_owned());
-        log::info!("Added Segoe UI Symbol as fallback for UI symbols");
+        // Add as fallback for the proportional (UI) font and the terminal chain
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(SYMBOL_FONT_NAME.to_owned());
+        }
+        log::info!("Added Segoe UI Symbol as fallback for symbols");
+    }
+    // Styled families put the matching face first and fall back to the regular chain,
+    // so they are always registered even when the face is missing
+    let monospace = fonts.families.get(&FontFamily::Monospace).cloned().unwrap_or_default();
+    let variants = [
+        (TERMINAL_BOLD_FAMILY, Weight::BOLD, Style::Normal, &mut faces.bold),
+        (TERMINAL_ITALIC_FAMILY, Weight::NORMAL, Style::Italic, &mut faces.italic),
+        (TERMINAL_BOLD_ITALIC_FAMILY, Weight::BOLD, Style::Italic, &mut faces.bold_italic),
+    ];
+    for (family, weight, style, found) in variants {
+        let mut chain = monospace.clone();
+        if let Some(face_data) = load_system_font_face(font_name, weight, style) {
+            fonts.font_data.insert(family.to_owned(), Arc::new(FontData::from_owned(face_data)));
+            chain.insert(0, family.to_owned());
+            *found = true;
+            log::info!("Loaded {} face of terminal font", family);
+        }
+        fonts.families.insert(FontFamily::Name(family.into()), chain);
     }
     ctx.set_fonts(fonts);
+    faces
+}
+
+/// A local path as typed into a shell, single-quoted unless every character is shell-safe
+fn quote_path(path: &std::path::Path) -> String {
+    let text = path.display().to_string();
+    let safe = |c: char| c.is_ascii_alphanumeric() || "/._-+:,@%=".contains(c);
+    if !text.is_empty() && text.chars().all(safe) {
+        text
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Whether a hyperlink from the remote side may be opened locally; file and custom schemes
+/// would refer to (or run) things on this machine
+fn is_openable_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    ["http://", "https://", "ftp://", "mailto:"].iter().any(|scheme| lower.starts_with(scheme))
+}
+
+/// Send the pointer events over the terminal to a program that enabled mouse tracking
+fn send_mouse_reports(
+    ui: &egui::Ui,
+    session: &mut ManagedSession,
+    response: &egui::Response,
+    tracking: MouseTracking,
+    last_cell: &mut Option<(usize, usize)>,
+) {
+    let sgr = session.emulator.mouse_sgr();
+    let wheel_steps = session.renderer.take_wheel_steps();
+    let buffer = session.emulator.buffer();
+    let screen_cell = |pos: egui::Pos2| {
+        session.renderer
+            .cell_at_pos(pos, response.rect.min, buffer, response.rect.height(), session.scroll_offset())
+            .and_then(|(line, col)| {
+                let row = line.checked_sub(buffer.scrollback_len())?;
+                (row < buffer.rows()).then_some((col, row))
+            })
+    };
+    // Releases outside the grid are moved to the nearest cell so the program never sees a button stuck down
+    let nearest_cell = |pos: egui::Pos2| {
+        let origin = session.renderer.grid_origin(response.rect);
+        let padding = origin - response.rect.min;
+        let max_x = origin.x + buffer.cols() as f32 * session.renderer.cell_width() - 1.0;
+        let max_y = response.rect.max.y - padding.y - 1.0;
+        let clamped = egui::pos2(pos.x.clamp(origin.x, max_x.max(origin.x)), pos.y.clamp(origin.y, max_y.max(origin.y)));
+        screen_cell(clamped)
+    };
+    let (events, modifiers, held) = ui.input(|i| {
+        let held = [egui::PointerButton::Primary, egui::PointerButton::Middle, egui::PointerButton::Secondary]
+            .into_iter()
+            .find(|button| i.pointer.button_down(*button));
+        (i.events.clone(), i.modifiers, held)
+    });
+    let mut reports = Vec::new();
+    for event in events {
+        match event {
+            egui::Event::PointerButton { pos, button, pressed, .. } => {
+                // Releases are reported even off the terminal so a drag can always finish
+                if pressed && !response.contains_pointer() {
+                    continue;
+                }
+                let cell = if pressed {
+                    screen_cell(pos)
+                } else {
+                    nearest_cell(pos).or(*last_cell).or(Some((0, 0)))
+                };
+                let (Some(code), Some((col, row))) = (mouse_button_code(button), cell) else {
+                    continue;
+                };
+                *last_cell = Some((col, row));
+                reports.extend(mouse_report(code, pressed, false, modifiers, col, row, sgr));
+            }
+            egui::Event::PointerMoved(pos) => {
+                let code = match (tracking, held) {
+                    (MouseTracking::Drags | MouseTracking::AllMotion, Some(button)) => mouse_button_code(button),
+                    (MouseTracking::AllMotion, None) if response.contains_pointer() => Some(MOUSE_NO_BUTTON),
+                    _ => None,
+                };
+                let (Some(code), Some(cell)) = (code, screen_cell(pos)) else {
+                    continue;
+                };
+                if *last_cell != Some(cell) {
+                    *last_cell = Some(cell);
+                    reports.extend(mouse_report(code, true, true, modifiers, cell.0, cell.1, sgr));
+                }
+            }
+            _ => {}
+        }
+    }
+    if wheel_steps != 0 {
+        if let Some((col, row)) = response.hover_pos().and_then(screen_cell) {
+            let code = if wheel_steps > 0 { MOUSE_WHEEL_UP } else { MOUSE_WHEEL_DOWN };
+            for _ in 0..wheel_steps.unsigned_abs() {
+                reports.extend(mouse_report(code, true, false, modifiers, col, row, sgr));
+            }
+        }
+    }
+    for report in reports {
+        session.send(&report);
+    }
+}
+
+/// Remove ANSI escape sequences and control characters other than newline and tab
+fn strip_terminal_controls(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\n' | '\t' => out.push(ch),
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, DCS, SOS, PM, APC: string terminated by BEL or ST (ESC \)
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Other escapes: optional intermediates followed by one final character
+                Some(c) if (' '..='/').contains(&c) => {
+                    while chars.next_if(|c| (' '..='/').contains(c)).is_some() {}
+                    chars.next();
+                }
+                _ => {}
+            },
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
 }
 
 
 impl YasshApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, launch_target: Option<HostSpec>) -> Self {
         let app_config = load_app_config();
         // Apply theme immediately on startup
         Self::apply_theme(&cc.egui_ctx, app_config.theme);
         let mut persistence = PersistenceManager::new();
         let _ = persistence.load();
-        let clipboard = Clipboard::new().ok();
+        let clipboard = AppClipboard::new();
         // Load default font
         let default_font = String::from("Consolas");
-        setup_terminal_font(&cc.egui_ctx, &default_font);
+        let styled_faces = setup_terminal_font(&cc.egui_ctx, &default_font, &app_config.fallback_font);
 
         // Register Tab interception plugin
         cc.egui_ctx.add_plugin(TabInterceptionPlugin);
+        // Restore the last fullscreen choice
+        if app_config.fullscreen {
+            cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        }
+        if app_config.always_on_top {
+            cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(Self::window_level(true)));
+        }
 
         let mut app = Self {
             app_config,
@@ -172,18 +414,34 @@ impl YasshApp {
             input_handler: InputHandler::new(),
             selection_managers: std::collections::HashMap::new(),
             clipboard,
-            sidebar_visible: true,
             folder_rename_dialog: None,
             confirm_delete_session: None,
             confirm_delete_folder: None,
+            confirm_paste: None,
+            dropped_files: None,
+            auth_prompt: None,
+            import_message: None,
+            toasts: Vec::new(),
+            undo_delete: None,
             terminal_focus_id: egui::Id::new("terminal_input_focus"),
             show_about_dialog: false,
+            show_diagnostics: false,
+            log_key_input: false,
+            search: None,
+            search_has_focus: false,
+            search_request_focus: false,
             theme_applied: false,
             frame_count: 0,
             last_sidebar_width: DEFAULT_SIDEBAR_WIDTH,
             current_font: default_font,
+            styled_faces,
             bell_blink_timer: None,
+            mouse_report_cell: None,
+            last_bell: None,
         };
+        app.config_dialog.set_terminal_defaults(app.app_config.terminal_defaults.clone());
+        app.session_manager.set_max_connecting(app.app_config.max_connecting_sessions);
+        app.session_manager.set_connection_profiles(&app.app_config.connection_profiles);
         // Restore open sessions
         if let Ok(open_ids) = load_open_sessions() {
             debug::log(&format!("[DEBUG APP] Restoring {} open sessions: {:?}", open_ids.len(), open_ids));
@@ -195,9 +453,32 @@ impl YasshApp {
                 }
             }
         }
+        if let Some(spec) = launch_target {
+            app.open_launch_target(spec);
+        }
         app
     }
 
+    /// Connect to a host given on the command line; without a username the
+    /// quick connect dialog is opened prefilled instead
+    fn open_launch_target(&mut self, spec: HostSpec) {
+        let Some(username) = spec.username.clone() else {
+            self.config_dialog.open_quick_connect_to(spec);
+            return;
+        };
+        let defaults = self.app_config.terminal_defaults.session_config();
+        let config = SessionConfig {
+            name: spec.host.clone(),
+            host: spec.host,
+            port: spec.port.unwrap_or(defaults.port),
+            username,
+            ..defaults
+        };
+        let session_id = self.session_manager.add_session(config);
+        self.session_manager.connect_session(session_id);
+        self.session_manager.set_active(session_id);
+    }
+
     fn apply_theme(ctx: &Context, theme: Theme) {
         let visuals = match theme {
             Theme::Dark => egui::Visuals::dark(),
@@ -239,15 +520,32 @@ impl YasshApp {
             });
     }
 
+    /// Stamp a saved session as just opened, for its sidebar tooltip
+    fn record_last_connected(&mut self, id: Uuid) {
+        if let Some(mut config) = self.persistence.get_session(id).cloned() {
+            config.last_connected = Some(chrono::Utc::now());
+            self.persistence.update_session(config);
+            let _ = self.persistence.save();
+        }
+    }
+
     fn handle_session_manager_action(&mut self, action: SessionManagerAction) {
         match action {
             SessionManagerAction::Connect(id) => {
+                self.record_last_connected(id);
                 if let Some(config) = self.persistence.get_session(id).cloned() {
                     let session_id = self.session_manager.add_session(config);
                     self.session_manager.connect_session(session_id);
                     self.session_manager.set_active(session_id);
                 }
             }
+            SessionManagerAction::ConnectBackground(id) => {
+                self.record_last_connected(id);
+                if let Some(config) = self.persistence.get_session(id).cloned() {
+                    let session_id = self.session_manager.add_session(config);
+                    self.session_manager.connect_session(session_id);
+                }
+            }
             SessionManagerAction::Edit(id) => {
                 if let Some(config) = self.persistence.get_session(id).cloned() {
                     self.config_dialog.open_edit(config);
@@ -257,8 +555,48 @@ impl YasshApp {
                 let _ = self.persistence.duplicate_session(id);
                 let _ = self.persistence.save();
             }
+            SessionManagerAction::CopySshCommand(id) => {
+                if let Some(config) = self.persistence.get_session(id) {
+                    let config = config.with_profile(self.app_config.connection_profile(config.profile_id));
+                    let gateway = config.gateway_session.and_then(|gateway_id| self.persistence.get_session(gateway_id));
+                    let command = config.ssh_command(gateway);
+                    self.clipboard.set_text(&command);
+                }
+            }
+            SessionManagerAction::TogglePin(id) => {
+                if let Some(mut config) = self.persistence.get_session(id).cloned() {
+                    config.pinned = !config.pinned;
+                    self.persistence.update_session(config);
+                    let _ = self.persistence.save();
+                }
+            }
+            SessionManagerAction::ConnectMany(ids) => {
+                for id in ids {
+                    self.handle_session_manager_action(SessionManagerAction::Connect(id));
+                }
+            }
+            SessionManagerAction::DuplicateMany(ids) => {
+                for id in ids {
+                    self.handle_session_manager_action(SessionManagerAction::Duplicate(id));
+                }
+            }
+            SessionManagerAction::DeleteMany(ids) => {
+                // One confirmation for the whole selection
+                self.confirm_delete_session = Some(ids);
+            }
+            SessionManagerAction::MoveSessions { session_ids, folder_id } => {
+                for session_id in session_ids {
+                    self.handle_session_manager_action(SessionManagerAction::MoveSession { session_id, folder_id });
+                }
+            }
+            SessionManagerAction::MoveSession { session_id, folder_id } => {
+                // Append at the end of the target group
+                let order = self.persistence.get_last_order_in_folder(folder_id) + 1;
+                self.persistence.move_session(session_id, folder_id, order);
+                let _ = self.persistence.save();
+            }
             SessionManagerAction::Delete(id) => {
-                self.confirm_delete_session = Some(id);
+                self.confirm_delete_session = Some(vec![id]);
             }
             SessionManagerAction::NewSession => {
                 self.config_dialog.open_new();
@@ -267,20 +605,27 @@ impl YasshApp {
                 self.config_dialog.open_new_in_folder(folder_id);
             }
             SessionManagerAction::NewFolder => {
-                let folder = SessionFolder::new(String::from("New Folder"));
-                let folder_id = folder.id;
-                self.persistence.add_folder(folder);
-                let _ = self.persistence.save();
-                self.folder_rename_dialog = Some((folder_id, String::from("New Folder")));
+                self.create_folder();
             }
             SessionManagerAction::EditFolder(id) => {
                 if let Some(folder) = self.persistence.get_folder(id) {
-                    self.folder_rename_dialog = Some((id, folder.name.clone()));
+                    let color = folder.color.as_ref().map(|c| [c.r, c.g, c.b]);
+                    self.folder_rename_dialog = Some((id, folder.name.clone(), color));
                 }
             }
             SessionManagerAction::DeleteFolder(id) => {
                 self.confirm_delete_folder = Some(id);
             }
+            SessionManagerAction::TogglePinFolder(id) => {
+                if let Some(pinned) = self.persistence.get_folder(id).map(|f| f.pinned) {
+                    self.persistence.set_folder_pinned(id, !pinned);
+                    let _ = self.persistence.save();
+                }
+            }
+            SessionManagerAction::ShiftFolder { folder_id, up } => {
+                self.persistence.shift_folder(folder_id, up);
+                let _ = self.persistence.save();
+            }
         }
     }
 
@@ -302,6 +647,21 @@ impl YasshApp {
                             session.update_config(config);
                         }
                     }
+                    DialogMode::ReconnectAs(connection_id) => {
+                        // The credentials apply to this connection only and are never saved
+                        if let Some(session) = self.session_manager.get_session_mut(connection_id) {
+                            session.update_config(config);
+                            session.connect();
+                        }
+                    }
+                    DialogMode::SaveConnection(connection_id) => {
+                        self.persistence.add_session(config.clone());
+                        let _ = self.persistence.save();
+                        // The tab now belongs to the stored session (same id) and takes its name
+                        if let Some(session) = self.session_manager.get_session_mut(connection_id) {
+                            session.update_config(config);
+                        }
+                    }
                     DialogMode::QuickConnect => {
                         let session_id = self.session_manager.add_session(config);
                         self.session_manager.connect_session(session_id);
@@ -317,16 +677,105 @@ impl YasshApp {
         match result {
             OptionsResult::Saved(config) => {
                 let theme_changed = self.app_config.theme != config.theme;
+                let fallback_font_changed = self.app_config.fallback_font != config.fallback_font;
                 self.app_config = config;
                 if theme_changed {
                     Self::apply_theme(ctx, self.app_config.theme);
                 }
+                if fallback_font_changed {
+                    self.styled_faces = setup_terminal_font(ctx, &self.current_font, &self.app_config.fallback_font);
+                }
+                self.config_dialog.set_terminal_defaults(self.app_config.terminal_defaults.clone());
+                self.session_manager.set_max_connecting(self.app_config.max_connecting_sessions);
+                self.session_manager.set_connection_profiles(&self.app_config.connection_profiles);
                 let _ = save_app_config(&self.app_config);
             }
+            OptionsResult::ApplyTerminalDefaults(defaults) => {
+                // Open connections only; stored sessions keep their own settings
+                for session in self.session_manager.sessions_mut() {
+                    let mut config = session.config.clone();
+                    defaults.apply_to(&mut config);
+                    session.update_config(config);
+                }
+            }
             OptionsResult::Cancelled => {}
         }
     }
 
+    /// Grow or shrink the OS window so the active terminal gets the requested grid.
+    /// The grid follows the window on the next frame; maximized/fullscreen windows are left alone.
+    fn apply_resize_request(&mut self, ctx: &Context, rows: usize, cols: usize) {
+        let Some(session) = self.session_manager.active_session() else {
+            return;
+        };
+        let (locked, inner_rect) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.maximized.unwrap_or(false) || viewport.fullscreen.unwrap_or(false),
+                viewport.inner_rect,
+            )
+        });
+        let Some(inner_rect) = inner_rect else {
+            return;
+        };
+        if locked {
+            debug::log(&format!("Ignoring server resize to {}x{} while maximized", cols, rows));
+            return;
+        }
+        let buffer = session.emulator.buffer();
+        let delta = egui::vec2(
+            (cols as f32 - buffer.cols() as f32) * session.renderer.cell_width(),
+            (rows as f32 - buffer.rows() as f32) * session.renderer.cell_height(),
+        );
+        if delta == egui::Vec2::ZERO {
+            return;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(inner_rect.size() + delta));
+    }
+
+    fn toggle_scroll_lock(&mut self) {
+        if let Some(session) = self.session_manager.active_session_mut() {
+            let locked = !session.is_scroll_locked();
+            session.set_scroll_locked(locked);
+        }
+    }
+
+    fn toggle_fullscreen(&mut self, ctx: &Context) {
+        self.app_config.fullscreen = !self.app_config.fullscreen;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.app_config.fullscreen));
+        let _ = save_app_config(&self.app_config);
+        // The terminal grid is recalculated from the new size on the next frame
+        ctx.request_repaint();
+    }
+
+    /// Add an empty folder and open the rename dialog for it
+    fn create_folder(&mut self) {
+        let folder = SessionFolder::new(String::from("New Folder"));
+        let folder_id = folder.id;
+        self.persistence.add_folder(folder);
+        let _ = self.persistence.save();
+        self.folder_rename_dialog = Some((folder_id, String::from("New Folder"), None));
+    }
+
+    fn toggle_sidebar(&mut self) {
+        self.app_config.sidebar_visible = !self.app_config.sidebar_visible;
+        let _ = save_app_config(&self.app_config);
+    }
+
+    fn toggle_always_on_top(&mut self, ctx: &Context) {
+        self.app_config.always_on_top = !self.app_config.always_on_top;
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(Self::window_level(self.app_config.always_on_top)));
+        let _ = save_app_config(&self.app_config);
+    }
+
+    fn window_level(always_on_top: bool) -> egui::WindowLevel {
+        if always_on_top {
+            egui::WindowLevel::AlwaysOnTop
+        } else {
+            egui::WindowLevel::Normal
+        }
+    }
+
     fn close_active_session(&mut self) {
         if let Some(session) = self.session_manager.active_session() {
             let session_id = session.id;
@@ -375,6 +824,19 @@ impl YasshApp {
         false
     }
 
+    fn open_reconnect_as(&mut self, id: Uuid) {
+        if let Some(session) = self.session_manager.get_session(id) {
+            self.config_dialog.open_reconnect_as(id, session.config.clone());
+        }
+    }
+
+    /// Open a tab with another shell over the connection of session `id`
+    fn open_new_shell(&mut self, id: Uuid) {
+        if let Some(session_id) = self.session_manager.open_shell_on(id) {
+            self.session_manager.set_active(session_id);
+        }
+    }
+
     fn handle_tab_action(&mut self, action: TabAction) {
         match action {
             TabAction::Select(id) => {
@@ -389,6 +851,15 @@ impl YasshApp {
                     session.connect();
                 }
             }
+            TabAction::ReconnectAll => {
+                self.session_manager.reconnect_disconnected();
+            }
+            TabAction::ReconnectAs(id) => {
+                self.open_reconnect_as(id);
+            }
+            TabAction::NewShell(id) => {
+                self.open_new_shell(id);
+            }
             TabAction::EditSettings(id) => {
                 if let Some(session) = self.session_manager.get_session(id) {
                     // Edit the connection's runtime settings, not the stored session
@@ -399,26 +870,148 @@ impl YasshApp {
         }
     }
 
+    fn selected_text(&self) -> Option<String> {
+        let session = self.session_manager.active_session()?;
+        let sel_mgr = self.selection_managers.get(&session.id)?;
+        sel_mgr.get_text(session.emulator.buffer(), self.app_config.copy_preserve_trailing_spaces)
+    }
+
     fn copy_selection(&mut self) {
+        if let Some(text) = self.selected_text() {
+            self.clipboard.set_text(&text);
+        }
+    }
+
+    /// Enter or leave keyboard copy mode for the active session
+    fn toggle_copy_mode(&mut self) {
         let Some(session) = self.session_manager.active_session() else {
             return;
         };
-        let session_id = session.id;
-        let Some(sel_mgr) = self.selection_managers.get(&session_id) else {
+        let sel_mgr = self.selection_managers.entry(session.id).or_default();
+        if sel_mgr.copy_cursor().is_some() {
+            sel_mgr.exit_copy_mode();
+        } else {
+            sel_mgr.enter_copy_mode(session.emulator.buffer());
+            self.reveal_copy_cursor();
+        }
+    }
+
+    /// Copy mode keys: hjkl/arrows, w/b and Home/End move, v starts or drops the
+    /// selection, y/Enter copy it and q/Esc leave
+    fn handle_copy_mode_key(&mut self, key: egui::Key) {
+        let movement = match key {
+            egui::Key::H | egui::Key::ArrowLeft => Some(SelectionMove::Left),
+            egui::Key::L | egui::Key::ArrowRight => Some(SelectionMove::Right),
+            egui::Key::K | egui::Key::ArrowUp => Some(SelectionMove::Up),
+            egui::Key::J | egui::Key::ArrowDown => Some(SelectionMove::Down),
+            egui::Key::B => Some(SelectionMove::WordLeft),
+            egui::Key::W => Some(SelectionMove::WordRight),
+            egui::Key::Home => Some(SelectionMove::LineStart),
+            egui::Key::End => Some(SelectionMove::LineEnd),
+            _ => None,
+        };
+        let Some(session) = self.session_manager.active_session() else {
             return;
         };
-        if let Some(text) = sel_mgr.get_text(session.emulator.buffer()) {
-            if let Some(clipboard) = &mut self.clipboard {
-                let _ = clipboard.set_text(&text);
+        let session_id = session.id;
+        let sel_mgr = self.selection_managers.entry(session_id).or_default();
+        if let Some(movement) = movement {
+            sel_mgr.move_copy_cursor(movement, session.emulator.buffer());
+            self.reveal_copy_cursor();
+            return;
+        }
+        match key {
+            egui::Key::V => sel_mgr.toggle_copy_selection(),
+            egui::Key::Y | egui::Key::Enter => {
+                self.copy_selection();
+                if let Some(sel_mgr) = self.selection_managers.get_mut(&session_id) {
+                    sel_mgr.exit_copy_mode();
+                }
             }
+            egui::Key::Q | egui::Key::Escape => sel_mgr.exit_copy_mode(),
+            _ => {}
         }
     }
 
-    fn paste(&mut self) {
-        let Some(clipboard) = &mut self.clipboard else {
+    /// Scroll just enough to keep the copy mode cursor on screen
+    fn reveal_copy_cursor(&mut self) {
+        let Some(session) = self.session_manager.active_session_mut() else {
+            return;
+        };
+        let Some(cursor) = self.selection_managers.get(&session.id).and_then(|m| m.copy_cursor()) else {
+            return;
+        };
+        let buffer = session.emulator.buffer();
+        let rows = buffer.rows().max(1);
+        let max_scroll = buffer.total_lines().saturating_sub(rows);
+        let mut offset = session.scroll_offset().min(max_scroll);
+        if cursor.line < offset {
+            offset = cursor.line;
+        } else if cursor.line >= offset + rows {
+            offset = cursor.line + 1 - rows;
+        }
+        session.set_scroll_offset_with_bottom(offset, offset >= max_scroll);
+    }
+
+    /// Scroll so the previous or next known prompt is at the top of the view
+    fn jump_to_prompt(&mut self, previous: bool) {
+        let Some(session) = self.session_manager.active_session_mut() else {
+            return;
+        };
+        let buffer = session.emulator.buffer();
+        let max_scroll = buffer.total_lines().saturating_sub(buffer.rows());
+        let offset = session.scroll_offset().min(max_scroll);
+        let target = if previous {
+            buffer.prompt_lines().filter(|&line| line < offset).last()
+        } else {
+            buffer.prompt_lines().find(|&line| line > offset)
+        };
+        if let Some(line) = target {
+            let offset = line.min(max_scroll);
+            session.set_scroll_offset_with_bottom(offset, offset >= max_scroll);
+        }
+    }
+
+    /// Copy the output of the last command marked with OSC 133 C/D,
+    /// or found between prompts matched by the session's prompt pattern
+    fn copy_last_command_output(&mut self) {
+        let Some(session) = self.session_manager.active_session() else {
+            return;
+        };
+        let buffer = session.emulator.buffer();
+        let Some((start_row, start_col, end_row, end_col)) = buffer.last_command_output() else {
+            debug::log("No command output marks (OSC 133 or prompt pattern) to copy");
+            return;
+        };
+        let text = buffer.get_text_range(start_row, start_col, end_row, end_col, self.app_config.copy_preserve_trailing_spaces);
+        self.clipboard.set_text(&text);
+    }
+
+    fn connect_to_selection(&mut self) {
+        let Some(text) = self.selected_text() else {
             return;
         };
-        let Ok(mut text) = clipboard.get_text() else {
+        if let Some(session) = self.session_manager.active_session() {
+            if let Some(sel_mgr) = self.selection_managers.get_mut(&session.id) {
+                sel_mgr.clear();
+            }
+        }
+        if let Some(spec) = HostSpec::parse(&text) {
+            self.config_dialog.open_quick_connect_to(spec);
+        }
+    }
+
+    fn paste(&mut self) {
+        self.paste_clipboard(false);
+    }
+
+    /// Paste with escape sequences and control codes (other than newline/tab) removed
+    fn paste_without_formatting(&mut self) {
+        self.paste_clipboard(true);
+    }
+
+    fn paste_clipboard(&mut self, strip_formatting: bool) {
+        let Some(mut text) = self.clipboard.get_text() else {
             return;
         };
         let Some(session) = self.session_manager.active_session() else {
@@ -438,6 +1031,24 @@ impl YasshApp {
         // Normalize clipboard text to \n first, then session.send() will convert to configured format
         // This prevents double conversion: clipboard \r\n -> normalize to \n -> convert to configured format
         text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if strip_formatting {
+            text = strip_terminal_controls(&text);
+        }
+        if let Some(spaces) = self.app_config.paste_tab_spaces {
+            text = text.replace('\t', &" ".repeat(spaces));
+        }
+        // Ask before sending text that would execute more than one line
+        if self.app_config.confirm_multiline_paste && text.contains('\n') {
+            self.confirm_paste = Some((session_id, text));
+            return;
+        }
+        self.send_paste(session_id, text);
+    }
+
+    fn send_paste(&mut self, session_id: Uuid, text: String) {
+        let Some(session) = self.session_manager.get_session(session_id) else {
+            return;
+        };
         let bracketed = session.emulator.bracketed_paste();
         let data = if bracketed {
             format!("\x1b[200~{}\x1b[201~", text)
@@ -452,17 +1063,20 @@ impl YasshApp {
     }
 
     fn handle_bell(&mut self, notification: BellNotification) {
+        if notification == BellNotification::None {
+            return;
+        }
+        // Rate limit so a process spewing BEL doesn't queue a beep per bell
+        let min_interval = std::time::Duration::from_millis(self.app_config.bell_min_interval_ms.max(MIN_BELL_MIN_INTERVAL_MS));
+        let now = std::time::Instant::now();
+        if self.last_bell.is_some_and(|last| now.duration_since(last) < min_interval) {
+            return;
+        }
+        self.last_bell = Some(now);
         match notification {
             BellNotification::Sound => {
-                #[cfg(windows)]
-                {
-                    #[link(name = "user32")]
-                    extern "system" {
-                        fn MessageBeep(uType: u32) -> i32;
-                    }
-                    // Use Windows standard notification sound
-                    unsafe { MessageBeep(MB_ICONASTERISK) };
-                }
+                let sound_file = self.app_config.bell_sound_file.trim();
+                bell::play((!sound_file.is_empty()).then(|| std::path::Path::new(sound_file)));
             }
             BellNotification::BlinkScreen | BellNotification::BlinkLine => {
                 // Start a blink timer for visual feedback
@@ -478,21 +1092,31 @@ impl YasshApp {
             egui::MenuBar::new().ui(ui, |ui| {
                 // File menu
                 ui.menu_button("File", |ui| {
-                    if ui.button("New Session...").clicked() {
+                    if ui.button("New Session... (Ctrl+N)").clicked() {
                         self.config_dialog.open_new();
                         ui.close();
                     }
-                    if ui.button("Quick Connect...").clicked() {
+                    if ui.button("Quick Connect... (Ctrl+Shift+T)").clicked() {
                         self.config_dialog.open_quick_connect();
                         ui.close();
                     }
+                    if ui.button("New Local Shell").clicked() {
+                        self.open_local_shell();
+                        ui.close();
+                    }
+                    ui.menu_button("Import", |ui| {
+                        if ui.button("From SSH Config (~/.ssh/config)").clicked() {
+                            self.import_from(ImportSource::SshConfig);
+                            ui.close();
+                        }
+                        if ui.button("From PuTTY").clicked() {
+                            self.import_from(ImportSource::Putty);
+                            ui.close();
+                        }
+                    });
                     ui.separator();
-                    if ui.button("New Folder").clicked() {
-                        let folder = SessionFolder::new(String::from("New Folder"));
-                        let folder_id = folder.id;
-                        self.persistence.add_folder(folder);
-                        let _ = self.persistence.save();
-                        self.folder_rename_dialog = Some((folder_id, String::from("New Folder")));
+                    if ui.button("New Folder (Ctrl+Shift+N)").clicked() {
+                        self.create_folder();
                         ui.close();
                     }
                     ui.separator();
@@ -516,12 +1140,54 @@ impl YasshApp {
                         self.paste();
                         ui.close();
                     }
+                    if ui.add_enabled(has_active, egui::Button::new("Paste Without Formatting (Ctrl+Shift+V)")).clicked() {
+                        self.paste_without_formatting();
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.add_enabled(has_active, egui::Button::new("Find... (Ctrl+Shift+F)")).clicked() {
+                        self.open_search();
+                        ui.close();
+                    }
+                    if ui.add_enabled(has_active, egui::Button::new("Copy Last Command Output (Ctrl+Shift+O)")).clicked() {
+                        self.copy_last_command_output();
+                        ui.close();
+                    }
+                    if ui.add_enabled(has_active, egui::Button::new("Jump to Previous Prompt")).clicked() {
+                        self.jump_to_prompt(true);
+                        ui.close();
+                    }
+                    if ui.add_enabled(has_active, egui::Button::new("Jump to Next Prompt")).clicked() {
+                        self.jump_to_prompt(false);
+                        ui.close();
+                    }
+                    if ui.add_enabled(has_active, egui::Button::new("Copy Mode (Ctrl+Shift+Space)")).clicked() {
+                        self.toggle_copy_mode();
+                        ui.close();
+                    }
                 });
                 // View menu
                 ui.menu_button("View", |ui| {
-                    let sidebar_text = if self.sidebar_visible { "Hide Sidebar" } else { "Show Sidebar" };
+                    let sidebar_text = if self.app_config.sidebar_visible { "Hide Sidebar (Ctrl+Shift+B)" } else { "Show Sidebar (Ctrl+Shift+B)" };
                     if ui.button(sidebar_text).clicked() {
-                        self.sidebar_visible = !self.sidebar_visible;
+                        self.toggle_sidebar();
+                        ui.close();
+                    }
+                    let scroll_locked = self.session_manager.active_session().is_some_and(|s| s.is_scroll_locked());
+                    let has_active = self.session_manager.active_session().is_some();
+                    let scroll_lock_text = if scroll_locked { "Release Scroll Lock (Ctrl+Shift+S)" } else { "Scroll Lock (Ctrl+Shift+S)" };
+                    if ui.add_enabled(has_active, egui::Button::new(scroll_lock_text)).clicked() {
+                        self.toggle_scroll_lock();
+                        ui.close();
+                    }
+                    let mut always_on_top = self.app_config.always_on_top;
+                    if ui.checkbox(&mut always_on_top, "Always on Top (Ctrl+Shift+P)").clicked() {
+                        self.toggle_always_on_top(ctx);
+                        ui.close();
+                    }
+                    let fullscreen_text = if self.app_config.fullscreen { "Exit Fullscreen (F11)" } else { "Fullscreen (F11)" };
+                    if ui.button(fullscreen_text).clicked() {
+                        self.toggle_fullscreen(ctx);
                         ui.close();
                     }
                 });
@@ -535,6 +1201,24 @@ impl YasshApp {
                         }
                         ui.close();
                     }
+                    if ui.add_enabled(has_active, egui::Button::new("Reconnect As...")).clicked() {
+                        if let Some(id) = self.session_manager.active_session().map(|s| s.id) {
+                            self.open_reconnect_as(id);
+                        }
+                        ui.close();
+                    }
+                    let disconnected = self.session_manager.disconnected_count();
+                    if ui.add_enabled(disconnected > 0, egui::Button::new(format!("Reconnect All Disconnected ({})", disconnected))).clicked() {
+                        self.session_manager.reconnect_disconnected();
+                        ui.close();
+                    }
+                    let is_connected = self.session_manager.active_session().is_some_and(|s| s.is_connected());
+                    if ui.add_enabled(is_connected, egui::Button::new("New Shell on This Connection")).clicked() {
+                        if let Some(id) = self.session_manager.active_session().map(|s| s.id) {
+                            self.open_new_shell(id);
+                        }
+                        ui.close();
+                    }
                     if ui.add_enabled(has_active, egui::Button::new("Disconnect")).clicked() {
                         if let Some(session) = self.session_manager.active_session_mut() {
                             session.disconnect();
@@ -542,15 +1226,65 @@ impl YasshApp {
                         ui.close();
                     }
                     ui.separator();
-                    if ui.add_enabled(has_active, egui::Button::new("Edit Connection Settings...")).clicked() {
+                    // Control codes written straight to the server
+                    for (label, byte) in [("Send Ctrl+C", 0x03u8), ("Send Ctrl+D", 0x04), ("Send Ctrl+Z", 0x1A)] {
+                        if ui.add_enabled(has_active, egui::Button::new(label)).clicked() {
+                            if let Some(session) = self.session_manager.active_session() {
+                                session.send(&[byte]);
+                            }
+                            ui.close();
+                        }
+                    }
+                    ui.add_enabled_ui(has_active, |ui| {
+                        ui.menu_button("Send Signal", |ui| {
+                            for (name, _) in SIGNALS {
+                                if ui.button(format!("SIG{}", name)).clicked() {
+                                    if let Some(session) = self.session_manager.active_session() {
+                                        session.send_signal(name);
+                                    }
+                                    ui.close();
+                                }
+                            }
+                            ui.separator();
+                            ui.label(egui::RichText::new("Servers that refuse SSH signals get INT, QUIT\nand TSTP as Ctrl+C, Ctrl+\\ and Ctrl+Z").weak());
+                        });
+                    });
+                    let break_bytes = self.session_manager.active_session()
+                        .map(|s| s.config.break_bytes())
+                        .unwrap_or_default();
+                    if ui.add_enabled(!break_bytes.is_empty(), egui::Button::new("Send Break"))
+                        .on_disabled_hover_text("Set a break sequence in the connection settings")
+                        .clicked()
+                    {
                         if let Some(session) = self.session_manager.active_session() {
-                            // Edit the connection's runtime settings, not the stored session
-                            let id = session.id;
-                            self.config_dialog.open_edit_connection(id, session.config.clone());
+                            session.send(&break_bytes);
                         }
                         ui.close();
                     }
                     ui.separator();
+                    if ui.add_enabled(has_active, egui::Button::new("Edit Connection Settings... (Ctrl+,)")).clicked() {
+                        self.edit_active_connection();
+                        ui.close();
+                    }
+                    let has_saved = self.session_manager.active_session()
+                        .is_some_and(|s| self.persistence.get_session(s.config.id).is_some());
+                    if ui.add_enabled(has_active && !has_saved, egui::Button::new("Save This Session..."))
+                        .on_disabled_hover_text("Only for tabs without a saved session, such as Quick Connect")
+                        .clicked()
+                    {
+                        if let Some(session) = self.session_manager.active_session() {
+                            self.config_dialog.open_save_connection(session.id, session.config.clone());
+                        }
+                        ui.close();
+                    }
+                    if ui.add_enabled(has_saved, egui::Button::new("Reset to Saved Settings"))
+                        .on_hover_text("Undo zoom and connection setting changes made since this tab was opened")
+                        .clicked()
+                    {
+                        self.reset_active_to_saved();
+                        ui.close();
+                    }
+                    ui.separator();
                     if ui.add_enabled(has_active, egui::Button::new("Close Tab")).clicked() {
                         self.close_active_session();
                         ui.close();
@@ -558,13 +1292,153 @@ impl YasshApp {
                 });
                 // Help menu
                 ui.menu_button("Help", |ui| {
+                    if ui.button("Terminal Diagnostics").clicked() {
+                        self.show_diagnostics = true;
+                        ui.close();
+                    }
+                    ui.separator();
                     if ui.button("About Yassh").clicked() {
                         self.show_about_dialog = true;
                         ui.close();
                     }
-                });
+                });
+            });
+        });
+    }
+
+    /// Unsaved tab running the default shell on this machine
+    fn open_local_shell(&mut self) {
+        let config = SessionConfig {
+            name: String::from("Local Shell"),
+            kind: SessionKind::LocalShell,
+            ..self.app_config.terminal_defaults.session_config()
+        };
+        let session_id = self.session_manager.add_session(config);
+        self.session_manager.connect_session(session_id);
+        self.session_manager.set_active(session_id);
+    }
+
+    /// Add sessions saved by another client into a new folder, skipping hosts already saved
+    fn import_from(&mut self, source: ImportSource) {
+        let template = self.app_config.terminal_defaults.session_config();
+        let (imported, folder_name) = match source {
+            ImportSource::SshConfig => (import::ssh_config_sessions(&template), "Imported from SSH config"),
+            ImportSource::Putty => (import::putty_sessions(&template), "Imported from PuTTY"),
+        };
+        let imported = match imported {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.import_message = Some(format!("Import failed: {:#}", e));
+                return;
+            }
+        };
+        let new_sessions: Vec<SessionConfig> = imported.into_iter()
+            .filter(|s| !self.persistence.sessions().iter().any(|existing| {
+                existing.host == s.host && existing.port == s.port && existing.username == s.username
+            }))
+            .collect();
+        if new_sessions.is_empty() {
+            self.import_message = Some(String::from("No new sessions found to import"));
+            return;
+        }
+        let folder = SessionFolder::new(String::from(folder_name));
+        let folder_id = folder.id;
+        self.persistence.add_folder(folder);
+        let count = new_sessions.len();
+        for mut session in new_sessions {
+            session.folder_id = Some(folder_id);
+            self.persistence.add_session(session);
+        }
+        let _ = self.persistence.save();
+        self.import_message = Some(format!("Imported {} session(s) into \"{}\"", count, folder_name));
+    }
+
+    fn show_import_message(&mut self, ctx: &Context) {
+        let Some(message) = &self.import_message else {
+            return;
+        };
+        let mut close = false;
+        Self::draw_modal_overlay(ctx, "import_message_overlay");
+        egui::Window::new("Import Sessions")
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if ui.input(|i| i.key_pressed(egui::Key::Enter) || i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+                ui.label(message);
+                ui.add_space(8.0);
+                if ui.button("OK").clicked() {
+                    close = true;
+                }
+            });
+        if close {
+            self.import_message = None;
+        }
+    }
+
+    /// Save after a delete and offer to undo it; a newer delete replaces the older offer
+    fn offer_undo(&mut self, deleted: DeletedItems) {
+        let _ = self.persistence.save();
+        self.undo_delete = Some((std::time::Instant::now(), deleted));
+    }
+
+    /// Non-modal notices stacked in the corner; each fades out on its own or when clicked
+    fn show_toasts(&mut self, ctx: &Context) {
+        let duration = std::time::Duration::from_secs(TOAST_DURATION_SECS);
+        let undo_duration = std::time::Duration::from_secs(UNDO_DURATION_SECS);
+        self.toasts.retain(|(shown, _)| shown.elapsed() < duration);
+        if self.undo_delete.as_ref().is_some_and(|(shown, _)| shown.elapsed() >= undo_duration) {
+            self.undo_delete = None;
+        }
+        if self.toasts.is_empty() && self.undo_delete.is_none() {
+            return;
+        }
+        let mut dismissed = None;
+        let mut undo = false;
+        egui::Area::new(egui::Id::new("toasts"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-TOAST_MARGIN, -TOAST_MARGIN])
+            .interactable(true)
+            .show(ctx, |ui| {
+                if let Some((_, deleted)) = &self.undo_delete {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(deleted.description());
+                            undo = ui.button("Undo").clicked();
+                        });
+                    });
+                }
+                for (index, (_, notice)) in self.toasts.iter().enumerate() {
+                    let color = if notice.is_error { TOAST_ERROR_COLOR } else { TOAST_SUCCESS_COLOR };
+                    let response = egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(1.0, color))
+                        .show(ui, |ui| ui.label(&notice.text))
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.clicked() {
+                        dismissed = Some(index);
+                    }
+                }
             });
-        });
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+        if undo {
+            if let Some((_, deleted)) = self.undo_delete.take() {
+                self.persistence.restore(deleted);
+                let _ = self.persistence.save();
+            }
+        }
+        // Wake up to expire the oldest toast and the undo offer
+        if let Some((shown, _)) = self.toasts.first() {
+            ctx.request_repaint_after(duration.saturating_sub(shown.elapsed()));
+        }
+        if let Some((shown, _)) = &self.undo_delete {
+            ctx.request_repaint_after(undo_duration.saturating_sub(shown.elapsed()));
+        }
     }
 
     fn show_about_dialog(&mut self, ctx: &Context) {
@@ -597,11 +1471,199 @@ impl YasshApp {
             });
     }
 
+    fn edit_active_connection(&mut self) {
+        if let Some(session) = self.session_manager.active_session() {
+            // Edit the connection's runtime settings, not the stored session
+            let id = session.id;
+            self.config_dialog.open_edit_connection(id, session.config.clone());
+        }
+    }
+
+    /// Re-apply the stored session's settings to the active connection without reconnecting
+    fn reset_active_to_saved(&mut self) {
+        let Some(session) = self.session_manager.active_session() else {
+            return;
+        };
+        let Some(saved) = self.persistence.get_session(session.config.id).cloned() else {
+            return;
+        };
+        let id = session.id;
+        if let Some(session) = self.session_manager.get_session_mut(id) {
+            // Keep where and as whom this tab connects; Reconnect As may have changed those
+            let current = &session.config;
+            let config = SessionConfig {
+                host: current.host.clone(),
+                port: current.port,
+                username: current.username.clone(),
+                auth_method: current.auth_method.clone(),
+                password: current.password.clone(),
+                private_key_path: current.private_key_path.clone(),
+                fallback_password: current.fallback_password.clone(),
+                ..saved
+            };
+            session.update_config(config);
+        }
+    }
+
+    fn open_search(&mut self) {
+        let Some(session) = self.session_manager.active_session() else {
+            return;
+        };
+        if self.search.as_ref().map(|s| s.session_id) != Some(session.id) {
+            self.search = Some(SearchState::new(session.id));
+        }
+        self.search_request_focus = true;
+    }
+
+    /// Scroll the active terminal so the current match is in view
+    fn reveal_search_match(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let Some(session) = self.session_manager.active_session_mut() else {
+            return;
+        };
+        if let Some((line, _, _)) = search.current_match(session.emulator.buffer()) {
+            let rows = session.emulator.buffer().rows();
+            session.set_scroll_offset_with_bottom(line.saturating_sub(rows / 2), false);
+        }
+    }
+
+    fn show_search_bar(&mut self, ctx: &Context) {
+        let active_id = self.session_manager.active_session().map(|s| s.id);
+        if self.search.as_ref().is_some_and(|s| Some(s.session_id) != active_id) {
+            // The searched session was closed or another tab was selected
+            self.search = None;
+        }
+        let Some(search) = &mut self.search else {
+            self.search_has_focus = false;
+            return;
+        };
+        let mut close = false;
+        let mut navigate = None;
+        let mut query_changed = false;
+        egui::Window::new("Find")
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::RIGHT_TOP, [-SEARCH_BAR_MARGIN, SEARCH_BAR_TOP_OFFSET])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let response = ui.add(egui::TextEdit::singleline(&mut search.query)
+                        .hint_text("Find")
+                        .desired_width(SEARCH_FIELD_WIDTH));
+                    if std::mem::take(&mut self.search_request_focus) {
+                        response.request_focus();
+                    }
+                    query_changed = response.changed();
+                    self.search_has_focus = response.has_focus();
+                    let (enter, shift, escape) = ui.input(|i| {
+                        (i.key_pressed(egui::Key::Enter), i.modifiers.shift, i.key_pressed(egui::Key::Escape))
+                    });
+                    if response.lost_focus() && enter {
+                        // Enter re-runs the search (picking up new output) and moves on
+                        navigate = Some(!shift);
+                        response.request_focus();
+                    }
+                    if escape {
+                        close = true;
+                    }
+                    ui.label(search.status());
+                    if ui.small_button("▲").on_hover_text("Previous (Shift+Enter)").clicked() {
+                        navigate = Some(false);
+                    }
+                    if ui.small_button("▼").on_hover_text("Next (Enter)").clicked() {
+                        navigate = Some(true);
+                    }
+                    if ui.small_button("✕").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if close {
+            self.search = None;
+            self.search_has_focus = false;
+            return;
+        }
+        if let Some(session) = self.session_manager.active_session() {
+            let buffer = session.emulator.buffer();
+            if query_changed {
+                search.run(buffer);
+            } else if let Some(forward) = navigate {
+                search.run(buffer);
+                if forward {
+                    search.next(buffer);
+                } else {
+                    search.previous(buffer);
+                }
+            }
+        }
+        if query_changed || navigate.is_some() {
+            self.reveal_search_match();
+        }
+    }
+
+    fn show_diagnostics_window(&mut self, ctx: &Context) {
+        if !self.show_diagnostics {
+            return;
+        }
+        // Non-modal so the terminal keeps receiving input while the flags are watched
+        let mut open = true;
+        egui::Window::new("Terminal Diagnostics")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.log_key_input, "Log key input to the debug log");
+                ui.separator();
+                let Some(session) = self.session_manager.active_session() else {
+                    ui.label("No active session");
+                    return;
+                };
+                let emulator = &session.emulator;
+                let buffer = emulator.buffer();
+                let cursor = buffer.cursor();
+                let (scroll_top, scroll_bottom) = buffer.scroll_region();
+                let on_off = |value: bool| if value { "on" } else { "off" };
+                egui::Grid::new("diagnostics_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let rows = [
+                            ("Cursor keys application (DECCKM)", on_off(emulator.cursor_keys_application()).to_string()),
+                            ("Bracketed paste", on_off(emulator.bracketed_paste()).to_string()),
+                            ("Reverse video (DECSCNM)", on_off(emulator.reverse_video()).to_string()),
+                            ("Cursor visible (DECTCEM)", on_off(emulator.cursor_visible()).to_string()),
+                            ("Auto wrap (DECAWM)", on_off(emulator.auto_wrap()).to_string()),
+                            ("Origin mode (DECOM)", on_off(buffer.origin_mode()).to_string()),
+                            ("Insert mode (IRM)", on_off(emulator.insert_mode()).to_string()),
+                            ("Linefeed/newline (LNM)", on_off(emulator.linefeed_newline()).to_string()),
+                            ("Screen size", format!("{} x {}", buffer.cols(), buffer.rows())),
+                            ("Cursor", format!("row {}, col {}", cursor.row + 1, cursor.col + 1)),
+                            ("Scroll region", format!("{} - {}", scroll_top + 1, scroll_bottom + 1)),
+                            ("Scrollback lines", buffer.scrollback_len().to_string()),
+                        ];
+                        for (name, value) in rows {
+                            ui.label(name);
+                            ui.label(egui::RichText::new(value).monospace());
+                            ui.end_row();
+                        }
+                    });
+            });
+        if !open {
+            self.show_diagnostics = false;
+        }
+    }
+
     fn show_delete_confirmation_dialogs(&mut self, ctx: &Context) {
-        if let Some(id) = self.confirm_delete_session {
-            let session_name = self.persistence.get_session(id)
-                .map(|s| s.name.clone())
-                .unwrap_or_default();
+        if let Some(ids) = self.confirm_delete_session.clone() {
+            let prompt = match ids.as_slice() {
+                [id] => format!(
+                    "Delete session '{}'?",
+                    self.persistence.get_session(*id).map(|s| s.name.clone()).unwrap_or_default()
+                ),
+                _ => format!("Delete {} sessions?", ids.len()),
+            };
             // Modal overlay
             Self::draw_modal_overlay(ctx, "delete_session_overlay");
             egui::Window::new("Confirm Delete")
@@ -612,14 +1674,14 @@ impl YasshApp {
                 .show(ctx, |ui| {
                     // Handle Enter for confirm, Escape for cancel
                     if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        self.persistence.remove_session(id);
-                        let _ = self.persistence.save();
+                        let deleted = self.persistence.delete_sessions(&ids);
+                        self.offer_undo(deleted);
                         self.confirm_delete_session = None;
                     }
                     if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
                         self.confirm_delete_session = None;
                     }
-                    ui.label(format!("Delete session '{}'?", session_name));
+                    ui.label(prompt);
                     ui.horizontal(|ui| {
                         if ui.button("Cancel").clicked() {
                             self.confirm_delete_session = None;
@@ -627,8 +1689,8 @@ impl YasshApp {
                         let delete_btn = ui.button("Delete");
                         delete_btn.request_focus();
                         if delete_btn.clicked() {
-                            self.persistence.remove_session(id);
-                            let _ = self.persistence.save();
+                            let deleted = self.persistence.delete_sessions(&ids);
+                            self.offer_undo(deleted);
                             self.confirm_delete_session = None;
                         }
                     });
@@ -648,8 +1710,8 @@ impl YasshApp {
                 .show(ctx, |ui| {
                     // Handle Enter for confirm, Escape for cancel
                     if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        self.persistence.remove_folder(id);
-                        let _ = self.persistence.save();
+                        let deleted = self.persistence.delete_folder(id);
+                        self.offer_undo(deleted);
                         self.confirm_delete_folder = None;
                     }
                     if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
@@ -663,8 +1725,8 @@ impl YasshApp {
                         let delete_btn = ui.button("Delete");
                         delete_btn.request_focus();
                         if delete_btn.clicked() {
-                            self.persistence.remove_folder(id);
-                            let _ = self.persistence.save();
+                            let deleted = self.persistence.delete_folder(id);
+                            self.offer_undo(deleted);
                             self.confirm_delete_folder = None;
                         }
                     });
@@ -672,8 +1734,205 @@ impl YasshApp {
         }
     }
 
+    fn show_paste_confirmation_dialog(&mut self, ctx: &Context) {
+        let Some((session_id, text)) = &self.confirm_paste else {
+            return;
+        };
+        let session_id = *session_id;
+        let line_count = text.lines().count();
+        let preview: Vec<&str> = text.lines().take(PASTE_PREVIEW_LINES).collect();
+        let mut preview = preview.join("\n");
+        if line_count > PASTE_PREVIEW_LINES {
+            preview.push_str("\n...");
+        }
+        let mut confirm = false;
+        let mut close = false;
+        // Modal overlay
+        Self::draw_modal_overlay(ctx, "confirm_paste_overlay");
+        egui::Window::new("Confirm Paste")
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                // Handle Enter for confirm, Escape for cancel
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    confirm = true;
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+                let noun = if line_count == 1 { "line" } else { "lines" };
+                ui.label(format!("Paste {} {} (including newlines) into the terminal?", line_count, noun));
+                ui.add_space(4.0);
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(preview).monospace());
+                });
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                    let paste_btn = ui.button("Paste");
+                    paste_btn.request_focus();
+                    if paste_btn.clicked() {
+                        confirm = true;
+                    }
+                });
+            });
+        if confirm {
+            if let Some((_, text)) = self.confirm_paste.take() {
+                self.send_paste(session_id, text);
+            }
+        }
+        if close {
+            self.confirm_paste = None;
+        }
+    }
+
+    /// Ask what to do with files dropped onto the terminal: upload them or type their paths
+    fn show_dropped_files_dialog(&mut self, ctx: &Context) {
+        let dropped: Vec<std::path::PathBuf> = ctx.input(|i| {
+            i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect()
+        });
+        if !dropped.is_empty() && !self.any_dialog_visible() {
+            if let Some(session) = self.session_manager.active_session() {
+                self.dropped_files = Some((session.id, dropped));
+            }
+        }
+        let Some((session_id, files)) = &self.dropped_files else {
+            return;
+        };
+        let session_id = *session_id;
+        let connected = self.session_manager.get_session(session_id).is_some_and(|s| s.is_connected());
+        let mut upload = false;
+        let mut insert_paths = false;
+        let mut close = false;
+        Self::draw_modal_overlay(ctx, "dropped_files_overlay");
+        egui::Window::new("Dropped Files")
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+                for path in files.iter().take(PASTE_PREVIEW_LINES) {
+                    ui.label(egui::RichText::new(path.display().to_string()).monospace());
+                }
+                if files.len() > PASTE_PREVIEW_LINES {
+                    ui.label("...");
+                }
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                    if ui.add_enabled(connected, egui::Button::new("Upload via SFTP"))
+                        .on_hover_text("Into the shell's current directory if it reports one (OSC 7), else the home directory")
+                        .clicked()
+                    {
+                        upload = true;
+                    }
+                    if ui.button("Insert Path").clicked() {
+                        insert_paths = true;
+                    }
+                });
+            });
+        if upload || insert_paths {
+            if let Some((_, files)) = self.dropped_files.take() {
+                if upload {
+                    if let Some(session) = self.session_manager.get_session_mut(session_id) {
+                        session.upload_files(files);
+                    }
+                } else {
+                    let text = files.iter().map(|path| quote_path(path)).collect::<Vec<_>>().join(" ");
+                    self.send_paste(session_id, text);
+                }
+            }
+        }
+        if close {
+            self.dropped_files = None;
+        }
+    }
+
+    /// Ask for the answers to a server's keyboard-interactive prompts (password, one-time code)
+    fn show_auth_prompt_dialog(&mut self, ctx: &Context) {
+        if self.auth_prompt.is_none() {
+            self.auth_prompt = self.session_manager.auth_prompt_session().map(|id| (id, Vec::new()));
+        }
+        let Some((session_id, answers)) = &mut self.auth_prompt else {
+            return;
+        };
+        let session_id = *session_id;
+        // The login may have failed or been replaced while the dialog was open
+        let Some(session) = self.session_manager.get_session(session_id) else {
+            self.auth_prompt = None;
+            return;
+        };
+        let Some((instructions, prompts)) = session.auth_prompt() else {
+            self.auth_prompt = None;
+            return;
+        };
+        answers.resize(prompts.len(), String::new());
+        let title = format!("{}@{}", session.config.username, session.config.host);
+        let mut confirm = false;
+        let mut cancel = false;
+        Self::draw_modal_overlay(ctx, "auth_prompt_overlay");
+        egui::Window::new("Authentication")
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    confirm = true;
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    cancel = true;
+                }
+                ui.label(egui::RichText::new(title).strong());
+                if !instructions.trim().is_empty() {
+                    ui.label(instructions.trim());
+                }
+                ui.add_space(4.0);
+                egui::Grid::new("auth_prompt_grid").num_columns(2).show(ui, |ui| {
+                    for (i, ((prompt, echo), answer)) in prompts.iter().zip(answers.iter_mut()).enumerate() {
+                        ui.label(prompt.trim());
+                        let field = ui.add(egui::TextEdit::singleline(answer).password(!echo).desired_width(200.0));
+                        if i == 0 && ui.memory(|m| m.focused().is_none()) {
+                            field.request_focus();
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                    if ui.button("OK").clicked() {
+                        confirm = true;
+                    }
+                });
+            });
+        if confirm {
+            if let Some((_, answers)) = self.auth_prompt.take() {
+                if let Some(session) = self.session_manager.get_session_mut(session_id) {
+                    session.answer_auth_prompt(answers);
+                }
+            }
+        } else if cancel {
+            self.auth_prompt = None;
+            if let Some(session) = self.session_manager.get_session_mut(session_id) {
+                session.cancel_auth_prompt();
+            }
+        }
+    }
+
     fn show_folder_rename_dialog(&mut self, ctx: &Context) {
-        if let Some((id, ref mut name)) = &mut self.folder_rename_dialog {
+        if let Some((id, ref mut name, ref mut color)) = &mut self.folder_rename_dialog {
             let id = *id;
             let mut close = false;
             let mut confirm = false;
@@ -696,6 +1955,15 @@ impl YasshApp {
                         ui.label("Name:");
                         ui.text_edit_singleline(name);
                     });
+                    ui.horizontal(|ui| {
+                        let mut use_color = color.is_some();
+                        if ui.checkbox(&mut use_color, "Tab color:").changed() {
+                            *color = use_color.then_some(DEFAULT_FOLDER_COLOR);
+                        }
+                        if let Some(rgb) = color {
+                            ui.color_edit_button_srgb(rgb);
+                        }
+                    });
                     ui.horizontal(|ui| {
                         if ui.button("Cancel").clicked() {
                             close = true;
@@ -708,6 +1976,7 @@ impl YasshApp {
             if confirm {
                 if let Some(folder) = self.persistence.folders.iter_mut().find(|f| f.id == id) {
                     folder.name = name.clone();
+                    folder.color = color.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b).into());
                 }
                 let _ = self.persistence.save();
                 close = true;
@@ -724,15 +1993,33 @@ impl YasshApp {
             || self.folder_rename_dialog.is_some()
             || self.confirm_delete_session.is_some()
             || self.confirm_delete_folder.is_some()
+            || self.confirm_paste.is_some()
+            || self.dropped_files.is_some()
+            || self.auth_prompt.is_some()
+            || self.import_message.is_some()
             || self.show_about_dialog
     }
 
     fn process_keyboard_input(&mut self, ctx: &Context) {
-        // Don't process keyboard when any dialog is visible
-        if self.any_dialog_visible() {
+        // Don't process keyboard when any dialog is visible or the find field is being typed in
+        if self.any_dialog_visible() || self.search_has_focus {
             return;
         }
         let has_active_session = self.session_manager.active_session().is_some();
+        let keyboard_selection = self.session_manager.active_session()
+            .and_then(|s| self.selection_managers.get(&s.id))
+            .is_some_and(|m| m.is_keyboard_selection());
+        let copy_mode = self.session_manager.active_session()
+            .and_then(|s| self.selection_managers.get(&s.id))
+            .is_some_and(|m| m.copy_cursor().is_some());
+        // Shift+navigation keys go to the program when it runs full-screen or tracks the mouse,
+        // unless a selection is already being made
+        let shift_keys_select = self.session_manager.active_session().is_some_and(|s| {
+            keyboard_selection
+                || copy_mode
+                || self.selection_managers.get(&s.id).is_some_and(|m| m.has_selection())
+                || (s.emulator.mouse_tracking() == MouseTracking::Off && !s.emulator.alternate_screen())
+        });
         // Collect text and key events to forward to terminal
         // Use Text events for character input (respects keyboard layout)
         // Use Key events for special keys (arrows, function keys, etc.)
@@ -740,6 +2027,21 @@ impl YasshApp {
         let mut key_events: Vec<(egui::Key, egui::Modifiers)> = Vec::new();
         let mut send_ctrl_c = false;
         let mut send_ctrl_x = false;
+        let mut toggle_fullscreen = false;
+        let mut toggle_always_on_top = false;
+        let mut toggle_sidebar = false;
+        let mut toggle_scroll_lock = false;
+        let mut copy_command_output = false;
+        let mut open_search = false;
+        let mut edit_connection = false;
+        let mut new_session = false;
+        let mut new_folder = false;
+        let mut quick_connect = false;
+        let mut paste_plain = false;
+        let mut selection_moves: Vec<SelectionMove> = Vec::new();
+        let mut copy_keyboard_selection = false;
+        let mut toggle_copy_mode = false;
+        let mut copy_mode_keys: Vec<egui::Key> = Vec::new();
         let mut app_shortcuts: Vec<(egui::Key, egui::Modifiers)> = Vec::new();
         ctx.input_mut(|i| {
             // Note: Tab events are now intercepted early in update() before UI processing
@@ -753,7 +2055,83 @@ impl YasshApp {
                         if *key == egui::Key::Tab {
                             return false; // Should already be intercepted, but be safe
                         }
+                        // F11 toggles fullscreen whether or not a session is open
+                        if *key == egui::Key::F11 && modifiers.is_none() {
+                            toggle_fullscreen = true;
+                            return false;
+                        }
+                        // Ctrl+Shift+B: plain Ctrl+B is the tmux prefix and F9 belongs to mc and htop
+                        if *key == egui::Key::B && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                            toggle_sidebar = true;
+                            return false;
+                        }
+                        if *key == egui::Key::P && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                            toggle_always_on_top = true;
+                            return false;
+                        }
+                        // Like Ctrl+W, Ctrl+N is taken from the terminal for the app
+                        if *key == egui::Key::N && modifiers.ctrl && !modifiers.alt {
+                            if modifiers.shift {
+                                new_folder = true;
+                            } else {
+                                new_session = true;
+                            }
+                            return false;
+                        }
+                        if *key == egui::Key::T && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                            quick_connect = true;
+                            return false;
+                        }
                         if has_active_session {
+                            if *key == egui::Key::Space && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                                toggle_copy_mode = true;
+                                return false;
+                            }
+                            // Copy mode takes every key until it is left
+                            if copy_mode {
+                                copy_mode_keys.push(*key);
+                                return false;
+                            }
+                            // egui does not report the Scroll Lock key, so it gets a chord instead
+                            if *key == egui::Key::S && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                                toggle_scroll_lock = true;
+                                return false;
+                            }
+                            if *key == egui::Key::O && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                                copy_command_output = true;
+                                return false;
+                            }
+                            if *key == egui::Key::F && modifiers.ctrl && modifiers.shift && !modifiers.alt {
+                                open_search = true;
+                                return false;
+                            }
+                            if *key == egui::Key::Comma && modifiers.ctrl && !modifiers.shift && !modifiers.alt {
+                                edit_connection = true;
+                                return false;
+                            }
+                            // Shift+navigation keys select text instead of being forwarded
+                            if shift_keys_select && modifiers.shift && !modifiers.alt {
+                                let movement = match key {
+                                    egui::Key::ArrowLeft if modifiers.ctrl => Some(SelectionMove::WordLeft),
+                                    egui::Key::ArrowRight if modifiers.ctrl => Some(SelectionMove::WordRight),
+                                    egui::Key::ArrowLeft => Some(SelectionMove::Left),
+                                    egui::Key::ArrowRight => Some(SelectionMove::Right),
+                                    egui::Key::ArrowUp => Some(SelectionMove::Up),
+                                    egui::Key::ArrowDown => Some(SelectionMove::Down),
+                                    egui::Key::Home if !modifiers.ctrl => Some(SelectionMove::LineStart),
+                                    egui::Key::End if !modifiers.ctrl => Some(SelectionMove::LineEnd),
+                                    _ => None,
+                                };
+                                if let Some(movement) = movement {
+                                    selection_moves.push(movement);
+                                    return false;
+                                }
+                            }
+                            // Enter copies a keyboard selection instead of being sent
+                            if keyboard_selection && *key == egui::Key::Enter && modifiers.is_none() {
+                                copy_keyboard_selection = true;
+                                return false;
+                            }
                             // Check for app shortcuts - these should NOT be forwarded to server
                             // Handle them exactly like Ctrl+W
                             if (modifiers.ctrl && !modifiers.shift && !modifiers.alt && *key == egui::Key::W) ||
@@ -784,6 +2162,10 @@ impl YasshApp {
                         true
                     }
                     // Handle Copy/Paste events - egui converts some key combinations to these
+                    egui::Event::Copy | egui::Event::Cut | egui::Event::Paste(_) | egui::Event::Text(_) if copy_mode => {
+                        // Nothing reaches the server while in copy mode
+                        !has_active_session
+                    }
                     egui::Event::Copy => {
                         if has_active_session {
                             let current_modifiers = i.modifiers;
@@ -792,6 +2174,11 @@ impl YasshApp {
                                 app_shortcuts.push((egui::Key::C, current_modifiers));
                                 return false; // Consume, don't forward
                             }
+                            // Ctrl+C copies a keyboard selection
+                            if keyboard_selection {
+                                copy_keyboard_selection = true;
+                                return false;
+                            }
                             // Regular Ctrl+C - forward to terminal
                             // Note: Ctrl+Insert is handled in Key events, not here
                             send_ctrl_c = true;
@@ -814,6 +2201,11 @@ impl YasshApp {
                                 app_shortcuts.push((egui::Key::V, current_modifiers));
                                 return false; // Consume, don't forward
                             }
+                            // Ctrl+Shift+V pastes without formatting
+                            if current_modifiers.shift && current_modifiers.ctrl {
+                                paste_plain = true;
+                                return false;
+                            }
                             // Shift+Insert (app shortcut) - Shift without Ctrl indicates Shift+Insert
                             if current_modifiers.shift && !current_modifiers.ctrl {
                                 app_shortcuts.push((egui::Key::Insert, current_modifiers));
@@ -844,6 +2236,62 @@ impl YasshApp {
             });
         });
         
+        if toggle_fullscreen {
+            self.toggle_fullscreen(ctx);
+        }
+        if toggle_always_on_top {
+            self.toggle_always_on_top(ctx);
+        }
+        if toggle_sidebar {
+            self.toggle_sidebar();
+        }
+        if toggle_scroll_lock {
+            self.toggle_scroll_lock();
+        }
+        if copy_command_output {
+            self.copy_last_command_output();
+        }
+        if open_search {
+            self.open_search();
+        }
+        if edit_connection {
+            self.edit_active_connection();
+        }
+        if new_session {
+            self.config_dialog.open_new();
+        }
+        if new_folder {
+            self.create_folder();
+        }
+        if quick_connect {
+            self.config_dialog.open_quick_connect();
+        }
+        if paste_plain {
+            self.paste_without_formatting();
+        }
+        if toggle_copy_mode {
+            self.toggle_copy_mode();
+        }
+        for key in copy_mode_keys {
+            self.handle_copy_mode_key(key);
+        }
+        // Keyboard selection (not forwarded to server)
+        if !selection_moves.is_empty() {
+            if let Some(session) = self.session_manager.active_session() {
+                let sel_mgr = self.selection_managers.entry(session.id).or_default();
+                for movement in selection_moves {
+                    sel_mgr.extend_with_key(movement, session.emulator.buffer());
+                }
+            }
+        }
+        if copy_keyboard_selection {
+            self.copy_selection();
+            if let Some(session) = self.session_manager.active_session() {
+                if let Some(sel_mgr) = self.selection_managers.get_mut(&session.id) {
+                    sel_mgr.clear();
+                }
+            }
+        }
         // Handle app shortcuts (these are NOT forwarded to server)
         for (key, modifiers) in app_shortcuts {
             self.handle_app_shortcut(key, modifiers);
@@ -892,8 +2340,16 @@ impl YasshApp {
         for (key, modifiers) in key_events {
             // Forward to terminal
             if let Some(session) = self.session_manager.active_session_mut() {
+                if session.config.block_ctrl_s && key == egui::Key::S && modifiers.ctrl && !modifiers.alt && !modifiers.shift {
+                    continue;
+                }
                 let backspace_seq = session.backspace_sequence().to_vec();
-                if let InputResult::Forward(data) = self.input_handler.handle_key(key, modifiers, &backspace_seq) {
+                let delete_seq = session.delete_sequence().to_vec();
+                let result = self.input_handler.handle_key(key, modifiers, &backspace_seq, &delete_seq);
+                if self.log_key_input {
+                    debug::log(&format!("[KEY {}] {}", session.config.name, describe_key_input(key, modifiers, &result)));
+                }
+                if let InputResult::Forward(data) = result {
                     session.send(&data);
                     if session.config.reset_scroll_on_input {
                         session.reset_scroll_to_bottom();
@@ -936,7 +2392,10 @@ impl eframe::App for YasshApp {
             for (modifiers, key) in queue.drain(..) {
                 // Handle Ctrl+Tab combinations for terminal switching (don't forward to server)
                 if modifiers.ctrl && !modifiers.alt {
-                    if modifiers.shift {
+                    if self.app_config.mru_tab_switching {
+                        // Cycles while Ctrl is held; releasing it commits the choice
+                        self.session_manager.cycle_mru(!modifiers.shift);
+                    } else if modifiers.shift {
                         // Ctrl+Shift+Tab: Previous tab
                         self.session_manager.prev_tab();
                     } else {
@@ -949,7 +2408,12 @@ impl eframe::App for YasshApp {
                 // Forward plain Tab and Shift+Tab to terminal
                 if let Some(session) = self.session_manager.active_session_mut() {
                     let backspace_seq = session.backspace_sequence().to_vec();
-                    if let InputResult::Forward(data) = self.input_handler.handle_key(key, modifiers, &backspace_seq) {
+                    let delete_seq = session.delete_sequence().to_vec();
+                    let result = self.input_handler.handle_key(key, modifiers, &backspace_seq, &delete_seq);
+                    if self.log_key_input {
+                        debug::log(&format!("[KEY {}] {}", session.config.name, describe_key_input(key, modifiers, &result)));
+                    }
+                    if let InputResult::Forward(data) = result {
                         session.send(&data);
                         if session.config.reset_scroll_on_input {
                             session.reset_scroll_to_bottom();
@@ -963,6 +2427,10 @@ impl eframe::App for YasshApp {
             }
         }
 
+        if !ctx.input(|i| i.modifiers.ctrl) {
+            self.session_manager.commit_mru_cycle();
+        }
+
         // Process keyboard input FIRST before any UI to prevent egui from consuming events
         // This MUST be called before ANY UI widgets are shown
         self.process_keyboard_input(ctx);
@@ -976,7 +2444,7 @@ impl eframe::App for YasshApp {
         if let Some(session) = self.session_manager.active_session() {
             if session.config.font != self.current_font {
                 self.current_font = session.config.font.clone();
-                setup_terminal_font(ctx, &self.current_font);
+                self.styled_faces = setup_terminal_font(ctx, &self.current_font, &self.app_config.fallback_font);
             }
         }
         // Update all sessions - request repaint only when data is actually received
@@ -1028,27 +2496,49 @@ impl eframe::App for YasshApp {
         for session_id in sessions_to_close {
             self.close_session_by_id(session_id);
         }
+        let now = std::time::Instant::now();
+        self.toasts.extend(self.session_manager.collect_notices().into_iter().map(|notice| (now, notice)));
+        if let Some(text) = self.session_manager.take_clipboard_write() {
+            self.clipboard.set_text(&text);
+        }
+        if self.clipboard.take_unavailable_warning() {
+            self.toasts.push((now, Notice {
+                text: String::from("System clipboard unavailable; copy and paste only work within Yassh"),
+                is_error: true,
+            }));
+        }
         // Handle bells
         let bells = self.session_manager.collect_pending_bells();
         for bell in bells {
             self.handle_bell(bell);
         }
+        // Handle server-initiated resize (CSI 8 ; rows ; cols t)
+        if let Some((rows, cols)) = self.session_manager.take_active_resize_request() {
+            self.apply_resize_request(ctx, rows, cols);
+        }
         // Show dialogs
-        if let Some(result) = self.config_dialog.show(ctx, &self.persistence) {
+        if let Some(result) = self.config_dialog.show(ctx, &self.persistence, &self.app_config.connection_profiles) {
             self.handle_dialog_result(result);
         }
-        if let Some(result) = self.options_dialog.show(ctx) {
+        if let Some(result) = self.options_dialog.show(ctx, &self.persistence) {
             self.handle_options_result(ctx, result);
         }
         self.show_delete_confirmation_dialogs(ctx);
+        self.show_paste_confirmation_dialog(ctx);
+        self.show_dropped_files_dialog(ctx);
+        self.show_auth_prompt_dialog(ctx);
+        self.show_import_message(ctx);
+        self.show_toasts(ctx);
         self.show_folder_rename_dialog(ctx);
         self.show_about_dialog(ctx);
+        self.show_diagnostics_window(ctx);
+        self.show_search_bar(ctx);
         // Menu bar
         self.show_menu_bar(ctx);
         // Debug: track frame count
         self.frame_count += 1;
         // Sidebar
-        if self.sidebar_visible {
+        if self.app_config.sidebar_visible {
             let panel_response = egui::SidePanel::left("sidebar")
                 .resizable(true)
                 .default_width(DEFAULT_SIDEBAR_WIDTH)
@@ -1076,16 +2566,28 @@ impl eframe::App for YasshApp {
         // Main content area
         CentralPanel::default().show(ctx, |ui| {
             // Collect session info for tab bar
+            let shared_counts = self.session_manager.shared_connection_counts();
             let tab_data: Vec<_> = self.session_manager.sessions().iter()
-                .map(|s| (s.id, s.title.clone(), s.state(), s.config.accent()))
+                .map(|s| {
+                    let folder = s.config.folder_id.and_then(|id| self.persistence.get_folder(id));
+                    // Folder color groups tabs visually; otherwise use the session's own accent
+                    let accent = folder
+                        .and_then(|folder| folder.color.clone())
+                        .map(egui::Color32::from)
+                        .unwrap_or_else(|| s.config.accent());
+                    let title = s.title(folder.map_or("", |folder| folder.name.as_str()));
+                    let shared_with = shared_counts.get(&s.id).copied().unwrap_or(0);
+                    (s.id, title, s.state(), accent, shared_with, s.reconnect_summary())
+                })
                 .collect();
             let active_id = self.session_manager.active_session().map(|s| s.id);
+            let disconnected = self.session_manager.disconnected_count();
             // Tab bar
             if !tab_data.is_empty() {
                 TopBottomPanel::top("tabs")
                     .frame(egui::Frame::NONE)
                     .show_inside(ui, |ui| {
-                        let action = self.tab_bar.show_with_data(ui, &tab_data, active_id);
+                        let action = self.tab_bar.show_with_data(ui, &tab_data, active_id, disconnected);
                         self.handle_tab_action(action);
                     });
             }
@@ -1093,6 +2595,7 @@ impl eframe::App for YasshApp {
             let dialogs_visible = self.any_dialog_visible();
             let mut should_copy_on_click = false;
             let mut should_paste_on_click = false;
+            let mut should_connect_to_selection = false;
             let mut copy_session_id = None;
             
             if let Some(session) = self.session_manager.active_session_mut() {
@@ -1116,14 +2619,53 @@ impl eframe::App for YasshApp {
                 };
 
                 // Calculate viewport size and handle resize
-                let available = ui.available_size();
-                let cell_width = session.renderer.cell_width();
-                let cell_height = session.renderer.cell_height();
-                let viewport_cols = (available.x / cell_width).floor() as usize;
-                let viewport_rows = (available.y / cell_height).floor() as usize;
+                let status_line = session.config.status_line.then(|| session.status_line_text());
+                if status_line.is_some() {
+                    // Keep the clock current
+                    ctx.request_repaint_after(std::time::Duration::from_secs(1));
+                }
+                session.renderer.set_status_line(status_line);
+                session.renderer.set_padding(session.config.padding);
+                session.renderer.set_line_height(session.config.line_height.clamp(MIN_LINE_HEIGHT, MAX_LINE_HEIGHT));
+                session.renderer.set_fixed_size(session.config.fixed_size);
+                session.renderer.set_max_columns(session.config.max_columns);
+                let (viewport_cols, viewport_rows) = session.renderer.calculate_grid_size(ui.available_size());
                 session.check_and_handle_resize(viewport_cols, viewport_rows, true);
                 // Render terminal
                 let current_scroll_offset = session.scroll_offset();
+                let search_highlight = self.search.as_ref()
+                    .filter(|search| search.session_id == session_id)
+                    .and_then(|search| search.current_match(session.emulator.buffer()));
+                session.renderer.set_search_highlight(search_highlight);
+                session.renderer.set_copy_cursor(sel_mgr.copy_cursor().map(|p| (p.line, p.col)));
+                session.renderer.set_scroll_options(
+                    self.app_config.scroll_lines_per_notch,
+                    self.app_config.invert_scroll,
+                );
+                session.renderer.set_activity_pane(session.config.activity_pane_rows);
+                session.renderer.set_font_styles(
+                    self.styled_faces,
+                    session.config.bold_font,
+                    session.config.italic_font,
+                );
+                session.renderer.set_font_family(&self.current_font, &self.app_config.fallback_font);
+                session.renderer.set_bold_is_bright(session.config.bold_is_bright);
+                session.renderer.set_dim_unfocused(self.app_config.dim_unfocused);
+                session.renderer.set_cache_line_layouts(self.app_config.performance_mode);
+                // Shift bypasses mouse reporting so text can still be selected, as in xterm
+                let mouse_tracking = session.emulator.mouse_tracking();
+                let report_mouse = mouse_tracking != MouseTracking::Off
+                    && !dialogs_visible
+                    && !ui.input(|i| i.modifiers.shift);
+                session.renderer.set_report_wheel(report_mouse);
+                let selection_color = self.app_config.selection_colors.get(&self.app_config.theme)
+                    .cloned()
+                    .map_or(ui.visuals().selection.bg_fill, Into::into);
+                session.renderer.set_selection_color(selection_color);
+                session.renderer.set_link_color(session.config.accent());
+                session.renderer.set_scrollbar_style(session.config.accent(), session.config.always_show_scrollbar);
+                let cell_size = session.renderer.cell_size();
+                session.emulator.set_cell_pixel_size(cell_size.x, cell_size.y);
                 let (response, new_scroll_offset, is_at_bottom, _viewport_cols, _viewport_rows) = session.renderer.render(
                     ui,
                     &session.emulator,
@@ -1134,6 +2676,43 @@ impl eframe::App for YasshApp {
                     current_scroll_offset,
                 );
                 session.set_scroll_offset_with_bottom(new_scroll_offset, is_at_bottom);
+                if report_mouse {
+                    send_mouse_reports(ui, session, &response, mouse_tracking, &mut self.mouse_report_cell);
+                }
+                if session.xoff_pending() {
+                    // Show the paused hint once output has stayed quiet long enough
+                    ctx.request_repaint_after(std::time::Duration::from_millis(250));
+                }
+                let indicators = [
+                    (session.is_scroll_locked(), "SCROLL LOCK"),
+                    (sel_mgr.copy_cursor().is_some(), "COPY MODE"),
+                    (session.emulator.insert_mode(), "INSERT"),
+                    (session.output_paused(), "OUTPUT PAUSED (Ctrl+Q to resume)"),
+                ];
+                let mut indicator_pos = response.rect.right_top() + egui::vec2(-INDICATOR_MARGIN, INDICATOR_MARGIN);
+                for (_, label) in indicators.into_iter().filter(|(shown, _)| *shown) {
+                    let painter = ui.painter();
+                    let galley = painter.layout_no_wrap(
+                        label.to_string(),
+                        egui::FontId::proportional(12.0),
+                        egui::Color32::BLACK,
+                    );
+                    let indicator_rect = egui::Rect::from_min_size(
+                        indicator_pos - egui::vec2(galley.size().x + 8.0, 0.0),
+                        galley.size() + egui::vec2(8.0, 4.0),
+                    );
+                    painter.rect_filled(indicator_rect, 3.0, egui::Color32::from_rgb(230, 180, 40));
+                    painter.galley(indicator_rect.min + egui::vec2(4.0, 2.0), galley, egui::Color32::BLACK);
+                    indicator_pos.y = indicator_rect.max.y + INDICATOR_MARGIN;
+                }
+                // Ctrl+wheel zooms the font of this connection only
+                let zoom_steps = session.renderer.take_zoom_steps();
+                if zoom_steps != 0 {
+                    let mut config = session.config.clone();
+                    config.font_size = (config.font_size as i32 + zoom_steps)
+                        .clamp(MIN_FONT_SIZE as i32, MAX_FONT_SIZE as i32) as u32;
+                    session.update_config(config);
+                }
                 // Handle bell visual feedback
                 if let Some((start_time, bell_type)) = &self.bell_blink_timer {
                     let elapsed = start_time.elapsed();
@@ -1150,10 +2729,11 @@ impl eframe::App for YasshApp {
                                 let line_idx = session.emulator.buffer().scrollback_len() + cursor_pos.row;
 
                                 // Create a temporary painter for just this line
-                                let line_y = response.rect.min.y + cursor_pos.row as f32 * session.renderer.cell_height();
+                                let grid_origin = session.renderer.grid_origin(response.rect);
+                                let line_y = grid_origin.y + cursor_pos.row as f32 * session.renderer.cell_height();
                                 let line_rect = egui::Rect::from_min_size(
-                                    egui::pos2(response.rect.min.x, line_y),
-                                    egui::vec2(response.rect.width(), session.renderer.cell_height()),
+                                    egui::pos2(grid_origin.x, line_y),
+                                    egui::vec2(session.emulator.buffer().cols() as f32 * session.renderer.cell_width(), session.renderer.cell_height()),
                                 );
 
                                 // Render the inverted line on top
@@ -1163,7 +2743,7 @@ impl eframe::App for YasshApp {
                                     session.emulator.buffer(),
                                     line_idx,
                                     0, // screen_row is 0 since we're rendering just this line
-                                    response.rect.min + egui::vec2(0.0, cursor_pos.row as f32 * session.renderer.cell_height()),
+                                    grid_origin + egui::vec2(0.0, cursor_pos.row as f32 * session.renderer.cell_height()),
                                     sel_mgr.selection(),
                                     session.emulator.reverse_video(),
                                 );
@@ -1179,7 +2759,7 @@ impl eframe::App for YasshApp {
                 }
 
                 // Handle left-click: copy selection if present, otherwise request focus
-                if !dialogs_visible && response.clicked() && sel_mgr.selection().is_some() {
+                if !dialogs_visible && !report_mouse && response.clicked() && sel_mgr.selection().is_some() {
                     should_copy_on_click = true;
                     copy_session_id = Some(session_id);
                 } else if !dialogs_visible && response.clicked() {
@@ -1187,14 +2767,32 @@ impl eframe::App for YasshApp {
                     ui.memory_mut(|m| m.request_focus(self.terminal_focus_id));
                 }
                 
-                // Handle right-click: paste from clipboard
-                if !dialogs_visible && response.secondary_clicked() {
+                // Handle right-click: context menu when text is selected, otherwise paste from clipboard
+                let has_selection = sel_mgr.selection().is_some();
+                if !dialogs_visible && !report_mouse && has_selection {
+                    response.context_menu(|ui| {
+                        if ui.button("Copy").clicked() {
+                            should_copy_on_click = true;
+                            copy_session_id = Some(session_id);
+                            ui.close();
+                        }
+                        if ui.button("Paste").clicked() {
+                            should_paste_on_click = true;
+                            ui.close();
+                        }
+                        ui.separator();
+                        if ui.button("Connect to selection").clicked() {
+                            should_connect_to_selection = true;
+                            ui.close();
+                        }
+                    });
+                } else if !dialogs_visible && !report_mouse && response.secondary_clicked() {
                     should_paste_on_click = true;
                 }
                 // Handle mouse input for selection
                 // Only process drag events if they're from the primary button (left click)
                 // Right-click drags should not create selections
-                let is_primary_drag = ui.input(|i| {
+                let is_primary_drag = !report_mouse && ui.input(|i| {
                     i.pointer.primary_down() && !i.pointer.secondary_down()
                 });
                 
@@ -1227,6 +2825,43 @@ impl eframe::App for YasshApp {
                 if response.drag_stopped() {
                     sel_mgr.finish();
                 }
+                // Hyperlinks (OSC 8) show their target on hover and open with Ctrl+click
+                let hovered_link = response.hover_pos()
+                    .and_then(|pos| session.renderer.cell_at_pos(
+                        pos,
+                        response.rect.min,
+                        session.emulator.buffer(),
+                        response.rect.height(),
+                        session.scroll_offset(),
+                    ))
+                    .and_then(|(line, col)| session.emulator.buffer().link_at(line, col))
+                    .map(String::from);
+                if let Some(url) = hovered_link {
+                    let ctrl = ui.input(|i| i.modifiers.ctrl);
+                    if !is_openable_url(&url) {
+                        response.clone().on_hover_text_at_pointer(format!("{}\n(not opened: unsupported link type)", url));
+                    } else if ctrl && response.clicked() {
+                        ctx.open_url(egui::OpenUrl::new_tab(&url));
+                    } else if ctrl {
+                        ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                    } else {
+                        response.clone().on_hover_text_at_pointer(format!("{}\nCtrl+click to open", url));
+                    }
+                }
+                if session.idle_disconnected() {
+                    ui.colored_label(Color32::from_rgb(255, 193, 7), "Disconnected due to inactivity");
+                    if ui.button("Reconnect").clicked() {
+                        session.connect();
+                    }
+                }
+                if let Some(status) = session.transfer_status.clone() {
+                    ui.horizontal(|ui| {
+                        ui.label(status);
+                        if ui.small_button("✖").clicked() {
+                            session.transfer_status = None;
+                        }
+                    });
+                }
                 // Show error message if any
                 if let Some(error) = &session.error_message {
                     ui.colored_label(Color32::from_rgb(244, 67, 54), format!("Error: {}", error));
@@ -1241,10 +2876,26 @@ impl eframe::App for YasshApp {
                         ui.add_space(WELCOME_SCREEN_TOP_MARGIN);
                         ui.heading("Welcome to Yassh");
                         ui.add_space(WELCOME_SCREEN_ELEMENT_SPACING);
-                        ui.label("Select a session from the sidebar or create a new one");
-                        ui.add_space(WELCOME_SCREEN_ELEMENT_SPACING);
-                        if ui.button("➕ New Session").clicked() {
-                            self.config_dialog.open_new();
+                        // First run: nothing saved yet, so offer to bring sessions over from other clients
+                        let first_run = self.persistence.sessions().is_empty() && self.persistence.folders().is_empty();
+                        if first_run {
+                            ui.label("You don't have any saved sessions yet");
+                            ui.add_space(WELCOME_SCREEN_ELEMENT_SPACING);
+                            if ui.button("➕ Create Your First Session").clicked() {
+                                self.config_dialog.open_new();
+                            }
+                            if ui.button("📥 Import from SSH Config").clicked() {
+                                self.import_from(ImportSource::SshConfig);
+                            }
+                            if ui.button("📥 Import from PuTTY").clicked() {
+                                self.import_from(ImportSource::Putty);
+                            }
+                        } else {
+                            ui.label("Select a session from the sidebar or create a new one");
+                            ui.add_space(WELCOME_SCREEN_ELEMENT_SPACING);
+                            if ui.button("➕ New Session").clicked() {
+                                self.config_dialog.open_new();
+                            }
                         }
                         if ui.button("⚡ Quick Connect").clicked() {
                             self.config_dialog.open_quick_connect();
@@ -1262,6 +2913,9 @@ impl eframe::App for YasshApp {
                     }
                 }
             }
+            if should_connect_to_selection {
+                self.connect_to_selection();
+            }
             if should_paste_on_click {
                 self.paste();
                 // Clear selection after paste to prevent pasted text from being highlighted
@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(unix)]
+use std::sync::OnceLock;
+
+// Windows API constants for bell sound
+#[cfg(windows)]
+const MB_ICONASTERISK: u32 = 0x00000040;
+#[cfg(windows)]
+const SND_ASYNC: u32 = 0x0001;
+#[cfg(windows)]
+const SND_NODEFAULT: u32 = 0x0002;
+#[cfg(windows)]
+const SND_FILENAME: u32 = 0x0002_0000;
+
+// Sound played on Linux when no custom file is set
+#[cfg(all(unix, not(target_os = "macos")))]
+const FREEDESKTOP_BELL_SOUND: &str = "/usr/share/sounds/freedesktop/stereo/bell.oga";
+// Players tried in order for sound files on Linux
+#[cfg(all(unix, not(target_os = "macos")))]
+const LINUX_SOUND_PLAYERS: [&str; 3] = ["paplay", "pw-play", "aplay"];
+
+/// Ring the audible bell: the given sound file, or the platform's notification sound.
+/// Never blocks the UI thread.
+pub fn play(sound_file: Option<&Path>) {
+    match sound_file {
+        Some(path) => play_file(path.to_path_buf()),
+        None => play_system_sound(),
+    }
+}
+
+#[cfg(windows)]
+fn play_system_sound() {
+    #[link(name = "user32")]
+    extern "system" {
+        fn MessageBeep(uType: u32) -> i32;
+    }
+    // Use Windows standard notification sound
+    unsafe { MessageBeep(MB_ICONASTERISK) };
+}
+
+#[cfg(windows)]
+fn play_file(path: PathBuf) {
+    use std::os::windows::ffi::OsStrExt;
+    #[link(name = "winmm")]
+    extern "system" {
+        fn PlaySoundW(pszSound: *const u16, hmod: *mut std::ffi::c_void, fdwSound: u32) -> i32;
+    }
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    // SND_ASYNC returns immediately; the system plays the file in the background
+    let played = unsafe {
+        PlaySoundW(wide.as_ptr(), std::ptr::null_mut(), SND_FILENAME | SND_ASYNC | SND_NODEFAULT)
+    };
+    if played == 0 {
+        play_system_sound();
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn play_system_sound() {
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn NSBeep();
+    }
+    unsafe { NSBeep() };
+}
+
+/// Hand a sound file to the one long-lived player thread, started on the first ring
+#[cfg(unix)]
+fn play_file(path: PathBuf) {
+    static PLAYER: OnceLock<Sender<PathBuf>> = OnceLock::new();
+    let player = PLAYER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || run_player(rx));
+        tx
+    });
+    let _ = player.send(path);
+}
+
+/// Play queued sounds one at a time; rings that arrive while one plays are merged into the next
+#[cfg(unix)]
+fn run_player(rx: Receiver<PathBuf>) {
+    while let Ok(mut path) = rx.recv() {
+        while let Ok(newer) = rx.try_recv() {
+            path = newer;
+        }
+        play_file_now(&path);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn play_file_now(path: &Path) {
+    let _ = std::process::Command::new("afplay").arg(path).status();
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn play_system_sound() {
+    let path = PathBuf::from(FREEDESKTOP_BELL_SOUND);
+    if path.is_file() {
+        play_file(path);
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn play_file_now(path: &Path) {
+    // There is no system beep API to call, so hand the file to whichever sound server is installed
+    for player in LINUX_SOUND_PLAYERS {
+        let status = std::process::Command::new(player)
+            .arg(path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+        if status.is_ok_and(|s| s.success()) {
+            break;
+        }
+    }
+}
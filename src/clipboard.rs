@@ -0,0 +1,89 @@
+use crate::debug;
+use arboard::Clipboard;
+use std::time::{Duration, Instant};
+
+// How often an unavailable system clipboard is tried again
+const RETRY_INTERVAL_SECS: u64 = 30;
+
+/// The system clipboard, with an in-app buffer standing in when it can't be reached
+/// (some Wayland setups), so copy and paste still work within yassh
+pub struct AppClipboard {
+    system: Option<Clipboard>,
+    last_attempt: Instant,
+    // Last text copied here; pasted when the system clipboard is unavailable
+    fallback: String,
+    // Set the first time the fallback is used, until the warning has been shown
+    warn: bool,
+    warned: bool,
+}
+
+impl AppClipboard {
+    pub fn new() -> Self {
+        let mut clipboard = Self {
+            system: None,
+            last_attempt: Instant::now(),
+            fallback: String::new(),
+            warn: false,
+            warned: false,
+        };
+        clipboard.connect();
+        clipboard
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.fallback = text.to_string();
+        self.retry();
+        let result = self.system.as_mut().map(|system| system.set_text(text));
+        match result {
+            Some(Ok(())) => {}
+            Some(Err(e)) => self.lost(&e),
+            None => self.use_fallback(),
+        }
+    }
+
+    pub fn get_text(&mut self) -> Option<String> {
+        self.retry();
+        let result = self.system.as_mut().map(|system| system.get_text());
+        match result {
+            Some(Ok(text)) => return Some(text),
+            // Nothing (or nothing textual) on the clipboard is not a failure
+            Some(Err(arboard::Error::ContentNotAvailable)) => return None,
+            Some(Err(e)) => self.lost(&e),
+            None => self.use_fallback(),
+        }
+        (!self.fallback.is_empty()).then(|| self.fallback.clone())
+    }
+
+    /// True once, after copy or paste first had to fall back to the in-app buffer
+    pub fn take_unavailable_warning(&mut self) -> bool {
+        std::mem::take(&mut self.warn)
+    }
+
+    fn connect(&mut self) {
+        self.last_attempt = Instant::now();
+        match Clipboard::new() {
+            Ok(system) => self.system = Some(system),
+            Err(e) => debug::log(&format!("[CLIPBOARD] System clipboard unavailable: {}", e)),
+        }
+    }
+
+    fn retry(&mut self) {
+        if self.system.is_none() && self.last_attempt.elapsed() >= Duration::from_secs(RETRY_INTERVAL_SECS) {
+            self.connect();
+        }
+    }
+
+    fn lost(&mut self, error: &arboard::Error) {
+        debug::log(&format!("[CLIPBOARD] System clipboard failed: {}", error));
+        self.system = None;
+        self.last_attempt = Instant::now();
+        self.use_fallback();
+    }
+
+    fn use_fallback(&mut self) {
+        if !self.warned {
+            self.warned = true;
+            self.warn = true;
+        }
+    }
+}
@@ -7,12 +7,29 @@ use std::time::Duration;
 use uuid::Uuid;
 
 // Default configuration values
-const DEFAULT_PORT: u16 = 22;
+pub const DEFAULT_PORT: u16 = 22;
 const DEFAULT_FONT_SIZE: u32 = 14;
+pub const MIN_FONT_SIZE: u32 = 6;
+pub const MAX_FONT_SIZE: u32 = 72;
 const DEFAULT_SCROLLBACK_LINES: usize = 20000;
+pub const MIN_SCROLLBACK_LINES: usize = 1000;
+pub const MAX_SCROLLBACK_LINES: usize = 100000;
+const DEFAULT_TAB_WIDTH: usize = 8;
+pub const MAX_TAB_WIDTH: usize = 16;
+pub const DEFAULT_ACTIVITY_PANE_ROWS: usize = 5;
+const DEFAULT_PADDING: u32 = 4;
+pub const MAX_PADDING: u32 = 64;
+const DEFAULT_LINE_HEIGHT: f32 = 1.2;
+pub const MIN_LINE_HEIGHT: f32 = 0.8;
+pub const MAX_LINE_HEIGHT: f32 = 3.0;
+pub const DEFAULT_FIXED_SIZE: (u16, u16) = (80, 24);
+pub const MAX_FIXED_COLUMNS: u16 = 1000;
+pub const MAX_FIXED_ROWS: u16 = 500;
+pub const DEFAULT_MAX_COLUMNS: u16 = 160;
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_KEEPALIVE_INTERVAL_SECS: u64 = 60;
 const DEFAULT_RECONNECT_MAX_ATTEMPTS: u32 = 5;
+pub const DEFAULT_IDLE_DISCONNECT_MINUTES: u32 = 30;
 
 static AVAILABLE_MONOSPACE_FONTS: OnceLock<Vec<String>> = OnceLock::new();
 
@@ -57,6 +74,10 @@ pub fn get_available_monospace_fonts() -> &'static Vec<String> {
 pub enum AuthMethod {
     Password,
     PrivateKey,
+    /// Keys held by a running SSH agent (OpenSSH agent, Pageant)
+    Agent,
+    /// Server-driven prompts (PAM, one-time passwords), answered in a dialog
+    KeyboardInteractive,
 }
 
 impl Default for AuthMethod {
@@ -94,6 +115,8 @@ impl Default for TerminalMode {
 pub enum BackspaceKey {
     Del,
     CtrlH,
+    /// DEL until the shell echoes it back as a literal ^?, then Ctrl+H
+    Auto,
 }
 
 impl Default for BackspaceKey {
@@ -102,6 +125,33 @@ impl Default for BackspaceKey {
     }
 }
 
+/// What the Delete key sends
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum DeleteKey {
+    #[default]
+    Vt220,
+    Del,
+    CtrlH,
+}
+
+/// Terminal multiplexer to attach to (or start) after login
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Multiplexer {
+    #[default]
+    Screen,
+    Tmux,
+}
+
+impl Multiplexer {
+    /// Attach-or-create command; `{name}` is replaced with the session name
+    pub fn default_command(self) -> &'static str {
+        match self {
+            Multiplexer::Screen => "screen -x {name} || screen -S {name}",
+            Multiplexer::Tmux => "tmux attach -t {name} || tmux new -s {name}",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ResizeMethod {
     Ssh,
@@ -130,6 +180,26 @@ impl Default for LineEnding {
     }
 }
 
+/// Character encoding used to decode server output
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Latin1,
+    Cp437,
+}
+
+/// What happens to the terminal contents when a session reconnects
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ReconnectHistory {
+    // Reset the terminal and drop all history
+    Clear,
+    // Reset the screen and modes but keep scrollback
+    KeepScrollback,
+    #[default]
+    KeepAll,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CursorType {
     Underline,
@@ -146,6 +216,7 @@ impl Default for CursorType {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AutoReconnect {
+    // Legacy setting, loaded as ReconnectPolicy::Never
     Manual,
     OnTabFocus,
     Immediate,
@@ -157,6 +228,57 @@ impl Default for AutoReconnect {
     }
 }
 
+/// What a session's tab runs: a remote shell over SSH or a shell on this machine
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SessionKind {
+    #[default]
+    Ssh,
+    LocalShell,
+}
+
+/// Whether server output moves the view to the bottom
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ScrollOnOutput {
+    // Stay on the same lines even while at the bottom
+    Never,
+    // Follow output only if the view was already at the bottom
+    #[default]
+    AtBottom,
+    // Jump to the bottom even when scrolled back
+    Always,
+}
+
+impl ScrollOnOutput {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Never => "Never",
+            Self::AtBottom => "Only when at bottom",
+            Self::Always => "Always",
+        }
+    }
+}
+
+/// Which disconnects trigger an automatic reconnect
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ReconnectPolicy {
+    Never,
+    // Network errors and dropped connections, but not the shell exiting
+    #[default]
+    OnError,
+    // Also reconnect when the remote shell exits normally
+    Always,
+}
+
+impl ReconnectPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Never => "Never",
+            Self::OnError => "On error only",
+            Self::Always => "Always",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortForward {
     pub local_port: u16,
@@ -164,6 +286,57 @@ pub struct PortForward {
     pub remote_port: u16,
 }
 
+/// Named connection defaults shared by many sessions (e.g. a common bastion)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub id: Uuid,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_session: Option<Uuid>,
+    #[serde(default = "default_true")]
+    pub keep_alive: bool,
+    #[serde(default = "default_keepalive_interval", with = "duration_secs")]
+    pub keepalive_interval: Duration,
+    #[serde(default)]
+    pub compression: bool,
+    #[serde(default = "default_timeout", with = "duration_secs")]
+    pub timeout: Duration,
+}
+
+impl ConnectionProfile {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            gateway_session: None,
+            keep_alive: true,
+            keepalive_interval: default_keepalive_interval(),
+            compression: false,
+            timeout: default_timeout(),
+        }
+    }
+}
+
+/// Profile fields a session sets itself instead of inheriting
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileOverrides {
+    pub gateway_session: bool,
+    // Covers the keep-alive interval as well
+    pub keep_alive: bool,
+    pub compression: bool,
+    pub timeout: bool,
+}
+
+/// One login automation step: wait for `pattern` in the output, then send `response`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpectStep {
+    pub pattern: String,
+    // Escapes like `\r` are expanded before sending
+    pub response: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableColor {
     pub r: u8,
@@ -195,6 +368,11 @@ pub struct SessionConfig {
     #[serde(default = "default_session_name")]
     pub name: String,
     #[serde(default)]
+    pub kind: SessionKind,
+    // Program run by local shell sessions (empty = $SHELL, or %COMSPEC% on Windows)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub local_shell: String,
+    #[serde(default)]
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
@@ -206,6 +384,12 @@ pub struct SessionConfig {
     pub password: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub private_key_path: Option<PathBuf>,
+    // Try the other auth method when the chosen one is rejected (key, then password or the reverse)
+    #[serde(default)]
+    pub auth_fallback: bool,
+    // Password for falling back from key auth (`password` holds the key passphrase then)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_password: Option<String>,
     #[serde(default = "default_font")]
     pub font: String,
     #[serde(default = "default_font_size")]
@@ -218,30 +402,92 @@ pub struct SessionConfig {
     pub accent_color: SerializableColor,
     #[serde(default)]
     pub cursor_type: CursorType,
+    #[serde(default = "default_padding")]
+    pub padding: u32,
+    // Cell height as a multiple of the font size
+    #[serde(default = "default_line_height")]
+    pub line_height: f32,
+    // Tab title with {name}, {user}, {host}, {port}, {folder} and {title} placeholders (empty = remote title)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title_template: String,
+    // Terminal size pinned to (cols, rows) regardless of the window size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_size: Option<(u16, u16)>,
+    // Widest the grid gets; a wider window centers the terminal between background-colored margins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_columns: Option<u16>,
+    #[serde(default = "default_true")]
+    pub bold_font: bool,
+    #[serde(default = "default_true")]
+    pub italic_font: bool,
+    #[serde(default)]
+    pub bold_is_bright: bool,
+    // Keep the scrollbar when there is no scrollback yet, instead of only showing it on overflow
+    #[serde(default)]
+    pub always_show_scrollbar: bool,
     #[serde(default = "default_scrollback_lines")]
     pub scrollback_lines: usize,
+    #[serde(default = "default_tab_width")]
+    pub tab_width: usize,
     #[serde(default = "default_true")]
     pub reset_scroll_on_input: bool,
     #[serde(default)]
+    pub scroll_on_output: ScrollOnOutput,
+    // Legacy setting, loaded as ScrollOnOutput::Always
+    #[serde(default, skip_serializing)]
     pub reset_scroll_on_output: bool,
+    // Live bottom rows kept visible while scrolled back (None = no split)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity_pane_rows: Option<usize>,
+    #[serde(default)]
+    pub status_line: bool,
     #[serde(default)]
     pub bell_notification: BellNotification,
     #[serde(default)]
+    pub reconnect_policy: ReconnectPolicy,
+    // When a reconnect happens; the policy decides whether it does
+    #[serde(default)]
     pub auto_reconnect: AutoReconnect,
     #[serde(default = "default_reconnect_max_attempts")]
     pub reconnect_max_attempts: u32,
     #[serde(default)]
+    pub reconnect_history: ReconnectHistory,
+    #[serde(default)]
     pub terminal_mode: TerminalMode,
     #[serde(default)]
     pub backspace_key: BackspaceKey,
     #[serde(default)]
+    pub delete_key: DeleteKey,
+    #[serde(default)]
+    pub swap_backspace_delete: bool,
+    // Keep Ctrl+S from sending XOFF, which freezes output until Ctrl+Q
+    #[serde(default)]
+    pub block_ctrl_s: bool,
+    #[serde(default)]
     pub resize_method: ResizeMethod,
     #[serde(default)]
     pub line_ending: LineEnding,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default)]
+    pub lone_cr_as_newline: bool,
+    #[serde(default = "default_true")]
+    pub sixel_graphics: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prompt_pattern: String,
+    #[serde(default)]
+    pub break_sequence: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_disconnect_minutes: Option<u32>,
+    #[serde(default)]
+    pub idle_output_counts_as_activity: bool,
     #[serde(default = "default_true")]
     pub keep_alive: bool,
     #[serde(default = "default_timeout", with = "duration_secs")]
     pub timeout: Duration,
+    // Try a quick TCP connect first, so a host that is down fails fast instead of after the full timeout
+    #[serde(default)]
+    pub check_reachable: bool,
     #[serde(default = "default_keepalive_interval", with = "duration_secs")]
     pub keepalive_interval: Duration,
     #[serde(default)]
@@ -249,27 +495,48 @@ pub struct SessionConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gateway_session: Option<Uuid>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<Uuid>,
+    #[serde(default)]
+    pub profile_overrides: ProfileOverrides,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub screen_session: Option<String>,
     #[serde(default)]
+    pub multiplexer: Multiplexer,
+    // Custom attach command with a {name} placeholder (empty = the multiplexer's default)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub multiplexer_command: String,
+    #[serde(default)]
     pub x11_forwarding: bool,
     #[serde(default)]
     pub local_forwards: Vec<PortForward>,
     #[serde(default)]
     pub remote_forwards: Vec<PortForward>,
+    #[serde(default)]
+    pub login_script: Vec<ExpectStep>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub folder_id: Option<Uuid>,
     #[serde(default)]
     pub order: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    // Free-form description shown when hovering the session in the sidebar
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 fn default_session_name() -> String { String::from("New Session") }
 fn default_port() -> u16 { DEFAULT_PORT }
 fn default_font() -> String { String::from("Consolas") }
 fn default_font_size() -> u32 { DEFAULT_FONT_SIZE }
+fn default_padding() -> u32 { DEFAULT_PADDING }
+fn default_line_height() -> f32 { DEFAULT_LINE_HEIGHT }
 fn default_foreground_color() -> SerializableColor { Color32::from_rgb(204, 204, 204).into() }
 fn default_background_color() -> SerializableColor { Color32::from_rgb(30, 30, 30).into() }
 fn default_accent_color() -> SerializableColor { Color32::from_rgb(128, 128, 128).into() }
 fn default_scrollback_lines() -> usize { DEFAULT_SCROLLBACK_LINES }
+fn default_tab_width() -> usize { DEFAULT_TAB_WIDTH }
 fn default_reconnect_max_attempts() -> u32 { DEFAULT_RECONNECT_MAX_ATTEMPTS }
 fn default_timeout() -> Duration { Duration::from_secs(DEFAULT_TIMEOUT_SECS) }
 fn default_keepalive_interval() -> Duration { Duration::from_secs(DEFAULT_KEEPALIVE_INTERVAL_SECS) }
@@ -300,11 +567,15 @@ impl Default for SessionConfig {
         Self {
             id: Uuid::new_v4(),
             name: String::from("New Session"),
+            kind: SessionKind::default(),
+            local_shell: String::new(),
             host: String::new(),
             port: DEFAULT_PORT,
             username: String::new(),
             auth_method: AuthMethod::default(),
             password: None,
+            auth_fallback: false,
+            fallback_password: None,
             private_key_path: None,
             font: String::from("Consolas"),
             font_size: DEFAULT_FONT_SIZE,
@@ -312,32 +583,123 @@ impl Default for SessionConfig {
             background_color: Color32::from_rgb(30, 30, 30).into(),
             accent_color: Color32::from_rgb(128, 128, 128).into(),
             cursor_type: CursorType::default(),
+            padding: DEFAULT_PADDING,
+            line_height: DEFAULT_LINE_HEIGHT,
+            title_template: String::new(),
+            fixed_size: None,
+            max_columns: None,
+            bold_font: true,
+            italic_font: true,
+            bold_is_bright: false,
+            always_show_scrollbar: false,
             scrollback_lines: DEFAULT_SCROLLBACK_LINES,
+            tab_width: DEFAULT_TAB_WIDTH,
             reset_scroll_on_input: true,
+            scroll_on_output: ScrollOnOutput::default(),
             reset_scroll_on_output: false,
+            activity_pane_rows: None,
+            status_line: false,
             bell_notification: BellNotification::default(),
+            reconnect_policy: ReconnectPolicy::default(),
             auto_reconnect: AutoReconnect::default(),
             reconnect_max_attempts: DEFAULT_RECONNECT_MAX_ATTEMPTS,
+            reconnect_history: ReconnectHistory::default(),
             terminal_mode: TerminalMode::default(),
             backspace_key: BackspaceKey::default(),
+            delete_key: DeleteKey::default(),
+            swap_backspace_delete: false,
+            block_ctrl_s: false,
             resize_method: ResizeMethod::default(),
             line_ending: LineEnding::default(),
+            encoding: Encoding::default(),
+            lone_cr_as_newline: false,
+            sixel_graphics: true,
+            prompt_pattern: String::new(),
+            break_sequence: String::new(),
+            idle_disconnect_minutes: None,
+            idle_output_counts_as_activity: false,
             keep_alive: true,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            check_reachable: false,
             keepalive_interval: Duration::from_secs(DEFAULT_KEEPALIVE_INTERVAL_SECS),
             compression: false,
             gateway_session: None,
+            profile_id: None,
+            profile_overrides: ProfileOverrides::default(),
             screen_session: None,
+            multiplexer: Multiplexer::default(),
+            multiplexer_command: String::new(),
             x11_forwarding: false,
             local_forwards: Vec::new(),
             remote_forwards: Vec::new(),
+            login_script: Vec::new(),
             folder_id: None,
             order: 0,
+            pinned: false,
+            notes: String::new(),
+            last_connected: None,
         }
     }
 }
 
 impl SessionConfig {
+    /// Bytes sent for the Backspace key, after any swap with Delete
+    pub fn backspace_sequence(&self) -> &'static [u8] {
+        if self.swap_backspace_delete {
+            self.delete_key_bytes()
+        } else {
+            self.backspace_key_bytes()
+        }
+    }
+
+    /// Bytes sent for the Delete key, after any swap with Backspace
+    pub fn delete_sequence(&self) -> &'static [u8] {
+        if self.swap_backspace_delete {
+            self.backspace_key_bytes()
+        } else {
+            self.delete_key_bytes()
+        }
+    }
+
+    fn backspace_key_bytes(&self) -> &'static [u8] {
+        match self.backspace_key {
+            BackspaceKey::Del | BackspaceKey::Auto => &[0x7F],
+            BackspaceKey::CtrlH => &[0x08],
+        }
+    }
+
+    fn delete_key_bytes(&self) -> &'static [u8] {
+        match self.delete_key {
+            DeleteKey::Vt220 => b"\x1b[3~",
+            DeleteKey::Del => &[0x7F],
+            DeleteKey::CtrlH => &[0x08],
+        }
+    }
+
+    /// Effective settings for connecting: profile fields replace the session's own
+    /// unless the session overrides them
+    pub fn with_profile(&self, profile: Option<&ConnectionProfile>) -> SessionConfig {
+        let mut config = self.clone();
+        let Some(profile) = profile else {
+            return config;
+        };
+        let overrides = self.profile_overrides;
+        if !overrides.gateway_session {
+            config.gateway_session = profile.gateway_session;
+        }
+        if !overrides.keep_alive {
+            config.keep_alive = profile.keep_alive;
+            config.keepalive_interval = profile.keepalive_interval;
+        }
+        if !overrides.compression {
+            config.compression = profile.compression;
+        }
+        if !overrides.timeout {
+            config.timeout = profile.timeout;
+        }
+        config
+    }
+
     pub fn foreground(&self) -> Color32 {
         self.foreground_color.clone().into()
     }
@@ -349,6 +711,205 @@ impl SessionConfig {
     pub fn accent(&self) -> Color32 {
         self.accent_color.clone().into()
     }
+
+    /// Bytes to send for "Send Break" (SSH has no BREAK, so the sequence is user-defined)
+    pub fn break_bytes(&self) -> Vec<u8> {
+        parse_escaped_sequence(&self.break_sequence)
+    }
+
+    /// Shell line that attaches to the multiplexer session, if one is set. When the multiplexer
+    /// is missing or fails the plain shell stays. Only `||` is used, so sh, fish and csh all accept it.
+    pub fn multiplexer_attach_line(&self) -> Option<String> {
+        let name = self.screen_session.as_deref().filter(|name| !name.is_empty())?;
+        let template = if self.multiplexer_command.trim().is_empty() {
+            self.multiplexer.default_command()
+        } else {
+            self.multiplexer_command.trim()
+        };
+        let command = template.replace("{name}", &shell_quote(name));
+        let program = command.split_whitespace().next()?;
+        Some(format!(
+            "{command} || echo '{program} is not available; staying in the login shell'\n"
+        ))
+    }
+
+    /// Equivalent OpenSSH command line, for use in scripts.
+    /// `gateway` is the session named by `gateway_session`, if it still exists.
+    pub fn ssh_command(&self, gateway: Option<&SessionConfig>) -> String {
+        let mut args = vec![String::from("ssh")];
+        if self.port != DEFAULT_PORT {
+            args.push(format!("-p {}", self.port));
+        }
+        if self.auth_method == AuthMethod::PrivateKey {
+            if let Some(key) = &self.private_key_path {
+                args.push(format!("-i {}", shell_quote(&key.to_string_lossy())));
+            }
+        }
+        if let Some(gateway) = gateway {
+            args.push(format!("-J {}", shell_quote(&gateway.destination_with_port())));
+        }
+        if self.compression {
+            args.push(String::from("-C"));
+        }
+        if self.x11_forwarding {
+            args.push(String::from("-X"));
+        }
+        for fwd in &self.local_forwards {
+            args.push(format!("-L {}", shell_quote(&format!("{}:{}:{}", fwd.local_port, fwd.remote_host, fwd.remote_port))));
+        }
+        for fwd in &self.remote_forwards {
+            args.push(format!("-R {}", shell_quote(&format!("{}:{}:localhost:{}", fwd.remote_host, fwd.remote_port, fwd.local_port))));
+        }
+        args.push(shell_quote(&self.destination()));
+        args.join(" ")
+    }
+
+    /// Sidebar tooltip: where the session connects, its notes and when it was last opened
+    pub fn hover_text(&self) -> String {
+        let mut text = match self.kind {
+            SessionKind::Ssh => format!("{}:{}", self.destination(), self.port),
+            SessionKind::LocalShell if self.local_shell.trim().is_empty() => String::from("Local shell"),
+            SessionKind::LocalShell => format!("Local shell: {}", self.local_shell.trim()),
+        };
+        if !self.notes.is_empty() {
+            text.push_str(&format!("\n\n{}", self.notes));
+        }
+        if let Some(time) = self.last_connected {
+            let local = time.with_timezone(&chrono::Local);
+            text.push_str(&format!("\n\nLast connected: {}", local.format("%Y-%m-%d %H:%M")));
+        }
+        text
+    }
+
+    // `user@host`, or just the host when no username is set
+    fn destination(&self) -> String {
+        if self.username.is_empty() {
+            self.host.clone()
+        } else {
+            format!("{}@{}", self.username, self.host)
+        }
+    }
+
+    // Destination in the `-J` form, which takes the port inline
+    fn destination_with_port(&self) -> String {
+        if self.port == DEFAULT_PORT {
+            self.destination()
+        } else {
+            format!("{}:{}", self.destination(), self.port)
+        }
+    }
+}
+
+/// Quote a word for a POSIX shell if it contains anything special
+fn shell_quote(word: &str) -> String {
+    let is_plain = !word.is_empty()
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_-~".contains(c));
+    if is_plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Expand `\xNN`, `\e`, `\r`, `\n`, `\t` and `\\` escapes into raw bytes
+pub fn parse_escaped_sequence(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut utf8 = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut utf8).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('e') => bytes.push(0x1B),
+            Some('r') => bytes.push(b'\r'),
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('x') => {
+                let hex: String = (0..2).filter_map(|_| chars.next_if(|c| c.is_ascii_hexdigit())).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) => bytes.push(byte),
+                    Err(_) => bytes.extend_from_slice(b"\\x"),
+                }
+            }
+            Some(other) => {
+                let mut utf8 = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut utf8).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    bytes
+}
+
+/// Host target parsed from a `[user@]host[:port]` string
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostSpec {
+    pub username: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl HostSpec {
+    /// Parse a launch argument: either `[user@]host[:port]` or an `ssh://` URL
+    pub fn parse_launch_arg(arg: &str) -> Option<Self> {
+        let arg = arg.trim();
+        let rest = match arg.get(..6) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("ssh://") => &arg[6..],
+            _ => arg,
+        };
+        // Drop any path component; it has no meaning for an interactive session
+        let authority = rest.split('/').next().unwrap_or(rest);
+        // RFC draft connection parameters (user;fingerprint=...@host) are ignored
+        let authority = match authority.rsplit_once('@') {
+            Some((user, host)) => format!("{}@{}", user.split(';').next().unwrap_or(user), host),
+            None => authority.to_string(),
+        };
+        Self::parse(&authority)
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        // Only the first word is considered, so a selection like "host.example.com:" works
+        let spec = text.split_whitespace().next()?;
+        let spec = spec.trim_matches(|c: char| matches!(c, '"' | '\'' | ',' | ';' | '(' | ')' | '<' | '>'));
+        let (username, rest) = match spec.rsplit_once('@') {
+            Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+            Some((_, rest)) => (None, rest),
+            None => (None, spec),
+        };
+        let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+            // IPv6 literal: [addr] or [addr]:port
+            let (addr, after) = bracketed.split_once(']')?;
+            let port = match after.strip_prefix(':') {
+                Some(p) => Some(p.parse::<u16>().ok()?),
+                None if after.is_empty() => None,
+                None => return None,
+            };
+            (addr, port)
+        } else {
+            match rest.split_once(':') {
+                // A single colon separates the port; more than one means a bare IPv6 address
+                Some((host, port)) if !port.contains(':') => {
+                    if port.is_empty() {
+                        (host, None)
+                    } else {
+                        (host, Some(port.parse::<u16>().ok()?))
+                    }
+                }
+                _ => (rest, None),
+            }
+        };
+        let host = host.trim_end_matches('.');
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self {
+            username,
+            host: host.to_string(),
+            port,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -362,6 +923,12 @@ pub struct SessionFolder {
     pub expanded: bool,
     #[serde(default)]
     pub order: u32,
+    // Pinned folders sort above unpinned ones at the same level
+    #[serde(default)]
+    pub pinned: bool,
+    // Tab tint for sessions in this folder (overrides the session accent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<SerializableColor>,
 }
 
 impl SessionFolder {
@@ -372,11 +939,13 @@ impl SessionFolder {
             parent_id: None,
             expanded: true,
             order: 0,
+            pinned: false,
+            color: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Theme {
     Dark,
     Light,
@@ -470,15 +1039,109 @@ pub struct AppConfig {
     pub window_height: f32,
     #[serde(default)]
     pub window_maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default = "default_true")]
+    pub sidebar_visible: bool,
+    #[serde(default = "default_true")]
+    pub confirm_multiline_paste: bool,
+    // Pasted tabs become this many spaces, so they don't trigger shell completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paste_tab_spaces: Option<usize>,
+    #[serde(default)]
+    pub copy_preserve_trailing_spaces: bool,
+    #[serde(default = "default_scroll_lines_per_notch")]
+    pub scroll_lines_per_notch: u32,
+    #[serde(default)]
+    pub invert_scroll: bool,
+    #[serde(default)]
+    pub dim_unfocused: bool,
+    // Lay out terminal text a line at a time, reusing unchanged lines (faster full-screen redraws)
+    #[serde(default)]
+    pub performance_mode: bool,
+    // Selection highlight chosen for a theme; themes without one use their own selection color
+    #[serde(default)]
+    pub selection_colors: std::collections::HashMap<Theme, SerializableColor>,
+    #[serde(default = "default_fallback_font")]
+    pub fallback_font: String,
+    #[serde(default)]
+    pub mru_tab_switching: bool,
+    #[serde(default)]
+    pub connection_profiles: Vec<ConnectionProfile>,
+    #[serde(default)]
+    pub terminal_defaults: TerminalDefaults,
+    // Bells closer together than this are dropped, so a flood of BEL can't spam notifications
+    #[serde(default = "default_bell_min_interval_ms")]
+    pub bell_min_interval_ms: u64,
+    // Sound file for the audible bell; empty uses the system notification sound
+    #[serde(default)]
+    pub bell_sound_file: String,
+    // Sessions allowed to be connecting at once; the rest wait their turn (0 = no limit)
+    #[serde(default = "default_max_connecting_sessions")]
+    pub max_connecting_sessions: usize,
+}
+
+/// App-wide terminal settings that new sessions start with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TerminalDefaults {
+    #[serde(default = "default_scrollback_lines")]
+    pub scrollback_lines: usize,
+    #[serde(default = "default_font_size")]
+    pub font_size: u32,
+}
+
+impl Default for TerminalDefaults {
+    fn default() -> Self {
+        Self {
+            scrollback_lines: DEFAULT_SCROLLBACK_LINES,
+            font_size: DEFAULT_FONT_SIZE,
+        }
+    }
+}
+
+impl TerminalDefaults {
+    /// Settings for a brand new session
+    pub fn session_config(&self) -> SessionConfig {
+        let mut config = SessionConfig::default();
+        self.apply_to(&mut config);
+        config
+    }
+
+    pub fn apply_to(&self, config: &mut SessionConfig) {
+        config.scrollback_lines = self.scrollback_lines;
+        config.font_size = self.font_size;
+    }
+}
+
+impl AppConfig {
+    pub fn connection_profile(&self, id: Option<Uuid>) -> Option<&ConnectionProfile> {
+        let id = id?;
+        self.connection_profiles.iter().find(|p| p.id == id)
+    }
 }
 
 fn default_sidebar_width() -> f32 { DEFAULT_SIDEBAR_WIDTH }
 fn default_window_width() -> f32 { DEFAULT_WINDOW_WIDTH }
 fn default_window_height() -> f32 { DEFAULT_WINDOW_HEIGHT }
+fn default_scroll_lines_per_notch() -> u32 { DEFAULT_SCROLL_LINES_PER_NOTCH }
+fn default_fallback_font() -> String { String::from("Segoe UI Emoji") }
+fn default_bell_min_interval_ms() -> u64 { DEFAULT_BELL_MIN_INTERVAL_MS }
+fn default_max_connecting_sessions() -> usize { DEFAULT_MAX_CONNECTING_SESSIONS }
 
 const DEFAULT_SIDEBAR_WIDTH: f32 = 130.0;
 const DEFAULT_WINDOW_WIDTH: f32 = 1200.0;
 const DEFAULT_WINDOW_HEIGHT: f32 = 800.0;
+const DEFAULT_SCROLL_LINES_PER_NOTCH: u32 = 3;
+pub const MAX_SCROLL_LINES_PER_NOTCH: u32 = 20;
+const DEFAULT_BELL_MIN_INTERVAL_MS: u64 = 200;
+// Floor for the bell throttle, so a stream of BELs can't queue a sound per byte
+pub const MIN_BELL_MIN_INTERVAL_MS: u64 = 50;
+pub const MAX_BELL_MIN_INTERVAL_MS: u64 = 5000;
+const DEFAULT_MAX_CONNECTING_SESSIONS: usize = 4;
+pub const MAX_CONNECTING_SESSIONS_LIMIT: usize = 64;
+pub const DEFAULT_PASTE_TAB_SPACES: usize = 4;
 
 impl Default for AppConfig {
     fn default() -> Self {
@@ -488,6 +1151,24 @@ impl Default for AppConfig {
             window_width: DEFAULT_WINDOW_WIDTH,
             window_height: DEFAULT_WINDOW_HEIGHT,
             window_maximized: false,
+            fullscreen: false,
+            always_on_top: false,
+            sidebar_visible: true,
+            confirm_multiline_paste: true,
+            paste_tab_spaces: None,
+            copy_preserve_trailing_spaces: false,
+            scroll_lines_per_notch: DEFAULT_SCROLL_LINES_PER_NOTCH,
+            invert_scroll: false,
+            dim_unfocused: false,
+            performance_mode: false,
+            selection_colors: std::collections::HashMap::new(),
+            fallback_font: default_fallback_font(),
+            mru_tab_switching: false,
+            connection_profiles: Vec::new(),
+            terminal_defaults: TerminalDefaults::default(),
+            bell_min_interval_ms: DEFAULT_BELL_MIN_INTERVAL_MS,
+            bell_sound_file: String::new(),
+            max_connecting_sessions: DEFAULT_MAX_CONNECTING_SESSIONS,
         }
     }
 }
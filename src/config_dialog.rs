@@ -1,15 +1,16 @@
 use crate::config::{
     get_available_monospace_fonts, AuthMethod, AutoReconnect, BackspaceKey, BellNotification,
-    LineEnding, PortForward, ResizeMethod, SessionConfig, TerminalMode,
+    ConnectionProfile, DeleteKey, TerminalDefaults, Encoding, ExpectStep, HostSpec, LineEnding, Multiplexer, PortForward, ReconnectHistory, ReconnectPolicy, ResizeMethod, ScrollOnOutput, SessionConfig, SessionKind,
+    TerminalMode, MAX_FONT_SIZE, MAX_PADDING, MIN_LINE_HEIGHT, MAX_LINE_HEIGHT, DEFAULT_FIXED_SIZE, DEFAULT_MAX_COLUMNS, MAX_FIXED_COLUMNS, MAX_FIXED_ROWS, MAX_SCROLLBACK_LINES, MAX_TAB_WIDTH, MIN_FONT_SIZE, MIN_SCROLLBACK_LINES, DEFAULT_ACTIVITY_PANE_ROWS,
+    DEFAULT_IDLE_DISCONNECT_MINUTES,
 };
 use crate::persistence::PersistenceManager;
+use crate::session_tree_view::FOLDER_MENU_INDENT;
 use egui::{Align2, Area, Color32, Order, RichText, Ui, Window};
 use uuid::Uuid;
 
 // Dialog constants
 const INPUT_WIDTH: f32 = 300.0;
-const MIN_FONT_SIZE: u32 = 6;
-const MAX_FONT_SIZE: u32 = 72;
 const OVERLAY_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 0, 0, 180);
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,7 +18,9 @@ pub enum DialogMode {
     New,
     Edit(Uuid),               // Edit stored session
     EditConnection(Uuid),     // Edit open connection (runtime settings only)
+    ReconnectAs(Uuid),        // One-off credentials for reconnecting an open connection
     QuickConnect,
+    SaveConnection(Uuid),     // Store an unsaved open connection (e.g. Quick Connect) as a session
 }
 
 pub struct ConfigDialog {
@@ -27,6 +30,7 @@ pub struct ConfigDialog {
     pub password_visible: bool,
     new_local_forward: PortForwardEdit,
     validation_error: Option<String>,
+    terminal_defaults: TerminalDefaults,
 }
 
 #[derive(Default)]
@@ -51,12 +55,18 @@ impl ConfigDialog {
             password_visible: false,
             new_local_forward: PortForwardEdit::default(),
             validation_error: None,
+            terminal_defaults: TerminalDefaults::default(),
         }
     }
 
+    /// Settings new and quick connect sessions start from
+    pub fn set_terminal_defaults(&mut self, defaults: TerminalDefaults) {
+        self.terminal_defaults = defaults;
+    }
+
     pub fn open_new(&mut self) {
         self.mode = DialogMode::New;
-        self.config = SessionConfig::default();
+        self.config = self.terminal_defaults.session_config();
         self.password_visible = false;
         self.validation_error = None;
         self.visible = true;
@@ -64,7 +74,7 @@ impl ConfigDialog {
 
     pub fn open_new_in_folder(&mut self, folder_id: Uuid) {
         self.mode = DialogMode::New;
-        self.config = SessionConfig::default();
+        self.config = self.terminal_defaults.session_config();
         self.config.folder_id = Some(folder_id);
         self.password_visible = false;
         self.validation_error = None;
@@ -87,20 +97,58 @@ impl ConfigDialog {
         self.visible = true;
     }
 
+    /// Ask for credentials to reconnect an open connection with; nothing is saved
+    pub fn open_reconnect_as(&mut self, connection_id: Uuid, config: SessionConfig) {
+        self.mode = DialogMode::ReconnectAs(connection_id);
+        self.config = config;
+        self.config.password = None;
+        self.password_visible = false;
+        self.validation_error = None;
+        self.visible = true;
+    }
+
+    /// Offer to store an open connection that has no saved session behind it
+    pub fn open_save_connection(&mut self, connection_id: Uuid, config: SessionConfig) {
+        self.mode = DialogMode::SaveConnection(connection_id);
+        self.config = config;
+        if self.config.name == "Quick Connect" && !self.config.host.is_empty() {
+            self.config.name = self.config.host.clone();
+        }
+        self.password_visible = false;
+        self.validation_error = None;
+        self.visible = true;
+    }
+
     pub fn open_quick_connect(&mut self) {
         self.mode = DialogMode::QuickConnect;
-        self.config = SessionConfig::default();
+        self.config = self.terminal_defaults.session_config();
         self.config.name = String::from("Quick Connect");
         self.password_visible = false;
         self.validation_error = None;
         self.visible = true;
     }
 
+    pub fn open_quick_connect_to(&mut self, spec: HostSpec) {
+        self.open_quick_connect();
+        self.config.host = spec.host;
+        if let Some(username) = spec.username {
+            self.config.username = username;
+        }
+        if let Some(port) = spec.port {
+            self.config.port = port;
+        }
+    }
+
     pub fn is_visible(&self) -> bool {
         self.visible
     }
 
-    pub fn show(&mut self, ctx: &egui::Context, persistence: &PersistenceManager) -> Option<DialogResult> {
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        persistence: &PersistenceManager,
+        profiles: &[ConnectionProfile],
+    ) -> Option<DialogResult> {
         if !self.visible {
             return None;
         }
@@ -118,7 +166,9 @@ impl ConfigDialog {
             DialogMode::New => "New Session",
             DialogMode::Edit(_) => "Edit Session",
             DialogMode::EditConnection(_) => "Connection Settings",
+            DialogMode::ReconnectAs(_) => "Reconnect As",
             DialogMode::QuickConnect => "Quick Connect",
+            DialogMode::SaveConnection(_) => "Save Session",
         };
         Window::new(title)
             .collapsible(false)
@@ -127,34 +177,90 @@ impl ConfigDialog {
             .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
             .min_width(500.0)
             .show(ctx, |ui| {
-                result = self.show_content(ui, persistence);
+                result = self.show_content(ui, persistence, profiles);
             });
         result
     }
 
-    fn show_content(&mut self, ui: &mut Ui, persistence: &PersistenceManager) -> Option<DialogResult> {
+    fn show_content(
+        &mut self,
+        ui: &mut Ui,
+        persistence: &PersistenceManager,
+        profiles: &[ConnectionProfile],
+    ) -> Option<DialogResult> {
         let mut result = None;
         let is_connection_edit = matches!(self.mode, DialogMode::EditConnection(_));
+        let is_reconnect_as = matches!(self.mode, DialogMode::ReconnectAs(_));
+        // Sampled before any widget runs: a focused text field gives up focus on Enter this frame
+        let editing_text = ui.ctx().wants_keyboard_input();
         egui::ScrollArea::vertical()
             .max_height(500.0)
             .show(ui, |ui| {
             // Connection section - hidden when editing an open connection
-            if !is_connection_edit {
+            if !is_connection_edit && !is_reconnect_as {
                 ui.heading("Connection");
                 ui.add_space(4.0);
                 ui.horizontal(|ui| {
                     ui.label("Name:");
                     ui.add(egui::TextEdit::singleline(&mut self.config.name).desired_width(INPUT_WIDTH));
                 });
+                if matches!(self.mode, DialogMode::New | DialogMode::SaveConnection(_)) {
+                    ui.horizontal(|ui| {
+                        ui.label("Group:");
+                        let selected = self.config.folder_id
+                            .and_then(|id| persistence.folder_path(id))
+                            .unwrap_or_else(|| String::from("None"));
+                        egui::ComboBox::from_id_salt("session_folder")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.config.folder_id, None, "None");
+                                for (folder, depth) in persistence.folder_tree() {
+                                    let label = format!("{}{}", FOLDER_MENU_INDENT.repeat(depth), folder.name);
+                                    ui.selectable_value(&mut self.config.folder_id, Some(folder.id), label);
+                                }
+                            });
+                    });
+                }
                 ui.horizontal(|ui| {
-                    ui.label("Host:");
-                    ui.add(egui::TextEdit::singleline(&mut self.config.host).desired_width(INPUT_WIDTH));
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("session_kind")
+                        .selected_text(match self.config.kind {
+                            SessionKind::Ssh => "SSH",
+                            SessionKind::LocalShell => "Local Shell",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.kind, SessionKind::Ssh, "SSH");
+                            ui.selectable_value(&mut self.config.kind, SessionKind::LocalShell, "Local Shell");
+                        });
                 });
+                if self.config.kind == SessionKind::LocalShell {
+                    ui.horizontal(|ui| {
+                        ui.label("Shell:");
+                        ui.add(egui::TextEdit::singleline(&mut self.config.local_shell)
+                            .hint_text("Default shell")
+                            .desired_width(INPUT_WIDTH));
+                    });
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.label("Host:");
+                        ui.add(egui::TextEdit::singleline(&mut self.config.host).desired_width(INPUT_WIDTH));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Port:");
+                        ui.add(egui::DragValue::new(&mut self.config.port).range(1..=65535));
+                    });
+                }
                 ui.horizontal(|ui| {
-                    ui.label("Port:");
-                    ui.add(egui::DragValue::new(&mut self.config.port).range(1..=65535));
+                    ui.label("Notes:");
+                    ui.add(egui::TextEdit::multiline(&mut self.config.notes)
+                        .hint_text("Shown when hovering the session")
+                        .desired_rows(3)
+                        .desired_width(INPUT_WIDTH));
                 });
                 ui.add_space(8.0);
+            }
+            let is_local = self.config.kind == SessionKind::LocalShell;
+            if !is_connection_edit && !is_local {
                 // Authentication section
                 ui.heading("Authentication");
                 ui.add_space(4.0);
@@ -163,17 +269,21 @@ impl ConfigDialog {
                     ui.add(egui::TextEdit::singleline(&mut self.config.username).desired_width(INPUT_WIDTH));
                 });
             }
-            if !is_connection_edit {
+            if !is_connection_edit && !is_local {
                 ui.horizontal(|ui| {
                     ui.label("Auth Method:");
                     egui::ComboBox::from_id_salt("auth_method")
                         .selected_text(match self.config.auth_method {
                             AuthMethod::Password => "Password",
                             AuthMethod::PrivateKey => "Private Key",
+                            AuthMethod::Agent => "SSH Agent",
+                            AuthMethod::KeyboardInteractive => "Keyboard-Interactive",
                         })
                         .show_ui(ui, |ui| {
                             ui.selectable_value(&mut self.config.auth_method, AuthMethod::Password, "Password");
                             ui.selectable_value(&mut self.config.auth_method, AuthMethod::PrivateKey, "Private Key");
+                            ui.selectable_value(&mut self.config.auth_method, AuthMethod::Agent, "SSH Agent");
+                            ui.selectable_value(&mut self.config.auth_method, AuthMethod::KeyboardInteractive, "Keyboard-Interactive");
                         });
                 });
                 match self.config.auth_method {
@@ -190,33 +300,48 @@ impl ConfigDialog {
                                 self.password_visible = !self.password_visible;
                             }
                         });
+                        ui.checkbox(&mut self.config.auth_fallback, "Fall back to the private key if the password is rejected");
+                        if self.config.auth_fallback {
+                            self.key_file_row(ui);
+                        }
                     }
                     AuthMethod::PrivateKey => {
-                        ui.horizontal(|ui| {
-                            ui.label("Key File:");
-                            let path_str = self.config.private_key_path
-                                .as_ref()
-                                .map(|p| p.display().to_string())
-                                .unwrap_or_default();
-                            ui.label(&path_str);
-                            if ui.button("Browse...").clicked() {
-                                if let Some(path) = rfd::FileDialog::new()
-                                    .add_filter("Private Key", &["pem", "ppk", "key", ""])
-                                    .pick_file()
-                                {
-                                    self.config.private_key_path = Some(path);
-                                }
-                            }
-                        });
+                        self.key_file_row(ui);
                         ui.horizontal(|ui| {
                             ui.label("Passphrase:");
                             let password = self.config.password.get_or_insert_with(String::new);
                             ui.add(egui::TextEdit::singleline(password).password(true).desired_width(INPUT_WIDTH - 60.0));
                         });
+                        ui.checkbox(&mut self.config.auth_fallback, "Fall back to a password if the key is rejected");
+                        if self.config.auth_fallback {
+                            ui.horizontal(|ui| {
+                                ui.label("Password:");
+                                let password = self.config.fallback_password.get_or_insert_with(String::new);
+                                ui.add(egui::TextEdit::singleline(password).password(true).desired_width(INPUT_WIDTH - 60.0));
+                            });
+                        }
+                    }
+                    AuthMethod::Agent => {
+                        ui.label(RichText::new("Each key loaded in the running SSH agent is offered in turn").weak());
+                        ui.checkbox(&mut self.config.auth_fallback, "Fall back to a password if no key is accepted");
+                        if self.config.auth_fallback {
+                            ui.horizontal(|ui| {
+                                ui.label("Password:");
+                                let password = self.config.fallback_password.get_or_insert_with(String::new);
+                                ui.add(egui::TextEdit::singleline(password).password(true).desired_width(INPUT_WIDTH - 60.0));
+                            });
+                        }
+                    }
+                    AuthMethod::KeyboardInteractive => {
+                        ui.label(RichText::new("The server's prompts (password, one-time code) are asked for when connecting").weak());
                     }
                 }
                 ui.add_space(8.0);
             }
+            // Reconnecting with other credentials only asks for authentication
+            if is_reconnect_as {
+                return;
+            }
             // Appearance section
             let header = egui::CollapsingHeader::new("Appearance");
             header.show(ui, |ui| {
@@ -298,36 +423,133 @@ impl ConfigDialog {
                             ui.selectable_value(&mut self.config.cursor_type, crate::config::CursorType::None, "None");
                         });
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Padding (pixels):");
+                    ui.add(egui::DragValue::new(&mut self.config.padding).range(0..=MAX_PADDING));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Line Height:");
+                    ui.add(egui::DragValue::new(&mut self.config.line_height)
+                        .range(MIN_LINE_HEIGHT..=MAX_LINE_HEIGHT)
+                        .speed(0.05)
+                        .fixed_decimals(2));
+                    ui.label("× font size");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Tab Title:");
+                    ui.add(egui::TextEdit::singleline(&mut self.config.title_template)
+                        .desired_width(INPUT_WIDTH)
+                        .hint_text("{title}"));
+                });
+                ui.label(RichText::new("Placeholders: {name} {user} {host} {port} {folder} {title} (remote title)").weak());
+                ui.horizontal(|ui| {
+                    let mut fixed = self.config.fixed_size.is_some();
+                    if ui.checkbox(&mut fixed, "Fixed size (columns × rows):").changed() {
+                        self.config.fixed_size = fixed.then_some(DEFAULT_FIXED_SIZE);
+                    }
+                    let (mut cols, mut rows) = self.config.fixed_size.unwrap_or(DEFAULT_FIXED_SIZE);
+                    let cols_changed = ui.add_enabled(fixed, egui::DragValue::new(&mut cols).range(1..=MAX_FIXED_COLUMNS)).changed();
+                    ui.label("×");
+                    let rows_changed = ui.add_enabled(fixed, egui::DragValue::new(&mut rows).range(1..=MAX_FIXED_ROWS)).changed();
+                    if cols_changed || rows_changed {
+                        self.config.fixed_size = Some((cols, rows));
+                    }
+                });
+                ui.add_enabled_ui(self.config.fixed_size.is_none(), |ui| {
+                    ui.horizontal(|ui| {
+                        let mut capped = self.config.max_columns.is_some();
+                        if ui.checkbox(&mut capped, "Maximum columns:").changed() {
+                            self.config.max_columns = capped.then_some(DEFAULT_MAX_COLUMNS);
+                        }
+                        if let Some(cols) = &mut self.config.max_columns {
+                            ui.add(egui::DragValue::new(cols).range(1..=MAX_FIXED_COLUMNS));
+                        }
+                    });
+                });
+                ui.checkbox(&mut self.config.bold_font, "Use bold font face for bold text");
+                ui.checkbox(&mut self.config.italic_font, "Use italic font face for italic text");
+                ui.checkbox(&mut self.config.bold_is_bright, "Show bold text in bright colors");
+                ui.checkbox(&mut self.config.always_show_scrollbar, "Always show the scrollbar");
+                ui.checkbox(&mut self.config.status_line, "Show status line (session, connection, clock)");
             });
             // Behavior section
             let header = egui::CollapsingHeader::new("Behavior");
             header.show(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Scrollback Lines:");
-                    ui.add(egui::DragValue::new(&mut self.config.scrollback_lines).range(1000..=100000).speed(100));
+                    ui.add(egui::DragValue::new(&mut self.config.scrollback_lines).range(MIN_SCROLLBACK_LINES..=MAX_SCROLLBACK_LINES).speed(100));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Tab Width:");
+                    ui.add(egui::DragValue::new(&mut self.config.tab_width).range(1..=MAX_TAB_WIDTH));
                 });
                 ui.checkbox(&mut self.config.reset_scroll_on_input, "Reset scroll position on user input");
-                ui.checkbox(&mut self.config.reset_scroll_on_output, "Reset scroll position on server output");
+                ui.horizontal(|ui| {
+                    ui.label("Scroll to bottom on output:");
+                    egui::ComboBox::from_id_salt("scroll_on_output")
+                        .selected_text(self.config.scroll_on_output.label())
+                        .show_ui(ui, |ui| {
+                            for mode in [ScrollOnOutput::Never, ScrollOnOutput::AtBottom, ScrollOnOutput::Always] {
+                                ui.selectable_value(&mut self.config.scroll_on_output, mode, mode.label());
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    let mut pane_enabled = self.config.activity_pane_rows.is_some();
+                    if ui.checkbox(&mut pane_enabled, "Keep live rows visible while scrolled back:").changed() {
+                        self.config.activity_pane_rows = pane_enabled.then_some(DEFAULT_ACTIVITY_PANE_ROWS);
+                    }
+                    if let Some(rows) = &mut self.config.activity_pane_rows {
+                        ui.add(egui::DragValue::new(rows).range(1..=50));
+                    }
+                });
                 ui.horizontal(|ui| {
                     ui.label("Auto-reconnect:");
-                    egui::ComboBox::from_id_salt("auto_reconnect")
-                        .selected_text(match self.config.auto_reconnect {
-                            AutoReconnect::Manual => "Manual",
-                            AutoReconnect::OnTabFocus => "On focus",
-                            AutoReconnect::Immediate => "Immediate",
-                        })
+                    egui::ComboBox::from_id_salt("reconnect_policy")
+                        .selected_text(self.config.reconnect_policy.label())
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut self.config.auto_reconnect, AutoReconnect::Manual, "Manual");
-                            ui.selectable_value(&mut self.config.auto_reconnect, AutoReconnect::OnTabFocus, "On focus");
-                            ui.selectable_value(&mut self.config.auto_reconnect, AutoReconnect::Immediate, "Immediate");
+                            for policy in [ReconnectPolicy::Never, ReconnectPolicy::OnError, ReconnectPolicy::Always] {
+                                ui.selectable_value(&mut self.config.reconnect_policy, policy, policy.label());
+                            }
                         });
                 });
-                if self.config.auto_reconnect != AutoReconnect::Manual {
+                ui.label(RichText::new(match self.config.reconnect_policy {
+                    ReconnectPolicy::Never => "Disconnected tabs stay open until reconnected by hand",
+                    ReconnectPolicy::OnError => "Exiting the shell closes the tab; dropped connections reconnect",
+                    ReconnectPolicy::Always => "Exiting the shell also reconnects instead of closing the tab",
+                }).weak());
+                if self.config.reconnect_policy != ReconnectPolicy::Never {
+                    ui.horizontal(|ui| {
+                        ui.label("Reconnect:");
+                        egui::ComboBox::from_id_salt("auto_reconnect")
+                            .selected_text(match self.config.auto_reconnect {
+                                AutoReconnect::OnTabFocus | AutoReconnect::Manual => "On focus",
+                                AutoReconnect::Immediate => "Immediately",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.config.auto_reconnect, AutoReconnect::OnTabFocus, "On focus");
+                                ui.selectable_value(&mut self.config.auto_reconnect, AutoReconnect::Immediate, "Immediately");
+                            });
+                    });
                     ui.horizontal(|ui| {
                         ui.label("Max reconnect attempts:");
                         ui.add(egui::DragValue::new(&mut self.config.reconnect_max_attempts).range(1..=100));
                     });
                 }
+                ui.horizontal(|ui| {
+                    ui.label("On reconnect:");
+                    egui::ComboBox::from_id_salt("reconnect_history")
+                        .selected_text(match self.config.reconnect_history {
+                            ReconnectHistory::Clear => "Clear everything",
+                            ReconnectHistory::KeepScrollback => "Reset screen, keep scrollback",
+                            ReconnectHistory::KeepAll => "Keep everything",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.reconnect_history, ReconnectHistory::Clear, "Clear everything");
+                            ui.selectable_value(&mut self.config.reconnect_history, ReconnectHistory::KeepScrollback, "Reset screen, keep scrollback");
+                            ui.selectable_value(&mut self.config.reconnect_history, ReconnectHistory::KeepAll, "Keep everything");
+                        });
+                });
                 ui.horizontal(|ui| {
                     ui.label("Bell Notification:");
                     egui::ComboBox::from_id_salt("bell")
@@ -364,12 +586,31 @@ impl ConfigDialog {
                         .selected_text(match self.config.backspace_key {
                             BackspaceKey::Del => "DEL (0x7F)",
                             BackspaceKey::CtrlH => "Ctrl+H (0x08)",
+                            BackspaceKey::Auto => "Auto",
                         })
                         .show_ui(ui, |ui| {
                             ui.selectable_value(&mut self.config.backspace_key, BackspaceKey::Del, "DEL (0x7F)");
                             ui.selectable_value(&mut self.config.backspace_key, BackspaceKey::CtrlH, "Ctrl+H (0x08)");
+                            ui.selectable_value(&mut self.config.backspace_key, BackspaceKey::Auto, "Auto")
+                                .on_hover_text("Send DEL, switching to Ctrl+H if the server echoes it as ^?");
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Delete Key:");
+                    egui::ComboBox::from_id_salt("delete")
+                        .selected_text(match self.config.delete_key {
+                            DeleteKey::Vt220 => "VT220 (ESC [3~)",
+                            DeleteKey::Del => "DEL (0x7F)",
+                            DeleteKey::CtrlH => "Ctrl+H (0x08)",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.delete_key, DeleteKey::Vt220, "VT220 (ESC [3~)");
+                            ui.selectable_value(&mut self.config.delete_key, DeleteKey::Del, "DEL (0x7F)");
+                            ui.selectable_value(&mut self.config.delete_key, DeleteKey::CtrlH, "Ctrl+H (0x08)");
                         });
                 });
+                ui.checkbox(&mut self.config.swap_backspace_delete, "Swap Backspace and Delete keys");
+                ui.checkbox(&mut self.config.block_ctrl_s, "Don't send Ctrl+S (XOFF pauses output until Ctrl+Q)");
                 ui.horizontal(|ui| {
                     ui.label("Resize Method:");
                     egui::ComboBox::from_id_salt("resize")
@@ -402,50 +643,121 @@ impl ConfigDialog {
                             ui.selectable_value(&mut self.config.line_ending, LineEnding::Cr, "CR");
                         });
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Encoding:");
+                    egui::ComboBox::from_id_salt("encoding")
+                        .selected_text(match self.config.encoding {
+                            Encoding::Utf8 => "UTF-8",
+                            Encoding::Latin1 => "Latin-1",
+                            Encoding::Cp437 => "Raw (CP437)",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.encoding, Encoding::Utf8, "UTF-8");
+                            ui.selectable_value(&mut self.config.encoding, Encoding::Latin1, "Latin-1");
+                            ui.selectable_value(&mut self.config.encoding, Encoding::Cp437, "Raw (CP437)");
+                        });
+                });
+                ui.checkbox(&mut self.config.lone_cr_as_newline, "Treat received CR without LF as a line break");
+                ui.checkbox(&mut self.config.sixel_graphics, "Display Sixel graphics");
+                ui.horizontal(|ui| {
+                    ui.label("Prompt Pattern:");
+                    ui.add(egui::TextEdit::singleline(&mut self.config.prompt_pattern)
+                        .hint_text(r"regex, e.g. ^\S+@\S+:.*[$#]\s")
+                        .desired_width(INPUT_WIDTH));
+                });
+                if !self.config.prompt_pattern.is_empty() && regex::Regex::new(&self.config.prompt_pattern).is_err() {
+                    ui.label(RichText::new("Invalid regular expression").color(Color32::from_rgb(244, 67, 54)));
+                }
+                ui.label(RichText::new("Heuristic for shells without OSC 133: lines matching the pattern are treated as prompts").weak());
+                ui.horizontal(|ui| {
+                    ui.label("Break Sequence:");
+                    ui.add(egui::TextEdit::singleline(&mut self.config.break_sequence)
+                        .hint_text("e.g. ~break or \\x00 (escapes: \\xNN \\e \\r \\n)")
+                        .desired_width(INPUT_WIDTH));
+                });
             });
             // Connection Options section
             let header = egui::CollapsingHeader::new("Connection Options");
             header.show(ui, |ui| {
-                ui.checkbox(&mut self.config.keep_alive, "Enable keep-alive");
-                ui.checkbox(&mut self.config.compression, "Enable compression");
-                ui.horizontal(|ui| {
-                    ui.label("Timeout (seconds):");
-                    let mut secs = self.config.timeout.as_secs() as u32;
-                    if ui.add(egui::DragValue::new(&mut secs).range(1..=300)).changed() {
-                        self.config.timeout = std::time::Duration::from_secs(secs as u64);
-                    }
-                });
-                // Gateway session selection
                 ui.horizontal(|ui| {
-                    ui.label("Gateway Session:");
-                    let current_name = self.config.gateway_session
-                        .and_then(|id| persistence.get_session(id))
-                        .map(|s| s.name.clone())
+                    ui.label("Connection Profile:");
+                    let current_name = self.config.profile_id
+                        .and_then(|id| profiles.iter().find(|p| p.id == id))
+                        .map(|p| p.name.clone())
                         .unwrap_or_else(|| "None".to_string());
-                    egui::ComboBox::from_id_salt("gateway_session")
+                    egui::ComboBox::from_id_salt("connection_profile")
                         .selected_text(&current_name)
                         .show_ui(ui, |ui| {
-                            if ui.selectable_label(self.config.gateway_session.is_none(), "None").clicked() {
-                                self.config.gateway_session = None;
-                            }
-                            for session in persistence.sessions.iter() {
-                                // Don't allow selecting self as gateway
-                                if session.id != self.config.id {
-                                    let selected = self.config.gateway_session == Some(session.id);
-                                    if ui.selectable_label(selected, &session.name).clicked() {
-                                        self.config.gateway_session = Some(session.id);
-                                    }
-                                }
+                            ui.selectable_value(&mut self.config.profile_id, None, "None");
+                            for profile in profiles {
+                                ui.selectable_value(&mut self.config.profile_id, Some(profile.id), &profile.name);
                             }
                         });
                 });
+                // Show the profile's values for fields this session does not override
+                let profile = self.config.profile_id.and_then(|id| profiles.iter().find(|p| p.id == id));
+                let has_profile = profile.is_some();
+                if has_profile {
+                    self.config = self.config.with_profile(profile);
+                    ui.label(RichText::new("Fields without \"Override\" checked come from the profile").weak());
+                }
+                let overrides = &mut self.config.profile_overrides;
+                profile_field(ui, has_profile, &mut overrides.keep_alive, |ui| {
+                    ui.checkbox(&mut self.config.keep_alive, "Enable keep-alive");
+                });
+                ui.horizontal(|ui| {
+                    let mut idle_enabled = self.config.idle_disconnect_minutes.is_some();
+                    if ui.checkbox(&mut idle_enabled, "Disconnect after idle (minutes):").changed() {
+                        self.config.idle_disconnect_minutes = idle_enabled.then_some(DEFAULT_IDLE_DISCONNECT_MINUTES);
+                    }
+                    if let Some(minutes) = &mut self.config.idle_disconnect_minutes {
+                        ui.add(egui::DragValue::new(minutes).range(1..=1440));
+                    }
+                });
+                if self.config.idle_disconnect_minutes.is_some() {
+                    ui.checkbox(&mut self.config.idle_output_counts_as_activity, "Server output counts as activity (tailing logs)");
+                }
+                let overrides = &mut self.config.profile_overrides;
+                profile_field(ui, has_profile, &mut overrides.compression, |ui| {
+                    ui.checkbox(&mut self.config.compression, "Enable compression");
+                });
+                profile_field(ui, has_profile, &mut overrides.timeout, |ui| {
+                    ui.label("Timeout (seconds):");
+                    timeout_drag(ui, &mut self.config.timeout);
+                });
+                if !is_local {
+                    ui.checkbox(&mut self.config.check_reachable, "Check the host is reachable before connecting")
+                        .on_hover_text("Fails fast when the host is down, at the cost of an extra round-trip");
+                }
+                profile_field(ui, has_profile, &mut overrides.gateway_session, |ui| {
+                    ui.label("Gateway Session:");
+                    // Don't allow selecting self as gateway
+                    gateway_session_combo(ui, "gateway_session", &mut self.config.gateway_session, Some(self.config.id), persistence);
+                });
                 ui.horizontal(|ui| {
-                    ui.label("Screen Session:");
+                    ui.label("Multiplexer:");
+                    egui::ComboBox::from_id_salt("multiplexer")
+                        .selected_text(match self.config.multiplexer {
+                            Multiplexer::Screen => "screen",
+                            Multiplexer::Tmux => "tmux",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.multiplexer, Multiplexer::Screen, "screen");
+                            ui.selectable_value(&mut self.config.multiplexer, Multiplexer::Tmux, "tmux");
+                        });
                     let mut screen = self.config.screen_session.clone().unwrap_or_default();
-                    if ui.add(egui::TextEdit::singleline(&mut screen).hint_text("Session name").desired_width(INPUT_WIDTH)).changed() {
+                    if ui.add(egui::TextEdit::singleline(&mut screen).hint_text("Session name").desired_width(INPUT_WIDTH - 80.0)).changed() {
                         self.config.screen_session = if screen.is_empty() { None } else { Some(screen) };
                     }
                 });
+                if self.config.screen_session.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.label("Attach Command:");
+                        ui.add(egui::TextEdit::singleline(&mut self.config.multiplexer_command)
+                            .hint_text(self.config.multiplexer.default_command())
+                            .desired_width(INPUT_WIDTH));
+                    });
+                }
             });
             // Port Forwarding section
             let header = egui::CollapsingHeader::new("Port Forwarding");
@@ -503,6 +815,29 @@ impl ConfigDialog {
                     self.config.remote_forwards.remove(i);
                 }
             });
+            // Login Script section
+            let header = egui::CollapsingHeader::new("Login Script");
+            header.show(ui, |ui| {
+                ui.label(RichText::new("After connecting, wait for each pattern in turn and send its response (\\r = Enter)").weak());
+                let mut to_remove = None;
+                for (i, step) in self.config.login_script.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}.", i + 1));
+                        ui.add(egui::TextEdit::singleline(&mut step.pattern).desired_width(120.0).hint_text("Wait for"));
+                        ui.label("→");
+                        ui.add(egui::TextEdit::singleline(&mut step.response).desired_width(120.0).hint_text("Send"));
+                        if ui.small_button("❌").clicked() {
+                            to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = to_remove {
+                    self.config.login_script.remove(i);
+                }
+                if ui.button("Add Step").clicked() {
+                    self.config.login_script.push(ExpectStep::default());
+                }
+            });
         });
         ui.add_space(8.0);
         // Show validation error if any
@@ -510,8 +845,8 @@ impl ConfigDialog {
             ui.label(RichText::new(error).color(Color32::from_rgb(244, 67, 54)));
         }
         ui.separator();
-        // Check for Enter key to submit
-        let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+        // Enter submits only when no text field or value is being edited
+        let enter_pressed = !editing_text && ui.input(|i| i.key_pressed(egui::Key::Enter));
         ui.horizontal(|ui| {
             if ui.button("Cancel").clicked() {
                 result = Some(DialogResult::Cancelled);
@@ -522,7 +857,9 @@ impl ConfigDialog {
                     DialogMode::New => "Create",
                     DialogMode::Edit(_) => "Save",
                     DialogMode::EditConnection(_) => "Apply",
+                    DialogMode::ReconnectAs(_) => "Reconnect",
                     DialogMode::QuickConnect => "Connect",
+                    DialogMode::SaveConnection(_) => "Save",
                 };
                 if ui.button(button_text).clicked() || enter_pressed {
                     match self.validate(persistence) {
@@ -540,20 +877,45 @@ impl ConfigDialog {
         result
     }
 
+    fn key_file_row(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Key File:");
+            let path_str = self.config.private_key_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            ui.label(&path_str);
+            if ui.button("Browse...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Private Key", &["pem", "ppk", "key", ""])
+                    .pick_file()
+                {
+                    self.config.private_key_path = Some(path);
+                }
+            }
+        });
+    }
+
     fn validate(&self, persistence: &PersistenceManager) -> Result<(), String> {
+        if let DialogMode::ReconnectAs(_) = &self.mode {
+            if self.config.username.trim().is_empty() {
+                return Err("Username is required".to_string());
+            }
+            return Ok(());
+        }
         // Skip connection field validation for EditConnection mode (runtime settings only)
         if !matches!(self.mode, DialogMode::EditConnection(_)) {
             if self.config.name.trim().is_empty() {
                 return Err("Session name is required".to_string());
             }
-            if self.config.host.trim().is_empty() {
+            if self.config.kind == SessionKind::Ssh && self.config.host.trim().is_empty() {
                 return Err("Host is required".to_string());
             }
-            if self.config.username.trim().is_empty() {
+            if self.config.kind == SessionKind::Ssh && self.config.username.trim().is_empty() {
                 return Err("Username is required".to_string());
             }
-            // Check for duplicate names (only for New mode, not Edit or QuickConnect)
-            if let DialogMode::New = &self.mode {
+            // Check for duplicate names (only for new stored sessions, not Edit or QuickConnect)
+            if let DialogMode::New | DialogMode::SaveConnection(_) = &self.mode {
                 let name_lower = self.config.name.trim().to_lowercase();
                 for session in &persistence.sessions {
                     if session.name.trim().to_lowercase() == name_lower {
@@ -579,3 +941,49 @@ pub enum DialogResult {
     Confirmed(SessionConfig),
     Cancelled,
 }
+
+/// A setting a connection profile can supply; only editable when the session overrides it
+fn profile_field(ui: &mut Ui, has_profile: bool, overridden: &mut bool, add_contents: impl FnOnce(&mut Ui)) {
+    ui.horizontal(|ui| {
+        if has_profile {
+            ui.checkbox(overridden, "Override");
+        }
+        ui.add_enabled_ui(!has_profile || *overridden, add_contents);
+    });
+}
+
+pub fn timeout_drag(ui: &mut Ui, timeout: &mut std::time::Duration) {
+    let mut secs = timeout.as_secs() as u32;
+    if ui.add(egui::DragValue::new(&mut secs).range(1..=300)).changed() {
+        *timeout = std::time::Duration::from_secs(secs as u64);
+    }
+}
+
+/// Picker for a stored session to tunnel through; `exclude` keeps a session from being its own gateway
+pub fn gateway_session_combo(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    gateway: &mut Option<Uuid>,
+    exclude: Option<Uuid>,
+    persistence: &PersistenceManager,
+) {
+    let current_name = gateway
+        .and_then(|id| persistence.get_session(id))
+        .map(|s| s.name.clone())
+        .unwrap_or_else(|| "None".to_string());
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(&current_name)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(gateway.is_none(), "None").clicked() {
+                *gateway = None;
+            }
+            for session in persistence.sessions.iter() {
+                if Some(session.id) != exclude {
+                    let selected = *gateway == Some(session.id);
+                    if ui.selectable_label(selected, &session.name).clicked() {
+                        *gateway = Some(session.id);
+                    }
+                }
+            }
+        });
+}
@@ -0,0 +1,277 @@
+use crate::config::{AuthMethod, SessionConfig, DEFAULT_PORT};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// PuTTY keeps its saved sessions here (Windows registry, or files on other platforms)
+#[cfg(windows)]
+const PUTTY_REGISTRY_KEY: &str = r"HKCU\Software\SimonTatham\PuTTY\Sessions";
+#[cfg(not(windows))]
+const PUTTY_SESSIONS_DIR: &str = ".putty/sessions";
+// PuTTY's template for new sessions, not a host
+const PUTTY_DEFAULT_SESSION: &str = "Default Settings";
+
+/// Other clients whose saved sessions can be imported
+#[derive(Clone, Copy)]
+pub enum ImportSource {
+    SshConfig,
+    Putty,
+}
+
+/// Sessions for every concrete `Host` alias in ~/.ssh/config
+pub fn ssh_config_sessions(template: &SessionConfig) -> Result<Vec<SessionConfig>> {
+    let path = dirs::home_dir().context("Could not find home directory")?.join(".ssh").join("config");
+    let content = fs::read_to_string(&path).with_context(|| format!("Could not read {}", path.display()))?;
+    Ok(parse_ssh_config(&content, template))
+}
+
+/// Sessions for every concrete `Host` alias in an ssh_config file.
+/// Wildcard patterns are not hosts; `Host *` values fill in what an alias leaves unset.
+/// Settings not found in the file come from `template`.
+pub fn parse_ssh_config(content: &str, template: &SessionConfig) -> Vec<SessionConfig> {
+    let mut blocks: Vec<(Vec<String>, HashMap<String, String>)> = Vec::new();
+    let mut in_match = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Keyword and value are separated by whitespace and/or a single '='
+        let Some(split) = line.find(|c: char| c.is_whitespace() || c == '=') else {
+            continue;
+        };
+        let keyword = line[..split].to_ascii_lowercase();
+        let value = line[split..].trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim().trim_matches('"');
+        match keyword.as_str() {
+            "host" => {
+                in_match = false;
+                blocks.push((value.split_whitespace().map(String::from).collect(), HashMap::new()));
+            }
+            // Match conditions can't be evaluated here, so their settings are skipped
+            "match" => in_match = true,
+            _ if !in_match => {
+                if let Some((_, settings)) = blocks.last_mut() {
+                    // The first value given for a keyword wins, as in OpenSSH
+                    settings.entry(keyword).or_insert_with(|| value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    let defaults = blocks.iter()
+        .find(|(patterns, _)| patterns.iter().any(|p| p == "*"))
+        .map(|(_, settings)| settings.clone())
+        .unwrap_or_default();
+    let mut sessions = Vec::new();
+    for (patterns, settings) in &blocks {
+        for alias in patterns.iter().filter(|p| !p.contains(['*', '?', '!'])) {
+            let get = |key: &str| settings.get(key).or_else(|| defaults.get(key));
+            let mut session = SessionConfig {
+                id: Uuid::new_v4(),
+                name: alias.clone(),
+                host: get("hostname").cloned().unwrap_or_else(|| alias.clone()),
+                port: get("port").and_then(|p| p.parse().ok()).unwrap_or(DEFAULT_PORT),
+                username: get("user").cloned().unwrap_or_default(),
+                ..template.clone()
+            };
+            if let Some(identity) = get("identityfile") {
+                session.auth_method = AuthMethod::PrivateKey;
+                session.private_key_path = Some(expand_home(identity));
+            }
+            sessions.push(session);
+        }
+    }
+    sessions
+}
+
+/// SSH sessions saved in PuTTY (other protocols are skipped)
+pub fn putty_sessions(template: &SessionConfig) -> Result<Vec<SessionConfig>> {
+    let mut sessions: Vec<SessionConfig> = read_putty_sessions()?
+        .into_iter()
+        .filter(|(name, settings)| {
+            name != PUTTY_DEFAULT_SESSION && settings.get("Protocol").is_none_or(|p| p == "ssh")
+        })
+        .filter_map(|(name, settings)| putty_session(name, &settings, template))
+        .collect();
+    sessions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(sessions)
+}
+
+fn putty_session(name: String, settings: &HashMap<String, String>, template: &SessionConfig) -> Option<SessionConfig> {
+    let host_name = settings.get("HostName").filter(|h| !h.is_empty())?;
+    // PuTTY accepts user@host in the host field
+    let (user, host) = match host_name.rsplit_once('@') {
+        Some((user, host)) => (user.to_string(), host.to_string()),
+        None => (settings.get("UserName").cloned().unwrap_or_default(), host_name.clone()),
+    };
+    let mut session = SessionConfig {
+        id: Uuid::new_v4(),
+        name,
+        host,
+        port: settings.get("PortNumber").and_then(|p| parse_putty_number(p)).unwrap_or(DEFAULT_PORT),
+        username: user,
+        ..template.clone()
+    };
+    // .ppk keys need converting to OpenSSH format before they can be used here
+    if let Some(key) = settings.get("PublicKeyFile").filter(|k| !k.is_empty() && !k.to_ascii_lowercase().ends_with(".ppk")) {
+        session.auth_method = AuthMethod::PrivateKey;
+        session.private_key_path = Some(PathBuf::from(key));
+    }
+    Some(session)
+}
+
+/// Saved session settings by session name, from ~/.putty/sessions
+#[cfg(not(windows))]
+fn read_putty_sessions() -> Result<Vec<(String, HashMap<String, String>)>> {
+    let dir = dirs::home_dir().context("Could not find home directory")?.join(PUTTY_SESSIONS_DIR);
+    let entries = fs::read_dir(&dir).with_context(|| format!("Could not read {}", dir.display()))?;
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let settings = content.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        sessions.push((percent_decode(&entry.file_name().to_string_lossy()), settings));
+    }
+    Ok(sessions)
+}
+
+/// Saved session settings by session name, from `reg query` on PuTTY's registry key
+#[cfg(windows)]
+fn read_putty_sessions() -> Result<Vec<(String, HashMap<String, String>)>> {
+    let output = std::process::Command::new("reg")
+        .args(["query", PUTTY_REGISTRY_KEY, "/s"])
+        .output()
+        .context("Could not run reg.exe")?;
+    if !output.status.success() {
+        anyhow::bail!("No PuTTY sessions found in the registry");
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let session_prefix = format!("{}\\", PUTTY_REGISTRY_KEY.replacen("HKCU", "HKEY_CURRENT_USER", 1));
+    let mut sessions: Vec<(String, HashMap<String, String>)> = Vec::new();
+    for line in text.lines() {
+        // Each session is a subkey; its values follow as indented "Name    REG_TYPE    Data" lines
+        if let Some(name) = line.strip_prefix(&session_prefix) {
+            sessions.push((percent_decode(name), HashMap::new()));
+        } else if let Some((_, settings)) = sessions.last_mut() {
+            let mut parts = line.trim().splitn(3, "    ");
+            if let (Some(name), Some(_), Some(data)) = (parts.next(), parts.next(), parts.next()) {
+                settings.insert(name.to_string(), data.trim().to_string());
+            }
+        }
+    }
+    Ok(sessions)
+}
+
+/// Decimal in session files, 0x-prefixed hex in `reg query` output
+fn parse_putty_number(value: &str) -> Option<u16> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// PuTTY escapes session names like URLs (%20 for space)
+fn percent_decode(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()).filter(|_| bytes[i] == b'%') {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# Personal machines
+Host web db-primary db
+    HostName 10.0.0.5
+    Port 2222
+    User deploy
+
+Host *.internal !bastion
+    User ops
+
+Host bastion
+    hostname = bastion.example.com
+    IdentityFile /keys/bastion
+
+Match host build
+    User nobody
+
+Host *
+    User fallback
+    IdentityFile ~/.ssh/id_ed25519
+";
+
+    fn session<'a>(sessions: &'a [SessionConfig], name: &str) -> &'a SessionConfig {
+        sessions.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn every_concrete_alias_becomes_a_session() {
+        let sessions = parse_ssh_config(CONFIG, &SessionConfig::default());
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "db-primary", "db", "bastion"]);
+    }
+
+    #[test]
+    fn host_settings_fill_the_session() {
+        let sessions = parse_ssh_config(CONFIG, &SessionConfig::default());
+        for name in ["web", "db-primary", "db"] {
+            let session = session(&sessions, name);
+            assert_eq!(session.host, "10.0.0.5");
+            assert_eq!(session.port, 2222);
+            assert_eq!(session.username, "deploy");
+        }
+        let bastion = session(&sessions, "bastion");
+        assert_eq!(bastion.host, "bastion.example.com");
+        assert_eq!(bastion.port, DEFAULT_PORT);
+        assert!(matches!(bastion.auth_method, AuthMethod::PrivateKey));
+        assert_eq!(bastion.private_key_path, Some(PathBuf::from("/keys/bastion")));
+    }
+
+    #[test]
+    fn wildcard_host_fills_unset_values() {
+        let sessions = parse_ssh_config(CONFIG, &SessionConfig::default());
+        // Match blocks are skipped, so "User nobody" never applies
+        assert_eq!(session(&sessions, "bastion").username, "fallback");
+        let web = session(&sessions, "web");
+        assert!(matches!(web.auth_method, AuthMethod::PrivateKey));
+        assert!(web.private_key_path.as_ref().unwrap().ends_with(".ssh/id_ed25519"));
+    }
+
+    #[test]
+    fn alias_without_hostname_connects_to_itself() {
+        let sessions = parse_ssh_config("Host router\n", &SessionConfig::default());
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].host, "router");
+        assert_eq!(sessions[0].port, DEFAULT_PORT);
+    }
+}
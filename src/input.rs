@@ -1,4 +1,16 @@
-use egui::{Key, Modifiers};
+use egui::{Key, Modifiers, PointerButton};
+
+// Button numbers in xterm mouse reports
+pub const MOUSE_NO_BUTTON: u8 = 3;
+pub const MOUSE_WHEEL_UP: u8 = 64;
+pub const MOUSE_WHEEL_DOWN: u8 = 65;
+// Added to the button number for held modifiers and for motion
+const MOUSE_SHIFT: u8 = 4;
+const MOUSE_ALT: u8 = 8;
+const MOUSE_CTRL: u8 = 16;
+const MOUSE_MOTION: u8 = 32;
+// Legacy reports send coordinates as single bytes offset by 32
+const X10_MAX_COORDINATE: usize = 223;
 
 // Input handling result
 pub enum InputResult {
@@ -26,9 +38,10 @@ impl InputHandler {
         key: Key,
         modifiers: Modifiers,
         backspace_seq: &[u8],
+        delete_seq: &[u8],
     ) -> InputResult {
         // Forward ALL keys to terminal - no app shortcuts
-        let bytes = self.key_to_bytes(key, modifiers, backspace_seq);
+        let bytes = self.key_to_bytes(key, modifiers, backspace_seq, delete_seq);
         if !bytes.is_empty() {
             InputResult::Forward(bytes)
         } else {
@@ -36,8 +49,24 @@ impl InputHandler {
         }
     }
 
-    fn key_to_bytes(&self, key: Key, modifiers: Modifiers, backspace_seq: &[u8]) -> Vec<u8> {
+    fn key_to_bytes(&self, key: Key, modifiers: Modifiers, backspace_seq: &[u8], delete_seq: &[u8]) -> Vec<u8> {
         let app_mode = self.cursor_keys_application;
+        // Shifted cursor and Home/End keys carry xterm's modifier parameter: 1 + Shift(1) + Ctrl(4)
+        if modifiers.shift && !modifiers.alt {
+            let final_byte = match key {
+                Key::ArrowUp => Some('A'),
+                Key::ArrowDown => Some('B'),
+                Key::ArrowRight => Some('C'),
+                Key::ArrowLeft => Some('D'),
+                Key::Home => Some('H'),
+                Key::End => Some('F'),
+                _ => None,
+            };
+            if let Some(final_byte) = final_byte {
+                let modifier = if modifiers.ctrl { 6 } else { 2 };
+                return format!("\x1b[1;{}{}", modifier, final_byte).into_bytes();
+            }
+        }
         // Handle special keys first
         match key {
             Key::Enter => return vec![b'\r'],
@@ -97,7 +126,7 @@ impl InputHandler {
                 };
             }
             Key::Insert => return vec![0x1b, b'[', b'2', b'~'],
-            Key::Delete => return vec![0x1b, b'[', b'3', b'~'],
+            Key::Delete => return delete_seq.to_vec(),
             Key::PageUp => return vec![0x1b, b'[', b'5', b'~'],
             Key::PageDown => return vec![0x1b, b'[', b'6', b'~'],
             Key::F1 => return vec![0x1b, b'O', b'P'],
@@ -226,8 +255,106 @@ impl InputHandler {
     }
 }
 
+/// xterm button number for a pointer button
+pub fn mouse_button_code(button: PointerButton) -> Option<u8> {
+    match button {
+        PointerButton::Primary => Some(0),
+        PointerButton::Middle => Some(1),
+        PointerButton::Secondary => Some(2),
+        _ => None,
+    }
+}
+
+/// Bytes reporting a mouse event at a 0-based screen cell, in SGR form (mode 1006) or the
+/// legacy X10 form. Legacy reports can't reach past cell 223 and don't say which button was
+/// released; None when the cell can't be encoded.
+pub fn mouse_report(button: u8, pressed: bool, motion: bool, modifiers: Modifiers, col: usize, row: usize, sgr: bool) -> Option<Vec<u8>> {
+    let mut code = button;
+    if modifiers.shift {
+        code += MOUSE_SHIFT;
+    }
+    if modifiers.alt {
+        code += MOUSE_ALT;
+    }
+    if modifiers.ctrl {
+        code += MOUSE_CTRL;
+    }
+    if motion {
+        code += MOUSE_MOTION;
+    }
+    if sgr {
+        let action = if pressed { 'M' } else { 'm' };
+        return Some(format!("\x1b[<{};{};{}{}", code, col + 1, row + 1, action).into_bytes());
+    }
+    if col >= X10_MAX_COORDINATE || row >= X10_MAX_COORDINATE {
+        return None;
+    }
+    if !pressed {
+        code |= MOUSE_NO_BUTTON;
+    }
+    Some(vec![0x1B, b'[', b'M', 32 + code, 33 + col as u8, 33 + row as u8])
+}
+
+/// One line for the key input log: the key chord and the bytes it produced
+pub fn describe_key_input(key: Key, modifiers: Modifiers, result: &InputResult) -> String {
+    let chord = egui::KeyboardShortcut::new(modifiers, key).format(&egui::ModifierNames::NAMES, cfg!(target_os = "macos"));
+    let bytes = match result {
+        InputResult::Forward(bytes) => bytes,
+        InputResult::Ignored => return format!("{} -> (nothing sent)", chord),
+    };
+    let mut escaped = String::new();
+    for &byte in bytes {
+        match byte {
+            0x1B => escaped.push_str("\\e"),
+            b'\\' => escaped.push_str("\\\\"),
+            0x20..=0x7E => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\x{:02X}", byte)),
+        }
+    }
+    format!("{} -> {}", chord, escaped)
+}
+
 impl Default for InputHandler {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgr_reports_press_and_release_with_one_based_cells() {
+        assert_eq!(mouse_report(0, true, false, Modifiers::NONE, 4, 9, true).unwrap(), b"\x1b[<0;5;10M");
+        assert_eq!(mouse_report(2, false, false, Modifiers::NONE, 4, 9, true).unwrap(), b"\x1b[<2;5;10m");
+    }
+
+    #[test]
+    fn sgr_reports_reach_past_the_legacy_limit() {
+        assert_eq!(mouse_report(0, true, false, Modifiers::NONE, 300, 250, true).unwrap(), b"\x1b[<0;301;251M");
+    }
+
+    #[test]
+    fn modifier_and_motion_bits_add_to_the_button_code() {
+        assert_eq!(mouse_report(0, true, false, Modifiers::SHIFT, 0, 0, true).unwrap(), b"\x1b[<4;1;1M");
+        assert_eq!(mouse_report(0, true, false, Modifiers::ALT, 0, 0, true).unwrap(), b"\x1b[<8;1;1M");
+        assert_eq!(mouse_report(0, true, false, Modifiers::CTRL, 0, 0, true).unwrap(), b"\x1b[<16;1;1M");
+        assert_eq!(mouse_report(1, true, true, Modifiers::SHIFT | Modifiers::CTRL, 0, 0, true).unwrap(), b"\x1b[<53;1;1M");
+        assert_eq!(mouse_report(MOUSE_WHEEL_UP, true, false, Modifiers::NONE, 0, 0, true).unwrap(), b"\x1b[<64;1;1M");
+    }
+
+    #[test]
+    fn legacy_reports_offset_coordinates_and_hide_the_released_button() {
+        assert_eq!(mouse_report(0, true, false, Modifiers::NONE, 4, 9, false).unwrap(), vec![0x1B, b'[', b'M', 32, 37, 42]);
+        // Release is button 3 whichever was let go, keeping the modifier bits
+        assert_eq!(mouse_report(2, false, false, Modifiers::CTRL, 0, 0, false).unwrap(), vec![0x1B, b'[', b'M', 32 + 16 + 3, 33, 33]);
+    }
+
+    #[test]
+    fn legacy_reports_stop_at_cell_223() {
+        assert!(mouse_report(0, true, false, Modifiers::NONE, 222, 222, false).is_some());
+        assert_eq!(mouse_report(0, true, false, Modifiers::NONE, 223, 0, false), None);
+        assert_eq!(mouse_report(0, true, false, Modifiers::NONE, 0, 223, false), None);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::ssh::connection::{ConnectionState, SshCommand, SshEvent};
+use crate::config::SessionConfig;
+use crate::debug;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+// Local shell constants
+#[cfg(unix)]
+const READ_BUFFER_SIZE: usize = 4096;
+#[cfg(unix)]
+const COMMAND_POLL_INTERVAL_MS: u64 = 10;
+#[cfg(unix)]
+const INITIAL_COLS: u16 = 80;
+#[cfg(unix)]
+const INITIAL_ROWS: u16 = 24;
+#[cfg(unix)]
+const FALLBACK_SHELL: &str = "/bin/sh";
+
+/// Run a shell on this machine in a pseudo-terminal, speaking the same
+/// event/command model as an SSH connection thread
+pub fn connection_thread(
+    config: SessionConfig,
+    state: Arc<Mutex<ConnectionState>>,
+    event_tx: Sender<SshEvent>,
+    command_rx: Receiver<SshCommand>,
+) {
+    *state.lock().unwrap() = ConnectionState::Connecting;
+    let shell = if config.local_shell.trim().is_empty() {
+        default_shell()
+    } else {
+        config.local_shell.trim().to_string()
+    };
+    debug::log(&format!("[LOCAL {}] Starting {}", config.id, shell));
+    match pty::Pty::spawn(&shell) {
+        Ok(pty) => {
+            *state.lock().unwrap() = ConnectionState::Connected;
+            let _ = event_tx.send(SshEvent::Connected);
+            let natural = serve(&config, pty, &event_tx, &command_rx);
+            let _ = event_tx.send(SshEvent::Disconnected { natural });
+        }
+        Err(e) => {
+            let error_msg = format!("{:#}", e);
+            debug::log(&format!("[LOCAL {}] Error: {}", config.id, error_msg));
+            *state.lock().unwrap() = ConnectionState::Error(error_msg.clone());
+            let _ = event_tx.send(SshEvent::Error(error_msg));
+        }
+    }
+    debug::log(&format!("[LOCAL {}] Shell thread ended", config.id));
+    *state.lock().unwrap() = ConnectionState::Disconnected;
+}
+
+/// Forward commands to the shell until it exits or the tab disconnects; returns whether the close was natural
+#[cfg(unix)]
+fn serve(config: &SessionConfig, mut pty: pty::Pty, event_tx: &Sender<SshEvent>, command_rx: &Receiver<SshCommand>) -> bool {
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+    let exited = Arc::new(AtomicBool::new(false));
+    match pty.master.try_clone() {
+        Ok(mut reader) => {
+            let event_tx = event_tx.clone();
+            let exited = exited.clone();
+            // Reads block, so output gets its own thread; EOF or EIO means every process on the pty is gone
+            std::thread::spawn(move || {
+                let mut buffer = [0u8; READ_BUFFER_SIZE];
+                while let Ok(n) = reader.read(&mut buffer) {
+                    if n == 0 || event_tx.send(SshEvent::Data(buffer[..n].to_vec())).is_err() {
+                        break;
+                    }
+                }
+                exited.store(true, Ordering::SeqCst);
+            });
+        }
+        Err(e) => {
+            debug::log(&format!("[LOCAL {}] Failed to read pty: {:?}", config.id, e));
+            return false;
+        }
+    }
+    loop {
+        if exited.load(Ordering::SeqCst) {
+            return true;
+        }
+        match command_rx.recv_timeout(Duration::from_millis(COMMAND_POLL_INTERVAL_MS)) {
+            Ok(SshCommand::Write(data)) => {
+                if let Err(e) = pty.master.write_all(&data) {
+                    debug::log(&format!("[LOCAL {}] Write error: {:?}", config.id, e));
+                    return false;
+                }
+            }
+            Ok(SshCommand::Resize { cols, rows }) => pty.resize(cols as u16, rows as u16),
+            Ok(SshCommand::Disconnect) => return true,
+            Ok(SshCommand::Upload { .. }) => {
+                let _ = event_tx.send(SshEvent::UploadFinished(Err(String::from("Uploads need an SSH connection"))));
+            }
+            // Local shells don't share a connection, so there is nothing to open another shell on
+            Ok(SshCommand::OpenShell(_)) => {}
+            // Local shells never ask for credentials
+            Ok(SshCommand::AuthResponse(_)) => {}
+            Ok(SshCommand::Signal(name)) => pty.signal(&name),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return false,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn serve(_config: &SessionConfig, _pty: pty::Pty, _event_tx: &Sender<SshEvent>, _command_rx: &Receiver<SshCommand>) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn default_shell() -> String {
+    std::env::var("SHELL").ok().filter(|shell| !shell.is_empty()).unwrap_or_else(|| String::from(FALLBACK_SHELL))
+}
+
+#[cfg(not(unix))]
+fn default_shell() -> String {
+    std::env::var("COMSPEC").unwrap_or_else(|_| String::from("cmd.exe"))
+}
+
+#[cfg(unix)]
+mod pty {
+    use super::{FALLBACK_SHELL, INITIAL_COLS, INITIAL_ROWS};
+    use anyhow::{Context, Result};
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::os::fd::FromRawFd;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+
+    /// Master side of a pseudo-terminal with a shell running on the slave side
+    pub struct Pty {
+        pub master: File,
+        child: libc::pid_t,
+    }
+
+    impl Pty {
+        pub fn spawn(shell: &str) -> Result<Self> {
+            // Everything the child needs is prepared before fork; only async-signal-safe calls follow it
+            // A program name or path (spaces and all) runs directly; anything else is a command
+            // line for the system shell, which takes care of quoting
+            let args: Vec<CString> = if Path::new(shell).is_file() || !shell.contains(char::is_whitespace) {
+                vec![CString::new(shell).context("Invalid shell command")?]
+            } else {
+                [FALLBACK_SHELL, "-c", &format!("exec {}", shell)]
+                    .into_iter()
+                    .map(CString::new)
+                    .collect::<Result<_, _>>()
+                    .context("Invalid shell command")?
+            };
+            let program = args.first().context("No shell given")?;
+            let program = CString::new(find_program(program.as_bytes()).as_os_str().as_bytes())?;
+            let mut argv: Vec<*const libc::c_char> = args.iter().map(|arg| arg.as_ptr()).collect();
+            argv.push(std::ptr::null());
+            let mut env: Vec<CString> = std::env::vars_os()
+                .filter(|(key, _)| key != "TERM" && key != "COLORTERM")
+                .filter_map(|(key, value)| {
+                    let mut entry = key.as_bytes().to_vec();
+                    entry.push(b'=');
+                    entry.extend_from_slice(value.as_bytes());
+                    CString::new(entry).ok()
+                })
+                .collect();
+            env.push(CString::new("TERM=xterm-256color")?);
+            env.push(CString::new("COLORTERM=truecolor")?);
+            let mut envp: Vec<*const libc::c_char> = env.iter().map(|entry| entry.as_ptr()).collect();
+            envp.push(std::ptr::null());
+            let home = dirs::home_dir().and_then(|home| CString::new(home.as_os_str().as_bytes()).ok());
+            let mut master: libc::c_int = -1;
+            let mut slave: libc::c_int = -1;
+            let mut size = libc::winsize { ws_row: INITIAL_ROWS, ws_col: INITIAL_COLS, ws_xpixel: 0, ws_ypixel: 0 };
+            // The winsize pointer is *const on Linux and *mut on macOS; a raw *mut fits both
+            let size_ptr: *mut libc::winsize = &mut size;
+            let opened = unsafe {
+                libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null_mut(), size_ptr)
+            };
+            if opened != 0 {
+                return Err(std::io::Error::last_os_error()).context("Failed to open a pseudo-terminal");
+            }
+            // Keep the pty out of children started later (other shells, bell players); dup2 in
+            // the child clears the flag on the copies it makes
+            unsafe {
+                libc::fcntl(master, libc::F_SETFD, libc::FD_CLOEXEC);
+                libc::fcntl(slave, libc::F_SETFD, libc::FD_CLOEXEC);
+            }
+            let pid = unsafe { libc::fork() };
+            if pid < 0 {
+                let error = std::io::Error::last_os_error();
+                unsafe {
+                    libc::close(master);
+                    libc::close(slave);
+                }
+                return Err(error).context("Failed to start the shell");
+            }
+            if pid == 0 {
+                // Child: become a session leader with the pty as controlling terminal, then exec the shell
+                unsafe {
+                    libc::close(master);
+                    libc::setsid();
+                    libc::ioctl(slave, libc::TIOCSCTTY as _, 0);
+                    libc::dup2(slave, 0);
+                    libc::dup2(slave, 1);
+                    libc::dup2(slave, 2);
+                    if slave > 2 {
+                        libc::close(slave);
+                    }
+                    if let Some(home) = &home {
+                        libc::chdir(home.as_ptr());
+                    }
+                    // The Rust runtime ignores SIGPIPE and that survives exec; restore the defaults
+                    // like std::process::Command does, or pipelines such as `yes | head` break
+                    libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+                    let mut no_signals: libc::sigset_t = std::mem::zeroed();
+                    libc::sigemptyset(&mut no_signals);
+                    libc::sigprocmask(libc::SIG_SETMASK, &no_signals, std::ptr::null_mut());
+                    libc::execve(program.as_ptr(), argv.as_ptr(), envp.as_ptr());
+                    libc::_exit(127);
+                }
+            }
+            unsafe { libc::close(slave) };
+            Ok(Self {
+                master: unsafe { File::from_raw_fd(master) },
+                child: pid,
+            })
+        }
+
+        pub fn resize(&self, cols: u16, rows: u16) {
+            let size = libc::winsize { ws_row: rows, ws_col: cols, ws_xpixel: 0, ws_ypixel: 0 };
+            // The kernel sends SIGWINCH to the foreground process group
+            unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ as _, &size) };
+        }
+
+        /// Signal the pty's foreground process group, as the matching key would
+        pub fn signal(&self, name: &str) {
+            let signal = match name {
+                "INT" => libc::SIGINT,
+                "QUIT" => libc::SIGQUIT,
+                "TSTP" => libc::SIGTSTP,
+                "TERM" => libc::SIGTERM,
+                "HUP" => libc::SIGHUP,
+                _ => return,
+            };
+            let group = unsafe { libc::tcgetpgrp(self.master.as_raw_fd()) };
+            if group > 0 {
+                unsafe { libc::killpg(group, signal) };
+            }
+        }
+    }
+
+    /// execve doesn't search PATH, so a bare program name is looked up first
+    fn find_program(name: &[u8]) -> PathBuf {
+        let name = Path::new(std::ffi::OsStr::from_bytes(name));
+        if name.components().count() > 1 {
+            return name.to_path_buf();
+        }
+        std::env::var_os("PATH")
+            .and_then(|path| std::env::split_paths(&path).map(|dir| dir.join(name)).find(|candidate| candidate.is_file()))
+            .unwrap_or_else(|| name.to_path_buf())
+    }
+
+    impl Drop for Pty {
+        fn drop(&mut self) {
+            let child = self.child;
+            unsafe { libc::kill(child, libc::SIGHUP) };
+            // Reap the shell without holding up the caller
+            std::thread::spawn(move || unsafe {
+                libc::waitpid(child, std::ptr::null_mut(), 0);
+            });
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod pty {
+    use anyhow::Result;
+
+    pub struct Pty;
+
+    impl Pty {
+        pub fn spawn(_shell: &str) -> Result<Self> {
+            anyhow::bail!("Local shell tabs are not supported on this platform yet")
+        }
+    }
+}
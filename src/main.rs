@@ -1,10 +1,15 @@
 mod app;
+mod bell;
+mod clipboard;
 mod config;
 mod config_dialog;
 mod debug;
+mod import;
 mod input;
+mod local_shell;
 mod options_dialog;
 mod persistence;
+mod search;
 mod selection;
 mod session_manager;
 mod session_tree;
@@ -14,6 +19,7 @@ mod tabs;
 mod terminal;
 
 use app::YasshApp;
+use config::HostSpec;
 use std::sync::Mutex;
 
 // Store the debug console process so we can kill it on exit
@@ -90,6 +96,14 @@ fn main() -> eframe::Result<()> {
     setup_debug_logging();
     
     env_logger::init();
+    // An optional `[user@]host[:port]` or `ssh://` URL argument opens that session on launch
+    let launch_target = std::env::args().nth(1).and_then(|arg| {
+        let spec = HostSpec::parse_launch_arg(&arg);
+        if spec.is_none() {
+            debug::log(&format!("[DEBUG APP] Ignoring unrecognized launch argument: {}", arg));
+        }
+        spec
+    });
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -103,7 +117,7 @@ fn main() -> eframe::Result<()> {
     let result = eframe::run_native(
         "Yassh",
         native_options,
-        Box::new(|cc| Ok(Box::new(YasshApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(YasshApp::new(cc, launch_target)))),
     );
     
     // Cleanup debug console on exit
@@ -1,5 +1,10 @@
-use crate::config::{AppConfig, Theme};
-use egui::{Align2, Area, Color32, Order, Ui, Window};
+use crate::config::{
+    AppConfig, ConnectionProfile, TerminalDefaults, Theme, DEFAULT_PASTE_TAB_SPACES, MAX_BELL_MIN_INTERVAL_MS, MIN_BELL_MIN_INTERVAL_MS, MAX_CONNECTING_SESSIONS_LIMIT, MAX_FONT_SIZE,
+    MAX_SCROLLBACK_LINES, MAX_SCROLL_LINES_PER_NOTCH, MAX_TAB_WIDTH, MIN_FONT_SIZE, MIN_SCROLLBACK_LINES,
+};
+use crate::config_dialog::{gateway_session_combo, timeout_drag};
+use crate::persistence::PersistenceManager;
+use egui::{Align2, Area, Color32, Order, RichText, Ui, Window};
 
 // Dialog constants
 const OVERLAY_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 0, 0, 180);
@@ -32,7 +37,7 @@ impl OptionsDialog {
         self.visible
     }
 
-    pub fn show(&mut self, ctx: &egui::Context) -> Option<OptionsResult> {
+    pub fn show(&mut self, ctx: &egui::Context, persistence: &PersistenceManager) -> Option<OptionsResult> {
         if !self.visible {
             return None;
         }
@@ -53,13 +58,15 @@ impl OptionsDialog {
             .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
             .min_width(400.0)
             .show(ctx, |ui| {
-                result = self.show_content(ui);
+                result = self.show_content(ui, persistence);
             });
         result
     }
 
-    fn show_content(&mut self, ui: &mut Ui) -> Option<OptionsResult> {
+    fn show_content(&mut self, ui: &mut Ui, persistence: &PersistenceManager) -> Option<OptionsResult> {
         let mut result = None;
+        // Sampled before any widget runs: a focused text field gives up focus on Enter this frame
+        let editing_text = ui.ctx().wants_keyboard_input();
         ui.heading("Appearance");
         ui.add_space(8.0);
         ui.horizontal(|ui| {
@@ -80,11 +87,97 @@ impl OptionsDialog {
                     ui.selectable_value(&mut self.config.theme, Theme::DarkGreen, "Dark Green");
                 });
         });
+        ui.horizontal(|ui| {
+            ui.label("Selection color:");
+            let theme = self.config.theme;
+            let default = ui.visuals().selection.bg_fill;
+            let custom = self.config.selection_colors.get(&theme).cloned();
+            let current: Color32 = custom.clone().map_or(default, Into::into);
+            let mut color = [current.r(), current.g(), current.b()];
+            if ui.color_edit_button_srgb(&mut color).changed() {
+                self.config.selection_colors.insert(theme, Color32::from_rgb(color[0], color[1], color[2]).into());
+            }
+            if custom.is_some() {
+                if ui.small_button("Theme default").clicked() {
+                    self.config.selection_colors.remove(&theme);
+                }
+            } else {
+                ui.label(RichText::new("(theme default)").weak());
+            }
+        });
+        ui.checkbox(&mut self.config.dim_unfocused, "Dim the terminal when it doesn't have focus");
+        ui.checkbox(&mut self.config.performance_mode, "Performance mode")
+            .on_hover_text("Draw terminal text a line at a time and reuse unchanged lines; faster for busy full-screen output");
+        ui.horizontal(|ui| {
+            ui.label("Emoji/symbol fallback font:");
+            ui.text_edit_singleline(&mut self.config.fallback_font);
+        });
+        ui.add_space(16.0);
+        ui.heading("Behavior");
+        ui.add_space(8.0);
+        ui.checkbox(&mut self.config.confirm_multiline_paste, "Confirm before pasting multiple lines");
+        ui.horizontal(|ui| {
+            let mut expand_tabs = self.config.paste_tab_spaces.is_some();
+            if ui.checkbox(&mut expand_tabs, "Paste tabs as spaces:").changed() {
+                self.config.paste_tab_spaces = expand_tabs.then_some(DEFAULT_PASTE_TAB_SPACES);
+            }
+            if let Some(spaces) = &mut self.config.paste_tab_spaces {
+                ui.add(egui::DragValue::new(spaces).range(1..=MAX_TAB_WIDTH));
+            }
+        });
+        ui.checkbox(&mut self.config.copy_preserve_trailing_spaces, "Preserve trailing spaces when copying");
+        ui.checkbox(&mut self.config.mru_tab_switching, "Ctrl+Tab switches tabs in most recently used order");
+        ui.horizontal(|ui| {
+            ui.label("Lines per wheel notch:");
+            ui.add(egui::DragValue::new(&mut self.config.scroll_lines_per_notch)
+                .range(1..=MAX_SCROLL_LINES_PER_NOTCH));
+        });
+        ui.checkbox(&mut self.config.invert_scroll, "Invert wheel scroll direction");
+        ui.label(RichText::new("Shift+wheel scrolls a page, Ctrl+wheel changes font size").weak());
+        ui.horizontal(|ui| {
+            ui.label("Minimum time between bells (ms):");
+            ui.add(egui::DragValue::new(&mut self.config.bell_min_interval_ms)
+                .range(MIN_BELL_MIN_INTERVAL_MS..=MAX_BELL_MIN_INTERVAL_MS)
+                .speed(10));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Bell sound file:");
+            ui.add(egui::TextEdit::singleline(&mut self.config.bell_sound_file)
+                .hint_text("System sound"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Sessions connecting at once:");
+            ui.add(egui::DragValue::new(&mut self.config.max_connecting_sessions)
+                .range(0..=MAX_CONNECTING_SESSIONS_LIMIT));
+        });
+        ui.label(RichText::new("Others wait until one finishes connecting (0 = no limit)").weak());
+        ui.add_space(16.0);
+        ui.heading("Terminal Defaults");
+        ui.add_space(8.0);
+        let defaults = &mut self.config.terminal_defaults;
+        ui.horizontal(|ui| {
+            ui.label("Scrollback Lines:");
+            ui.add(egui::DragValue::new(&mut defaults.scrollback_lines)
+                .range(MIN_SCROLLBACK_LINES..=MAX_SCROLLBACK_LINES)
+                .speed(100));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Font Size:");
+            ui.add(egui::DragValue::new(&mut defaults.font_size).range(MIN_FONT_SIZE..=MAX_FONT_SIZE));
+        });
+        ui.label(RichText::new("Used for new sessions").weak());
+        if ui.button("Apply to All Open Tabs").clicked() {
+            result = Some(OptionsResult::ApplyTerminalDefaults(defaults.clone()));
+        }
+        ui.add_space(16.0);
+        ui.heading("Connection Profiles");
+        ui.add_space(8.0);
+        self.show_profiles(ui, persistence);
         ui.add_space(16.0);
         ui.separator();
         ui.add_space(8.0);
-        // Check for Enter key to submit
-        let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+        // Enter submits only when no text field or value is being edited
+        let enter_pressed = !editing_text && ui.input(|i| i.key_pressed(egui::Key::Enter));
         ui.horizontal(|ui| {
             if ui.button("Cancel").clicked() {
                 result = Some(OptionsResult::Cancelled);
@@ -99,10 +192,53 @@ impl OptionsDialog {
         });
         result
     }
+
+    /// Shared gateway/keep-alive defaults that sessions can select
+    fn show_profiles(&mut self, ui: &mut Ui, persistence: &PersistenceManager) {
+        let mut to_remove = None;
+        for (i, profile) in self.config.connection_profiles.iter_mut().enumerate() {
+            egui::CollapsingHeader::new(&profile.name)
+                .id_salt(profile.id)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut profile.name);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Gateway Session:");
+                        gateway_session_combo(ui, ("profile_gateway", profile.id), &mut profile.gateway_session, None, persistence);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut profile.keep_alive, "Enable keep-alive every (seconds):");
+                        let mut secs = profile.keepalive_interval.as_secs();
+                        if ui.add_enabled(profile.keep_alive, egui::DragValue::new(&mut secs).range(5..=3600)).changed() {
+                            profile.keepalive_interval = std::time::Duration::from_secs(secs);
+                        }
+                    });
+                    ui.checkbox(&mut profile.compression, "Enable compression");
+                    ui.horizontal(|ui| {
+                        ui.label("Timeout (seconds):");
+                        timeout_drag(ui, &mut profile.timeout);
+                    });
+                    if ui.button("Remove Profile").clicked() {
+                        to_remove = Some(i);
+                    }
+                });
+        }
+        if let Some(i) = to_remove {
+            self.config.connection_profiles.remove(i);
+        }
+        if ui.button("Add Profile").clicked() {
+            let name = format!("Profile {}", self.config.connection_profiles.len() + 1);
+            self.config.connection_profiles.push(ConnectionProfile::new(name));
+        }
+    }
 }
 
 pub enum OptionsResult {
     Saved(AppConfig),
+    // Dialog stays open; the defaults are applied to every open connection
+    ApplyTerminalDefaults(TerminalDefaults),
     Cancelled,
 }
 
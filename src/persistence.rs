@@ -1,5 +1,6 @@
-use crate::config::{AppConfig, SessionConfig, SessionFolder};
+use crate::config::{AppConfig, AutoReconnect, ReconnectPolicy, ScrollOnOutput, SessionConfig, SessionFolder};
 use anyhow::{Context, Result};
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -101,6 +102,23 @@ pub fn save_open_sessions(session_ids: &[Uuid]) -> Result<()> {
     Ok(())
 }
 
+/// Sessions and folder removed by one delete, kept so the delete can be undone
+pub struct DeletedItems {
+    sessions: Vec<SessionConfig>,
+    // The folder and the sessions (with their order inside it) that were moved out of it to the root
+    folder: Option<(SessionFolder, Vec<(Uuid, u32)>)>,
+}
+
+impl DeletedItems {
+    pub fn description(&self) -> String {
+        match (&self.folder, self.sessions.as_slice()) {
+            (Some((folder, _)), _) => format!("Deleted folder '{}'", folder.name),
+            (None, [session]) => format!("Deleted session '{}'", session.name),
+            (None, sessions) => format!("Deleted {} sessions", sessions.len()),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PersistenceManager {
     pub sessions: Vec<SessionConfig>,
@@ -128,9 +146,21 @@ impl PersistenceManager {
             }
         };
         self.assign_missing_folder_orders();
+        self.migrate_manual_reconnect();
+        self.migrate_reset_scroll_on_output();
         Ok(())
     }
 
+    /// "Manual" auto-reconnect predates the reconnect policy and meant never reconnecting
+    fn migrate_manual_reconnect(&mut self) {
+        for session in &mut self.sessions {
+            if session.auto_reconnect == AutoReconnect::Manual {
+                session.reconnect_policy = ReconnectPolicy::Never;
+                session.auto_reconnect = AutoReconnect::default();
+            }
+        }
+    }
+
     fn assign_missing_folder_orders(&mut self) {
         // Check if any folders have order 0 (unassigned)
         let needs_migration = self.folders.iter().all(|f| f.order == 0) && !self.folders.is_empty();
@@ -143,18 +173,26 @@ impl PersistenceManager {
         }
     }
 
+    /// The old "reset scroll position on server output" checkbox forced following output
+    fn migrate_reset_scroll_on_output(&mut self) {
+        for session in &mut self.sessions {
+            if session.reset_scroll_on_output {
+                session.scroll_on_output = ScrollOnOutput::Always;
+                session.reset_scroll_on_output = false;
+            }
+        }
+    }
+
     pub fn save(&self) -> Result<()> {
         save_sessions(&self.sessions)?;
         save_folders(&self.folders)?;
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn sessions(&self) -> &[SessionConfig] {
         &self.sessions
     }
 
-    #[allow(dead_code)]
     pub fn folders(&self) -> &[SessionFolder] {
         &self.folders
     }
@@ -207,6 +245,56 @@ impl PersistenceManager {
         }
     }
 
+    pub fn delete_sessions(&mut self, ids: &[Uuid]) -> DeletedItems {
+        let sessions = self.sessions.iter().filter(|s| ids.contains(&s.id)).cloned().collect();
+        for id in ids {
+            self.remove_session(*id);
+        }
+        DeletedItems { sessions, folder: None }
+    }
+
+    pub fn delete_folder(&mut self, id: Uuid) -> DeletedItems {
+        let folder = self.get_folder(id).cloned().map(|folder| {
+            let members = self.sessions.iter().filter(|s| s.folder_id == Some(id)).map(|s| (s.id, s.order)).collect();
+            (folder, members)
+        });
+        self.remove_folder(id);
+        DeletedItems { sessions: Vec::new(), folder }
+    }
+
+    /// Put deleted items back where they were, making room at their old positions
+    pub fn restore(&mut self, deleted: DeletedItems) {
+        if let Some((folder, members)) = deleted.folder {
+            let folder_id = folder.id;
+            for other in self.folders.iter_mut() {
+                if other.parent_id == folder.parent_id && other.order >= folder.order {
+                    other.order += 1;
+                }
+            }
+            self.folders.push(folder);
+            for (id, order) in members {
+                // Only sessions still sitting at the root go back; any moved since stay put
+                if let Some(session) = self.sessions.iter_mut().find(|s| s.id == id && s.folder_id.is_none()) {
+                    session.folder_id = Some(folder_id);
+                    session.order = order;
+                }
+            }
+            self.normalize_session_orders(Some(folder_id));
+            self.normalize_session_orders(None);
+        }
+        for mut session in deleted.sessions {
+            if session.folder_id.is_some_and(|id| self.get_folder(id).is_none()) {
+                session.folder_id = None;
+            }
+            for other in self.sessions.iter_mut() {
+                if other.folder_id == session.folder_id && other.order >= session.order {
+                    other.order += 1;
+                }
+            }
+            self.sessions.push(session);
+        }
+    }
+
     pub fn get_folder(&self, id: Uuid) -> Option<&SessionFolder> {
         self.folders.iter().find(|f| f.id == id)
     }
@@ -225,15 +313,50 @@ impl PersistenceManager {
             .iter()
             .filter(|f| f.parent_id == parent_id)
             .collect();
-        folders.sort_by_key(|f| f.order);
+        folders.sort_by_key(|f| (!f.pinned, f.order));
         folders
     }
 
+    /// Every folder in tree order, each with its nesting depth (0 = top level)
+    pub fn folder_tree(&self) -> Vec<(&SessionFolder, usize)> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending: Vec<(&SessionFolder, usize)> = self.child_folders(None).into_iter().rev().map(|f| (f, 0)).collect();
+        while let Some((folder, depth)) = pending.pop() {
+            // A broken parent link must not loop forever
+            if !seen.insert(folder.id) {
+                continue;
+            }
+            out.push((folder, depth));
+            pending.extend(self.child_folders(Some(folder.id)).into_iter().rev().map(|f| (f, depth + 1)));
+        }
+        out
+    }
+
+    /// Folder names from the top level down to `id`, joined with " / "
+    pub fn folder_path(&self, id: Uuid) -> Option<String> {
+        let mut names = Vec::new();
+        let mut current = Some(id);
+        while let Some(folder) = current.and_then(|id| self.get_folder(id)) {
+            if names.len() > self.folders.len() {
+                break;
+            }
+            names.push(folder.name.as_str());
+            current = folder.parent_id;
+        }
+        if names.is_empty() {
+            return None;
+        }
+        names.reverse();
+        Some(names.join(" / "))
+    }
+
     pub fn duplicate_session(&mut self, id: Uuid) -> Option<Uuid> {
         let session = self.get_session(id)?.clone();
         let mut new_session = session;
         new_session.id = Uuid::new_v4();
         new_session.name = format!("{} (Copy)", new_session.name);
+        new_session.last_connected = None;
         let new_id = new_session.id;
         self.add_session(new_session);
         Some(new_id)
@@ -337,6 +460,41 @@ impl PersistenceManager {
         }
     }
 
+    /// Swap a folder with its neighbour above or below; pinned and unpinned folders stay in their own groups
+    pub fn shift_folder(&mut self, folder_id: Uuid, up: bool) {
+        let Some(folder) = self.get_folder(folder_id) else {
+            return;
+        };
+        let (parent_id, pinned) = (folder.parent_id, folder.pinned);
+        self.normalize_folder_orders(parent_id);
+        let siblings: Vec<(Uuid, u32)> = self.child_folders(parent_id)
+            .iter()
+            .filter(|f| f.pinned == pinned)
+            .map(|f| (f.id, f.order))
+            .collect();
+        let Some(index) = siblings.iter().position(|(id, _)| *id == folder_id) else {
+            return;
+        };
+        let neighbour = if up { index.checked_sub(1) } else { Some(index + 1) };
+        let Some(&(neighbour_id, neighbour_order)) = neighbour.and_then(|i| siblings.get(i)) else {
+            return;
+        };
+        let order = siblings[index].1;
+        for folder in self.folders.iter_mut() {
+            if folder.id == folder_id {
+                folder.order = neighbour_order;
+            } else if folder.id == neighbour_id {
+                folder.order = order;
+            }
+        }
+    }
+
+    pub fn set_folder_pinned(&mut self, folder_id: Uuid, pinned: bool) {
+        if let Some(folder) = self.folders.iter_mut().find(|f| f.id == folder_id) {
+            folder.pinned = pinned;
+        }
+    }
+
     pub fn set_folder_expanded(&mut self, folder_id: Uuid, expanded: bool) {
         if let Some(folder) = self.folders.iter_mut().find(|f| f.id == folder_id) {
             folder.expanded = expanded;
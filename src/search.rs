@@ -0,0 +1,113 @@
+use crate::terminal::buffer::TerminalBuffer;
+use uuid::Uuid;
+
+/// A match anchored to a stable line number (buffer index + lines trimmed so far),
+/// so it keeps pointing at the same text while scrollback is trimmed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchMatch {
+    stable_line: usize,
+    col: usize,
+    len: usize,
+}
+
+/// Find-in-scrollback state for one terminal session
+pub struct SearchState {
+    pub session_id: Uuid,
+    pub query: String,
+    matches: Vec<SearchMatch>,
+    current: Option<usize>,
+}
+
+impl SearchState {
+    pub fn new(session_id: Uuid) -> Self {
+        Self {
+            session_id,
+            query: String::new(),
+            matches: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Search the whole buffer (case-insensitive) and select the match closest to the bottom
+    pub fn run(&mut self, buffer: &TerminalBuffer) {
+        let previous = self.current.and_then(|i| self.matches.get(i).copied());
+        self.matches.clear();
+        self.current = None;
+        let needle: Vec<char> = self.query.to_lowercase().chars().collect();
+        if needle.is_empty() {
+            return;
+        }
+        let base = buffer.lines_dropped();
+        for index in 0..buffer.total_lines() {
+            let Some(line) = buffer.get_line(index) else {
+                continue;
+            };
+            let haystack: Vec<char> = line.cells().iter()
+                .map(|cell| cell.ch.to_lowercase().next().unwrap_or(cell.ch))
+                .collect();
+            let mut col = 0;
+            while col + needle.len() <= haystack.len() {
+                if haystack[col..col + needle.len()] == needle[..] {
+                    self.matches.push(SearchMatch { stable_line: base + index, col, len: needle.len() });
+                    col += needle.len();
+                } else {
+                    col += 1;
+                }
+            }
+        }
+        // Re-running keeps the position on the same match when it still exists
+        self.current = previous
+            .and_then(|m| self.matches.iter().position(|&x| x == m))
+            .or_else(|| self.matches.len().checked_sub(1));
+    }
+
+    pub fn next(&mut self, buffer: &TerminalBuffer) {
+        self.step(buffer, true);
+    }
+
+    pub fn previous(&mut self, buffer: &TerminalBuffer) {
+        self.step(buffer, false);
+    }
+
+    fn step(&mut self, buffer: &TerminalBuffer, forward: bool) {
+        self.drop_trimmed(buffer);
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.current = Some(match self.current {
+            Some(i) if forward => (i + 1) % count,
+            Some(i) => (i + count - 1) % count,
+            None => count - 1,
+        });
+    }
+
+    /// Forget matches whose lines were trimmed out of the buffer
+    fn drop_trimmed(&mut self, buffer: &TerminalBuffer) {
+        let base = buffer.lines_dropped();
+        let before = self.matches.len();
+        self.matches.retain(|m| m.stable_line >= base);
+        let removed = before - self.matches.len();
+        self.current = match self.current {
+            Some(i) if i >= removed => Some(i - removed),
+            Some(_) if !self.matches.is_empty() => Some(0),
+            _ => None,
+        };
+    }
+
+    /// The current match as (buffer line, start col, end col exclusive), if still in the buffer
+    pub fn current_match(&self, buffer: &TerminalBuffer) -> Option<(usize, usize, usize)> {
+        let m = self.matches.get(self.current?)?;
+        let line = m.stable_line.checked_sub(buffer.lines_dropped())?;
+        Some((line, m.col, m.col + m.len))
+    }
+
+    /// "3 of 12" style summary for the find bar
+    pub fn status(&self) -> String {
+        match self.current {
+            Some(i) => format!("{} of {}", i + 1, self.matches.len()),
+            None if self.query.is_empty() => String::new(),
+            None => String::from("No matches"),
+        }
+    }
+}
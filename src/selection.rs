@@ -33,6 +33,10 @@ impl Selection {
         self.end = SelectionPoint::new(line, col);
     }
 
+    pub fn end(&self) -> SelectionPoint {
+        self.end
+    }
+
     pub fn finish(&mut self) {
         self.active = false;
     }
@@ -73,27 +77,188 @@ impl Selection {
         true
     }
 
-    pub fn get_text(&self, buffer: &TerminalBuffer) -> String {
+    pub fn get_text(&self, buffer: &TerminalBuffer, preserve_trailing_spaces: bool) -> String {
         if self.is_empty() {
             return String::new();
         }
         let (start, end) = self.normalized();
-        buffer.get_text_range(start.line, start.col, end.line, end.col)
+        buffer.get_text_range(start.line, start.col, end.line, end.col, preserve_trailing_spaces)
     }
 }
 
+/// Keyboard movements that extend the selection end point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMove {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordLeft,
+    WordRight,
+    LineStart,
+    LineEnd,
+}
+
+fn is_word_char(buffer: &TerminalBuffer, line: usize, col: usize) -> bool {
+    buffer
+        .get_line(line)
+        .and_then(|l| l.get(col))
+        .is_some_and(|cell| !cell.ch.is_whitespace())
+}
+
+fn line_len(buffer: &TerminalBuffer, line: usize) -> usize {
+    buffer.get_line(line).map_or(0, |l| l.len())
+}
+
+fn move_point(point: SelectionPoint, movement: SelectionMove, buffer: &TerminalBuffer) -> SelectionPoint {
+    let last_line = buffer.total_lines().saturating_sub(1);
+    let SelectionPoint { mut line, mut col } = point;
+    match movement {
+        SelectionMove::Left => {
+            if col > 0 {
+                col -= 1;
+            } else if line > 0 {
+                line -= 1;
+                col = line_len(buffer, line).saturating_sub(1);
+            }
+        }
+        SelectionMove::Right => {
+            if col + 1 < line_len(buffer, line) {
+                col += 1;
+            } else if line < last_line {
+                line += 1;
+                col = 0;
+            }
+        }
+        SelectionMove::Up => line = line.saturating_sub(1),
+        SelectionMove::Down => line = (line + 1).min(last_line),
+        SelectionMove::WordLeft => {
+            // Skip whitespace to the left, then to the start of the word
+            while col > 0 && !is_word_char(buffer, line, col - 1) {
+                col -= 1;
+            }
+            while col > 0 && is_word_char(buffer, line, col - 1) {
+                col -= 1;
+            }
+        }
+        SelectionMove::WordRight => {
+            // Skip whitespace to the right, then to the last character of the word
+            let len = line_len(buffer, line);
+            while col + 1 < len && !is_word_char(buffer, line, col + 1) {
+                col += 1;
+            }
+            while col + 1 < len && is_word_char(buffer, line, col + 1) {
+                col += 1;
+            }
+        }
+        SelectionMove::LineStart => col = 0,
+        SelectionMove::LineEnd => {
+            let len = line_len(buffer, line);
+            col = (0..len).rev().find(|&c| is_word_char(buffer, line, c)).unwrap_or(0);
+        }
+    }
+    col = col.min(line_len(buffer, line).saturating_sub(1));
+    SelectionPoint::new(line, col)
+}
+
+/// The terminal cursor as an absolute buffer position
+fn cursor_point(buffer: &TerminalBuffer) -> SelectionPoint {
+    let cursor = buffer.cursor();
+    let line = (buffer.scrollback_len() + cursor.row).min(buffer.total_lines().saturating_sub(1));
+    let col = cursor.col.min(line_len(buffer, line).saturating_sub(1));
+    SelectionPoint::new(line, col)
+}
+
 #[derive(Debug, Default)]
 pub struct SelectionManager {
     selection: Option<Selection>,
+    // True when the current selection was made with the keyboard
+    keyboard: bool,
+    // Cursor of keyboard copy mode (None = not in copy mode)
+    copy_cursor: Option<SelectionPoint>,
 }
 
 impl SelectionManager {
     pub fn new() -> Self {
-        Self { selection: None }
+        Self {
+            selection: None,
+            keyboard: false,
+            copy_cursor: None,
+        }
     }
 
     pub fn start(&mut self, line: usize, col: usize) {
         self.selection = Some(Selection::new(line, col));
+        self.keyboard = false;
+    }
+
+    /// Extend the selection with a keyboard movement. Without an existing selection,
+    /// a new one is anchored at the terminal cursor.
+    pub fn extend_with_key(&mut self, movement: SelectionMove, buffer: &TerminalBuffer) {
+        if buffer.total_lines() == 0 {
+            return;
+        }
+        let end = match &self.selection {
+            Some(sel) => sel.end(),
+            None => {
+                let point = cursor_point(buffer);
+                let mut sel = Selection::new(point.line, point.col);
+                sel.finish();
+                self.selection = Some(sel);
+                point
+            }
+        };
+        let new_end = move_point(end, movement, buffer);
+        if let Some(sel) = &mut self.selection {
+            sel.update(new_end.line, new_end.col);
+        }
+        self.keyboard = true;
+    }
+
+    /// Start keyboard copy mode with its cursor on the terminal cursor
+    pub fn enter_copy_mode(&mut self, buffer: &TerminalBuffer) {
+        self.clear();
+        if buffer.total_lines() > 0 {
+            self.copy_cursor = Some(cursor_point(buffer));
+        }
+    }
+
+    pub fn exit_copy_mode(&mut self) {
+        self.copy_cursor = None;
+        self.clear();
+    }
+
+    pub fn copy_cursor(&self) -> Option<SelectionPoint> {
+        self.copy_cursor
+    }
+
+    /// Move the copy mode cursor; once a selection was started it follows the cursor
+    pub fn move_copy_cursor(&mut self, movement: SelectionMove, buffer: &TerminalBuffer) {
+        let Some(cursor) = self.copy_cursor else {
+            return;
+        };
+        let point = move_point(cursor, movement, buffer);
+        self.copy_cursor = Some(point);
+        if let Some(sel) = &mut self.selection {
+            sel.update(point.line, point.col);
+        }
+    }
+
+    /// Anchor a selection at the copy mode cursor, or drop the one in progress
+    pub fn toggle_copy_selection(&mut self) {
+        let Some(cursor) = self.copy_cursor else {
+            return;
+        };
+        if self.selection.take().is_none() {
+            let mut sel = Selection::new(cursor.line, cursor.col);
+            sel.finish();
+            self.selection = Some(sel);
+            self.keyboard = true;
+        }
+    }
+
+    pub fn is_keyboard_selection(&self) -> bool {
+        self.keyboard && self.selection.is_some()
     }
 
     pub fn update(&mut self, line: usize, col: usize) {
@@ -113,6 +278,7 @@ impl SelectionManager {
 
     pub fn clear(&mut self) {
         self.selection = None;
+        self.keyboard = false;
     }
 
     pub fn selection(&self) -> Option<&Selection> {
@@ -124,8 +290,8 @@ impl SelectionManager {
         self.selection.as_ref().map_or(false, |s| !s.is_empty())
     }
 
-    pub fn get_text(&self, buffer: &TerminalBuffer) -> Option<String> {
-        self.selection.as_ref().map(|s| s.get_text(buffer))
+    pub fn get_text(&self, buffer: &TerminalBuffer, preserve_trailing_spaces: bool) -> Option<String> {
+        self.selection.as_ref().map(|s| s.get_text(buffer, preserve_trailing_spaces))
     }
 
     #[allow(dead_code)]
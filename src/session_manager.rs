@@ -5,18 +5,32 @@ use uuid::Uuid;
 
 // Spacing constants
 const BUTTON_SPACING: f32 = 4.0;
+const PINNED_SECTION_SPACING: f32 = 6.0;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SessionManagerAction {
     Connect(Uuid),
+    // Open and connect without switching away from the current tab
+    ConnectBackground(Uuid),
     Edit(Uuid),
     Duplicate(Uuid),
+    CopySshCommand(Uuid),
+    TogglePin(Uuid),
+    MoveSession { session_id: Uuid, folder_id: Option<Uuid> },
+    // Bulk variants for multi-selection in the tree
+    ConnectMany(Vec<Uuid>),
+    DuplicateMany(Vec<Uuid>),
+    DeleteMany(Vec<Uuid>),
+    MoveSessions { session_ids: Vec<Uuid>, folder_id: Option<Uuid> },
     Delete(Uuid),
     NewSession,
     NewSessionInFolder(Uuid),
     NewFolder,
     EditFolder(Uuid),
     DeleteFolder(Uuid),
+    TogglePinFolder(Uuid),
+    // Move a folder one place up (true) or down among its siblings
+    ShiftFolder { folder_id: Uuid, up: bool },
 }
 
 pub struct SessionManagerUi {
@@ -61,6 +75,26 @@ impl SessionManagerUi {
                 );
             });
             ui.add_space(BUTTON_SPACING);
+            // Pinned sessions connect with a single click, regardless of folder
+            let mut pinned: Vec<_> = persistence.sessions().iter().filter(|s| s.pinned).collect();
+            if !pinned.is_empty() {
+                pinned.sort_by_key(|s| s.name.to_lowercase());
+                for session in pinned {
+                    let response = ui.add(egui::Button::new(format!("📌 {}", session.name)).frame(false))
+                        .on_hover_text(session.hover_text());
+                    if response.clicked() {
+                        action = Some(SessionManagerAction::Connect(session.id));
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button("Unpin").clicked() {
+                            action = Some(SessionManagerAction::TogglePin(session.id));
+                            ui.close();
+                        }
+                    });
+                }
+                ui.add_space(PINNED_SECTION_SPACING);
+                ui.separator();
+            }
             // Show custom tree view
             if let Some(tree_action) = self.tree_view.show(ui, persistence, &self.filter) {
                 action = Some(tree_action);
@@ -8,6 +8,8 @@ use uuid::Uuid;
 // Tree view layout constants
 const ITEM_HEIGHT: f32 = 18.0;
 const INDENT_WIDTH: f32 = 16.0;
+// Per-level prefix for nested folders in folder menus
+pub const FOLDER_MENU_INDENT: &str = "    ";
 const DROP_INDICATOR_HEIGHT: f32 = 2.0;
 const EXPAND_BUTTON_SIZE: f32 = 14.0;
 // Fraction of item height at edges that triggers root-level drop instead of into-folder
@@ -53,6 +55,11 @@ pub struct SessionTreeView {
     drop_target: Option<DropTarget>,
     item_positions: Vec<ItemPosition>,
     initialized: bool,
+    // Multi-selection (Ctrl/Shift+click); range selection uses the previous frame's order
+    selected_sessions: HashSet<Uuid>,
+    selection_anchor: Option<Uuid>,
+    visible_sessions: Vec<Uuid>,
+    last_visible_sessions: Vec<Uuid>,
 }
 
 impl Default for SessionTreeView {
@@ -69,6 +76,10 @@ impl SessionTreeView {
             drop_target: None,
             item_positions: Vec::new(),
             initialized: false,
+            selected_sessions: HashSet::new(),
+            selection_anchor: None,
+            visible_sessions: Vec::new(),
+            last_visible_sessions: Vec::new(),
         }
     }
 
@@ -89,6 +100,9 @@ impl SessionTreeView {
         }
         self.item_positions.clear();
         self.drop_target = None;
+        self.last_visible_sessions = std::mem::take(&mut self.visible_sessions);
+        // Forget selected sessions that were deleted
+        self.selected_sessions.retain(|id| persistence.get_session(*id).is_some());
         let mut action = None;
         // Render hierarchy starting from root
         self.render_children(ui, persistence, None, 0, filter, &mut action);
@@ -163,7 +177,7 @@ impl SessionTreeView {
         for session in sessions {
             let session_id = session.id;
             let y_before = ui.cursor().top();
-            if let Some(session_action) = self.render_session(ui, &session, depth) {
+            if let Some(session_action) = self.render_session(ui, &session, persistence, depth) {
                 *action = Some(session_action);
             }
             let y_after = ui.cursor().top();
@@ -209,7 +223,7 @@ impl SessionTreeView {
                 }
                 // Folder item
                 let item_response = ui.add(
-                    egui::Button::new(format!("📁 {}", folder.name))
+                    egui::Button::new(format!("{} {}", if folder.pinned { "📌" } else { "📁" }, folder.name))
                         .frame(false)
                         .sense(egui::Sense::click_and_drag())
                 );
@@ -227,6 +241,19 @@ impl SessionTreeView {
                         action = Some(SessionManagerAction::EditFolder(folder_id));
                         ui.close();
                     }
+                    let pin_text = if folder.pinned { "Unpin Group" } else { "Pin Group to Top" };
+                    if ui.button(pin_text).clicked() {
+                        action = Some(SessionManagerAction::TogglePinFolder(folder_id));
+                        ui.close();
+                    }
+                    if ui.button("Move Up").clicked() {
+                        action = Some(SessionManagerAction::ShiftFolder { folder_id, up: true });
+                        ui.close();
+                    }
+                    if ui.button("Move Down").clicked() {
+                        action = Some(SessionManagerAction::ShiftFolder { folder_id, up: false });
+                        ui.close();
+                    }
                     if ui.button("Delete Group").clicked() {
                         action = Some(SessionManagerAction::DeleteFolder(folder_id));
                         ui.close();
@@ -241,6 +268,7 @@ impl SessionTreeView {
         &mut self,
         ui: &mut Ui,
         session: &SessionConfig,
+        persistence: &PersistenceManager,
         depth: usize,
     ) -> Option<SessionManagerAction> {
         let mut action = None;
@@ -257,23 +285,48 @@ impl SessionTreeView {
                     ui.add_space(EXPAND_BUTTON_SIZE);
                 }
                 // Session item
+                self.visible_sessions.push(session_id);
+                let is_selected = self.selected_sessions.contains(&session_id);
                 let item_response = ui.add(
                     egui::Button::new(format!("💻 {}", session.name))
                         .frame(false)
+                        .selected(is_selected)
                         .sense(egui::Sense::click_and_drag())
-                );
+                ).on_hover_text(session.hover_text());
+                if item_response.clicked() {
+                    let modifiers = ui.input(|i| i.modifiers);
+                    self.click_session(session_id, modifiers);
+                }
                 if item_response.double_clicked() {
-                    action = Some(SessionManagerAction::Connect(session_id));
+                    // Ctrl+double-click queues the connection in the background
+                    action = Some(if ui.input(|i| i.modifiers.command) {
+                        SessionManagerAction::ConnectBackground(session_id)
+                    } else {
+                        SessionManagerAction::Connect(session_id)
+                    });
                 }
                 if item_response.drag_started() {
                     self.dragged_item = Some(TreeItem::Session(session_id));
                 }
-                // Context menu
+                // Context menu (acts on the whole selection when this session is part of it)
+                let bulk: Vec<Uuid> = if is_selected && self.selected_sessions.len() > 1 {
+                    self.selected_in_order()
+                } else {
+                    Vec::new()
+                };
                 item_response.context_menu(|ui| {
+                    if !bulk.is_empty() {
+                        action = Self::bulk_context_menu(ui, persistence, &bulk);
+                        return;
+                    }
                     if ui.button("Connect").clicked() {
                         action = Some(SessionManagerAction::Connect(session_id));
                         ui.close();
                     }
+                    if ui.button("Connect in Background").clicked() {
+                        action = Some(SessionManagerAction::ConnectBackground(session_id));
+                        ui.close();
+                    }
                     ui.separator();
                     if ui.button("Edit").clicked() {
                         action = Some(SessionManagerAction::Edit(session_id));
@@ -283,6 +336,18 @@ impl SessionTreeView {
                         action = Some(SessionManagerAction::Duplicate(session_id));
                         ui.close();
                     }
+                    if ui.button("Copy SSH Command").clicked() {
+                        action = Some(SessionManagerAction::CopySshCommand(session_id));
+                        ui.close();
+                    }
+                    let pin_text = if session.pinned { "Unpin" } else { "Pin" };
+                    if ui.button(pin_text).clicked() {
+                        action = Some(SessionManagerAction::TogglePin(session_id));
+                        ui.close();
+                    }
+                    if let Some(folder_id) = Self::move_to_group_menu(ui, persistence, Some(session.folder_id)) {
+                        action = Some(SessionManagerAction::MoveSession { session_id, folder_id });
+                    }
                     ui.separator();
                     if ui.button("Delete").clicked() {
                         action = Some(SessionManagerAction::Delete(session_id));
@@ -294,6 +359,89 @@ impl SessionTreeView {
         action
     }
 
+    fn click_session(&mut self, session_id: Uuid, modifiers: egui::Modifiers) {
+        if modifiers.command || modifiers.ctrl {
+            if !self.selected_sessions.remove(&session_id) {
+                self.selected_sessions.insert(session_id);
+            }
+            self.selection_anchor = Some(session_id);
+        } else if modifiers.shift {
+            let anchor = self.selection_anchor.unwrap_or(session_id);
+            let anchor_pos = self.last_visible_sessions.iter().position(|&id| id == anchor);
+            let click_pos = self.last_visible_sessions.iter().position(|&id| id == session_id);
+            self.selected_sessions.clear();
+            match (anchor_pos, click_pos) {
+                (Some(a), Some(b)) => {
+                    let range = a.min(b)..=a.max(b);
+                    self.selected_sessions.extend(&self.last_visible_sessions[range]);
+                }
+                _ => {
+                    self.selected_sessions.insert(session_id);
+                }
+            }
+        } else {
+            self.selected_sessions.clear();
+            self.selected_sessions.insert(session_id);
+            self.selection_anchor = Some(session_id);
+        }
+    }
+
+    /// Selected sessions in the order they appear in the tree
+    fn selected_in_order(&self) -> Vec<Uuid> {
+        self.last_visible_sessions
+            .iter()
+            .filter(|id| self.selected_sessions.contains(id))
+            .copied()
+            .collect()
+    }
+
+    fn bulk_context_menu(ui: &mut Ui, persistence: &PersistenceManager, session_ids: &[Uuid]) -> Option<SessionManagerAction> {
+        let mut action = None;
+        let count = session_ids.len();
+        if ui.button(format!("Connect {} Sessions", count)).clicked() {
+            action = Some(SessionManagerAction::ConnectMany(session_ids.to_vec()));
+            ui.close();
+        }
+        ui.separator();
+        if ui.button(format!("Duplicate {} Sessions", count)).clicked() {
+            action = Some(SessionManagerAction::DuplicateMany(session_ids.to_vec()));
+            ui.close();
+        }
+        if let Some(folder_id) = Self::move_to_group_menu(ui, persistence, None) {
+            action = Some(SessionManagerAction::MoveSessions { session_ids: session_ids.to_vec(), folder_id });
+        }
+        ui.separator();
+        if ui.button(format!("Delete {} Sessions", count)).clicked() {
+            action = Some(SessionManagerAction::DeleteMany(session_ids.to_vec()));
+            ui.close();
+        }
+        action
+    }
+
+    /// "Move to Group" submenu; returns the chosen folder (None = root).
+    /// `current_folder` disables the entry the session is already in (None when moving several).
+    fn move_to_group_menu(ui: &mut Ui, persistence: &PersistenceManager, current_folder: Option<Option<Uuid>>) -> Option<Option<Uuid>> {
+        let mut target = None;
+        ui.menu_button("Move to Group", |ui| {
+            if ui.add_enabled(current_folder != Some(None), egui::Button::new("Root")).clicked() {
+                target = Some(None);
+                ui.close();
+            }
+            let folders = persistence.folder_tree();
+            if !folders.is_empty() {
+                ui.separator();
+            }
+            for (folder, depth) in folders {
+                let label = format!("{}{}", FOLDER_MENU_INDENT.repeat(depth), folder.name);
+                if ui.add_enabled(current_folder != Some(Some(folder.id)), egui::Button::new(label)).clicked() {
+                    target = Some(Some(folder.id));
+                    ui.close();
+                }
+            }
+        });
+        target
+    }
+
     fn calculate_drop_target(
         &self,
         persistence: &PersistenceManager,
@@ -320,6 +468,11 @@ impl SessionTreeView {
                     if pos.parent_folder.is_some() {
                         return None; // Can't drop folder inside another folder
                     }
+                    // Pinned folders always sort first, so reordering stays within a group
+                    let pinned = |id: Uuid| persistence.get_folder(id).map(|f| f.pinned);
+                    if pinned(*target_id) != pinned(*dragged_id) {
+                        return None;
+                    }
                     let order = self.get_folder_order(persistence, *target_id)?;
                     if in_upper_half {
                         return Some(DropTarget {
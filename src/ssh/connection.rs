@@ -1,22 +1,41 @@
-use crate::config::{AuthMethod, BackspaceKey, LineEnding, ResizeMethod, SessionConfig};
+use crate::config::{AuthMethod, LineEnding, ResizeMethod, SessionConfig, SessionKind};
 use crate::debug;
 use anyhow::{Context, Result};
-use ssh2::{Channel, Session};
+use ssh2::{Channel, KeyboardInteractivePrompt, Prompt, Session, Sftp};
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
+use uuid::Uuid;
 
 // Connection constants
 const READ_BUFFER_SIZE: usize = 4096;
+// Bytes copied per loop pass while uploading, so shells on the session keep being served
+const UPLOAD_CHUNK_SIZE: usize = 32 * 1024;
 const CHANNEL_CHECK_INTERVAL_MS: u64 = 10;
+const REACHABILITY_PROBE_TIMEOUT_SECS: u64 = 5;
+// Upper bound for the optional check before connecting (the session timeout applies if shorter)
+const PRE_CONNECT_CHECK_TIMEOUT_SECS: u64 = 3;
+
+/// Signals offered for the foreground job, with the control character the remote terminal
+/// turns into the same signal, typed when a server refuses the signal request (TERM and HUP have none)
+pub const SIGNALS: [(&str, Option<u8>); 5] = [
+    ("INT", Some(0x03)),
+    ("QUIT", Some(0x1C)),
+    ("TSTP", Some(0x1A)),
+    ("TERM", None),
+    ("HUP", None),
+];
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
     Disconnected,
     Connecting,
+    // Waiting for the user to answer a keyboard-interactive prompt
+    Authenticating,
     Connected,
     Error(String),
 }
@@ -26,12 +45,94 @@ pub enum SshEvent {
     Data(Vec<u8>),
     Disconnected { natural: bool },
     Error(String),
+    // Result of an SFTP upload: a summary or the error
+    UploadFinished(Result<String, String>),
+    // Keyboard-interactive challenge: the server's instructions and each prompt with whether
+    // the answer may be shown; answered with SshCommand::AuthResponse
+    AuthPrompt { instructions: String, prompts: Vec<(String, bool)> },
 }
 
 pub enum SshCommand {
     Write(Vec<u8>),
     Disconnect,
     Resize { cols: u32, rows: u32 },
+    Upload { files: Vec<PathBuf>, remote_dir: String },
+    OpenShell(ShellRequest),
+    // Signal name without the SIG prefix, e.g. "INT"
+    Signal(String),
+    // Answers to the last SshEvent::AuthPrompt, one per prompt
+    AuthResponse(Vec<String>),
+}
+
+/// Forwards keyboard-interactive prompts to the UI and waits for the answers.
+/// Disconnecting while a prompt is open cancels the login.
+struct AuthPrompter<'a> {
+    config: &'a SessionConfig,
+    state: &'a Arc<Mutex<ConnectionState>>,
+    event_tx: &'a Sender<SshEvent>,
+    command_rx: &'a Receiver<SshCommand>,
+    cancelled: bool,
+}
+
+impl KeyboardInteractivePrompt for AuthPrompter<'_> {
+    fn prompt<'a>(&mut self, _username: &str, instructions: &str, prompts: &[Prompt<'a>]) -> Vec<String> {
+        // Rounds without prompts only carry information for the user
+        if self.cancelled || prompts.is_empty() {
+            return Vec::new();
+        }
+        let prompts = prompts.iter().map(|prompt| (prompt.text.to_string(), prompt.echo)).collect();
+        let event = SshEvent::AuthPrompt { instructions: instructions.to_string(), prompts };
+        if self.event_tx.send(event).is_err() {
+            self.cancelled = true;
+            return Vec::new();
+        }
+        *self.state.lock().unwrap() = ConnectionState::Authenticating;
+        loop {
+            match self.command_rx.recv() {
+                Ok(SshCommand::AuthResponse(answers)) => {
+                    *self.state.lock().unwrap() = ConnectionState::Connecting;
+                    return answers;
+                }
+                Ok(SshCommand::Disconnect) | Err(_) => {
+                    debug::log(&format!("[SSH {}] Keyboard-interactive login cancelled", self.config.id));
+                    self.cancelled = true;
+                    return Vec::new();
+                }
+                // There is no shell yet for anything else to go to
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+/// Endpoints for another tab's shell on an already authenticated session
+pub struct ShellRequest {
+    event_tx: Sender<SshEvent>,
+    command_rx: Receiver<SshCommand>,
+    state: Arc<Mutex<ConnectionState>>,
+}
+
+// A shell channel served by the connection thread; several can share one SSH session
+struct ShellChannel {
+    channel: Channel,
+    event_tx: Sender<SshEvent>,
+    command_rx: Receiver<SshCommand>,
+    state: Arc<Mutex<ConnectionState>>,
+}
+
+// An SFTP upload advanced one chunk per loop pass alongside the shells
+struct Upload {
+    sftp: Sftp,
+    pending: std::vec::IntoIter<PathBuf>,
+    current: Option<(std::fs::File, ssh2::File, String)>,
+    remote_dir: String,
+    count: usize,
+    event_tx: Sender<SshEvent>,
+}
+
+enum ShellStatus {
+    Open,
+    Closed { natural: bool },
 }
 
 pub struct SshConnection {
@@ -39,22 +140,27 @@ pub struct SshConnection {
     event_rx: Receiver<SshEvent>,
     command_tx: Sender<SshCommand>,
     config: SessionConfig,
+    // Shared by every shell opened over the same SSH session
+    link_id: Uuid,
 }
 
 impl SshConnection {
     pub fn new(config: SessionConfig) -> Self {
         let (event_tx, event_rx) = mpsc::channel();
         let (command_tx, command_rx) = mpsc::channel();
-        let state = Arc::new(Mutex::new(ConnectionState::Disconnected));
+        // Connecting from the start, so the thread counts against the connecting limit before it runs
+        let state = Arc::new(Mutex::new(ConnectionState::Connecting));
         let connection = Self {
             state: state.clone(),
             event_rx,
             command_tx,
             config: config.clone(),
+            link_id: Uuid::new_v4(),
         };
         let state_clone = state.clone();
-        thread::spawn(move || {
-            Self::connection_thread(config, state_clone, event_tx, command_rx);
+        thread::spawn(move || match config.kind {
+            SessionKind::Ssh => Self::connection_thread(config, state_clone, event_tx, command_rx),
+            SessionKind::LocalShell => crate::local_shell::connection_thread(config, state_clone, event_tx, command_rx),
         });
         connection
     }
@@ -67,13 +173,30 @@ impl SshConnection {
     ) {
         debug::log(&format!("[SSH {}] Connecting to {}:{}", config.id, config.host, config.port));
         *state.lock().unwrap() = ConnectionState::Connecting;
-        let result = Self::establish_connection(&config);
+        let result = if config.check_reachable && !Self::pre_connect_check(&config) {
+            Err(anyhow::anyhow!("Host unreachable: no answer from {}:{}", config.host, config.port))
+        } else {
+            let mut prompter = AuthPrompter {
+                config: &config,
+                state: &state,
+                event_tx: &event_tx,
+                command_rx: &command_rx,
+                cancelled: false,
+            };
+            Self::establish_connection(&config, &mut prompter)
+        };
         match result {
-            Ok((session, mut channel)) => {
+            Ok((session, channel)) => {
                 debug::log(&format!("[SSH {}] Connected", config.id));
                 *state.lock().unwrap() = ConnectionState::Connected;
                 let _ = event_tx.send(SshEvent::Connected);
-                Self::run_session(&config, session, &mut channel, &state, &event_tx, &command_rx);
+                let primary = ShellChannel {
+                    channel,
+                    event_tx,
+                    command_rx,
+                    state: state.clone(),
+                };
+                Self::run_session(&config, session, primary);
             }
             Err(e) => {
                 let error_msg = format!("{:#}", e);
@@ -86,7 +209,13 @@ impl SshConnection {
         *state.lock().unwrap() = ConnectionState::Disconnected;
     }
 
-    fn establish_connection(config: &SessionConfig) -> Result<(Session, Channel)> {
+    fn pre_connect_check(config: &SessionConfig) -> bool {
+        let timeout = config.timeout.min(Duration::from_secs(PRE_CONNECT_CHECK_TIMEOUT_SECS));
+        debug::log(&format!("[SSH {}] Checking {}:{} is reachable", config.id, config.host, config.port));
+        is_reachable(&format!("{}:{}", config.host, config.port), timeout)
+    }
+
+    fn establish_connection(config: &SessionConfig, prompter: &mut AuthPrompter) -> Result<(Session, Channel)> {
         let address = format!("{}:{}", config.host, config.port);
         let timeout = config.timeout;
         let tcp = TcpStream::connect_timeout(
@@ -98,11 +227,64 @@ impl SshConnection {
         let mut session = Session::new().context("Failed to create SSH session")?;
         session.set_tcp_stream(tcp);
         session.handshake().context("SSH handshake failed")?;
-        match config.auth_method {
+        Self::authenticate(&session, config, prompter)?;
+        if !session.authenticated() {
+            anyhow::bail!("Authentication failed");
+        }
+        if config.compression {
+            // Compression is handled automatically by libssh2
+        }
+        let mut channel = Self::start_shell(&session)?;
+        if let Some(attach_line) = config.multiplexer_attach_line() {
+            channel.write_all(attach_line.as_bytes())?;
+        }
+        Ok((session, channel))
+    }
+
+    /// Authenticate with the configured method, then with the other one if fallback is enabled
+    fn authenticate(session: &Session, config: &SessionConfig, prompter: &mut AuthPrompter) -> Result<()> {
+        let Err(error) = Self::try_auth(session, config, &config.auth_method, config.password.as_deref(), prompter) else {
+            return Ok(());
+        };
+        if !config.auth_fallback || prompter.cancelled {
+            return Err(error);
+        }
+        let (fallback, secret, offered_name) = match config.auth_method {
+            AuthMethod::PrivateKey | AuthMethod::Agent => {
+                let password = config.fallback_password.as_deref().filter(|p| !p.is_empty());
+                (AuthMethod::Password, password, "password")
+            }
+            AuthMethod::Password => (AuthMethod::PrivateKey, None, "publickey"),
+            // The server drives the prompts, so there is nothing to fall back to
+            AuthMethod::KeyboardInteractive => return Err(error),
+        };
+        // Skip methods the server no longer accepts for this user
+        let offered = match session.auth_methods(&config.username) {
+            Ok(methods) => methods.split(',').any(|m| m == offered_name),
+            Err(_) => true,
+        };
+        if !offered {
+            return Err(error);
+        }
+        if fallback == AuthMethod::Password && secret.is_none() {
+            return Err(error.context("No fallback password stored; use Reconnect As to enter one"));
+        }
+        debug::log(&format!("[SSH {}] {:#}; trying {} authentication", config.id, error, offered_name));
+        Self::try_auth(session, config, &fallback, secret, prompter)
+            .map_err(|fallback_error| fallback_error.context(format!("{:#}", error)))
+    }
+
+    fn try_auth(
+        session: &Session,
+        config: &SessionConfig,
+        method: &AuthMethod,
+        secret: Option<&str>,
+        prompter: &mut AuthPrompter,
+    ) -> Result<()> {
+        match method {
             AuthMethod::Password => {
-                let password = config.password.as_deref().unwrap_or("");
                 session
-                    .userauth_password(&config.username, password)
+                    .userauth_password(&config.username, secret.unwrap_or(""))
                     .context("Password authentication failed")?;
             }
             AuthMethod::PrivateKey => {
@@ -111,42 +293,52 @@ impl SshConnection {
                     .as_ref()
                     .context("Private key path not specified")?;
                 session
-                    .userauth_pubkey_file(
-                        &config.username,
-                        None,
-                        key_path,
-                        config.password.as_deref(),
-                    )
+                    .userauth_pubkey_file(&config.username, None, key_path, secret)
                     .context("Public key authentication failed")?;
             }
+            AuthMethod::Agent => Self::agent_auth(session, config)?,
+            AuthMethod::KeyboardInteractive => {
+                let result = session.userauth_keyboard_interactive(&config.username, prompter);
+                if prompter.cancelled {
+                    anyhow::bail!("Authentication cancelled");
+                }
+                result.context("Keyboard-interactive authentication failed")?;
+            }
         }
-        if !session.authenticated() {
-            anyhow::bail!("Authentication failed");
+        Ok(())
+    }
+
+    /// Offer each identity held by the SSH agent until the server accepts one
+    fn agent_auth(session: &Session, config: &SessionConfig) -> Result<()> {
+        let mut agent = session.agent().context("Failed to create SSH agent client")?;
+        agent.connect().context("Failed to connect to the SSH agent; is it running?")?;
+        agent.list_identities().context("Failed to list SSH agent identities")?;
+        let identities = agent.identities().context("Failed to list SSH agent identities")?;
+        if identities.is_empty() {
+            anyhow::bail!("The SSH agent has no keys loaded");
         }
-        if config.compression {
-            // Compression is handled automatically by libssh2
+        for identity in &identities {
+            match agent.userauth(&config.username, identity) {
+                Ok(()) => {
+                    debug::log(&format!("[SSH {}] Authenticated with agent key {}", config.id, identity.comment()));
+                    let _ = agent.disconnect();
+                    return Ok(());
+                }
+                Err(e) => debug::log(&format!("[SSH {}] Agent key {} rejected: {}", config.id, identity.comment(), e)),
+            }
         }
+        let _ = agent.disconnect();
+        anyhow::bail!("Agent authentication failed: none of the {} key(s) in the SSH agent was accepted", identities.len())
+    }
+
+    fn start_shell(session: &Session) -> Result<Channel> {
         let mut channel = session.channel_session().context("Failed to open channel")?;
         channel.request_pty("xterm-256color", None, None)?;
         channel.shell().context("Failed to start shell")?;
-        if let Some(screen_name) = &config.screen_session {
-            let screen_cmd = format!(
-                "screen -x {} || screen -S {}\n",
-                screen_name, screen_name
-            );
-            channel.write_all(screen_cmd.as_bytes())?;
-        }
-        Ok((session, channel))
+        Ok(channel)
     }
 
-    fn run_session(
-        config: &SessionConfig,
-        session: Session,
-        channel: &mut Channel,
-        _state: &Arc<Mutex<ConnectionState>>,
-        event_tx: &Sender<SshEvent>,
-        command_rx: &Receiver<SshCommand>,
-    ) {
+    fn run_session(config: &SessionConfig, session: Session, primary: ShellChannel) {
         // Set non-blocking mode so reads don't block the command processing
         session.set_blocking(false);
         
@@ -157,66 +349,170 @@ impl SshConnection {
             None
         };
         let mut last_keepalive = std::time::Instant::now();
-        #[allow(unused_assignments)]
-        let mut disconnect_natural = false;
-        loop {
-            match command_rx.try_recv() {
-                Ok(SshCommand::Write(_data)) => {
-                    if let Err(e) = channel.write_all(&_data) {
-                        debug::log(&format!("[SSH {}] Write error: {:?}", config.id, e));
-                        disconnect_natural = false;
-                        break;
-                    }
+        // The session stays up while any tab still has a shell on it
+        let mut shells = vec![primary];
+        let mut uploads = Vec::new();
+        let mut upload_buffer = vec![0u8; UPLOAD_CHUNK_SIZE];
+        while !shells.is_empty() {
+            let mut opened = Vec::new();
+            let mut i = 0;
+            while i < shells.len() {
+                match Self::service_shell(config, &session, &mut shells[i], &mut read_buffer, &mut opened, &mut uploads) {
+                    ShellStatus::Open => i += 1,
+                    ShellStatus::Closed { natural } => Self::close_shell(shells.remove(i), natural),
                 }
-                Ok(SshCommand::Disconnect) => {
-                    disconnect_natural = true;
-                    break;
+            }
+            shells.extend(opened);
+            let mut i = 0;
+            while i < uploads.len() {
+                // SFTP calls block, but only for one chunk at a time
+                session.set_blocking(true);
+                match Self::upload_chunk(&mut uploads[i], &mut upload_buffer) {
+                    Ok(false) => i += 1,
+                    step => Self::finish_upload(config, uploads.remove(i), step.map(|_| ())),
                 }
-                Ok(SshCommand::Resize { cols, rows }) => {
-                    if let Err(e) = Self::handle_resize(config, &session, channel, cols, rows) {
-                        debug::log(&format!("[SSH {}] Resize error: {:?}", config.id, e));
+                session.set_blocking(false);
+            }
+            if let Some(interval) = keepalive_interval {
+                if last_keepalive.elapsed() >= interval {
+                    if session.keepalive_send().is_err() {
+                        for shell in shells.drain(..) {
+                            Self::close_shell(shell, false);
+                        }
+                        break;
                     }
+                    last_keepalive = std::time::Instant::now();
                 }
-                Err(mpsc::TryRecvError::Empty) => {}
-                Err(mpsc::TryRecvError::Disconnected) => {
-                    disconnect_natural = false;
-                    break;
+            }
+        }
+    }
+
+    /// Handle one pending command and one read for a shell
+    fn service_shell(
+        config: &SessionConfig,
+        session: &Session,
+        shell: &mut ShellChannel,
+        read_buffer: &mut [u8],
+        opened: &mut Vec<ShellChannel>,
+        uploads: &mut Vec<Upload>,
+    ) -> ShellStatus {
+        match shell.command_rx.try_recv() {
+            Ok(SshCommand::Write(data)) => {
+                if let Err(e) = shell.channel.write_all(&data) {
+                    debug::log(&format!("[SSH {}] Write error: {:?}", config.id, e));
+                    return ShellStatus::Closed { natural: false };
                 }
             }
-            match channel.read(&mut read_buffer) {
-                Ok(0) => {
-                    if channel.eof() {
-                        debug::log(&format!("[SSH {}] EOF", config.id));
-                        disconnect_natural = true;
-                        break;
+            Ok(SshCommand::Disconnect) => return ShellStatus::Closed { natural: true },
+            Ok(SshCommand::Resize { cols, rows }) => {
+                if let Err(e) = Self::handle_resize(config, session, &mut shell.channel, cols, rows) {
+                    debug::log(&format!("[SSH {}] Resize error: {:?}", config.id, e));
+                }
+            }
+            Ok(SshCommand::Upload { files, remote_dir }) => {
+                // The copy itself proceeds in chunks from run_session
+                session.set_blocking(true);
+                match session.sftp() {
+                    Ok(sftp) => uploads.push(Upload {
+                        sftp,
+                        count: files.len(),
+                        pending: files.into_iter(),
+                        current: None,
+                        remote_dir,
+                        event_tx: shell.event_tx.clone(),
+                    }),
+                    Err(e) => {
+                        let message = format!("Upload failed: Failed to start SFTP: {}", e);
+                        debug::log(&format!("[SSH {}] {}", config.id, message));
+                        let _ = shell.event_tx.send(SshEvent::UploadFinished(Err(message)));
                     }
                 }
-                Ok(n) => {
-                    let data = read_buffer[..n].to_vec();
-                    if event_tx.send(SshEvent::Data(data)).is_err() {
-                        disconnect_natural = false;
-                        break;
+                session.set_blocking(false);
+            }
+            // Only expected while authenticating; a late answer has nothing left to go to
+            Ok(SshCommand::AuthResponse(_)) => {}
+            Ok(SshCommand::OpenShell(request)) => {
+                opened.extend(Self::open_shell_channel(config, session, request));
+            }
+            Ok(SshCommand::Signal(name)) => {
+                session.set_blocking(true);
+                let result = shell.channel.process_startup("signal", Some(&name));
+                session.set_blocking(false);
+                if let Err(e) = result {
+                    debug::log(&format!("[SSH {}] Signal request for SIG{} refused: {}", config.id, name, e));
+                    // Many servers ignore "signal" requests; the terminal's control character stands in
+                    match SIGNALS.iter().find(|(signal, _)| *signal == name).and_then(|(_, control)| *control) {
+                        Some(control) => {
+                            if let Err(e) = shell.channel.write_all(&[control]) {
+                                debug::log(&format!("[SSH {}] Write error: {:?}", config.id, e));
+                                return ShellStatus::Closed { natural: false };
+                            }
+                        }
+                        None => debug::log(&format!("[SSH {}] SIG{} has no control character to fall back on", config.id, name)),
                     }
                 }
-                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
-                Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {}
-                Err(e) => {
-                    debug::log(&format!("[SSH {}] Read error: {:?}", config.id, e));
-                    disconnect_natural = false;
-                    break;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => return ShellStatus::Closed { natural: false },
+        }
+        match shell.channel.read(read_buffer) {
+            Ok(0) => {
+                if shell.channel.eof() {
+                    debug::log(&format!("[SSH {}] EOF", config.id));
+                    return ShellStatus::Closed { natural: true };
                 }
             }
-            if let Some(interval) = keepalive_interval {
-                if last_keepalive.elapsed() >= interval {
-                    if session.keepalive_send().is_err() {
-                        disconnect_natural = false;
-                        break;
-                    }
-                    last_keepalive = std::time::Instant::now();
+            Ok(n) => {
+                let data = read_buffer[..n].to_vec();
+                if shell.event_tx.send(SshEvent::Data(data)).is_err() {
+                    return ShellStatus::Closed { natural: false };
                 }
             }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => {
+                debug::log(&format!("[SSH {}] Read error: {:?}", config.id, e));
+                return ShellStatus::Closed { natural: false };
+            }
+        }
+        ShellStatus::Open
+    }
+
+    /// Start another shell on the authenticated session; failures are reported to the requester only
+    fn open_shell_channel(config: &SessionConfig, session: &Session, request: ShellRequest) -> Option<ShellChannel> {
+        session.set_blocking(true);
+        let result = Self::start_shell(session);
+        session.set_blocking(false);
+        match result {
+            Ok(channel) => {
+                debug::log(&format!("[SSH {}] Opened additional shell", config.id));
+                *request.state.lock().unwrap() = ConnectionState::Connected;
+                let _ = request.event_tx.send(SshEvent::Connected);
+                Some(ShellChannel {
+                    channel,
+                    event_tx: request.event_tx,
+                    command_rx: request.command_rx,
+                    state: request.state,
+                })
+            }
+            Err(e) => {
+                let error_msg = format!("{:#}", e);
+                debug::log(&format!("[SSH {}] Error opening shell: {}", config.id, error_msg));
+                *request.state.lock().unwrap() = ConnectionState::Error(error_msg.clone());
+                let _ = request.event_tx.send(SshEvent::Error(error_msg));
+                None
+            }
         }
-        let _ = event_tx.send(SshEvent::Disconnected { natural: disconnect_natural });
+    }
+
+    fn close_shell(shell: ShellChannel, natural: bool) {
+        *shell.state.lock().unwrap() = ConnectionState::Disconnected;
+        let _ = shell.event_tx.send(SshEvent::Disconnected { natural });
+    }
+
+    /// Identifies the SSH session this shell runs on; equal for shells sharing a connection
+    pub fn link_id(&self) -> Uuid {
+        self.link_id
     }
 
     pub fn state(&self) -> ConnectionState {
@@ -242,10 +538,90 @@ impl SshConnection {
         let _ = self.command_tx.send(SshCommand::Disconnect);
     }
 
+    /// Answer the keyboard-interactive prompts from `SshEvent::AuthPrompt`
+    pub fn send_auth_response(&self, answers: Vec<String>) {
+        let _ = self.command_tx.send(SshCommand::AuthResponse(answers));
+    }
+
+    /// Send a signal from `SIGNALS` to the shell's foreground job
+    pub fn send_signal(&self, name: &str) {
+        let _ = self.command_tx.send(SshCommand::Signal(name.to_string()));
+    }
+
+    /// Copy local files into `remote_dir` over SFTP; completion arrives as `SshEvent::UploadFinished`
+    pub fn upload(&self, files: Vec<PathBuf>, remote_dir: String) {
+        let _ = self.command_tx.send(SshCommand::Upload { files, remote_dir });
+    }
+
+    /// Open another shell over this connection for a new tab.
+    /// None when this connection is not up.
+    pub fn open_shell(&self, config: SessionConfig) -> Option<SshConnection> {
+        if self.state() != ConnectionState::Connected || self.config.kind == SessionKind::LocalShell {
+            return None;
+        }
+        let (event_tx, event_rx) = mpsc::channel();
+        let (command_tx, command_rx) = mpsc::channel();
+        let state = Arc::new(Mutex::new(ConnectionState::Connecting));
+        let request = ShellRequest {
+            event_tx,
+            command_rx,
+            state: state.clone(),
+        };
+        self.command_tx.send(SshCommand::OpenShell(request)).ok()?;
+        Some(Self {
+            state,
+            event_rx,
+            command_tx,
+            config,
+            link_id: self.link_id,
+        })
+    }
+
     pub fn resize_terminal(&self, cols: u32, rows: u32) {
         let _ = self.command_tx.send(SshCommand::Resize { cols, rows });
     }
 
+    /// Copy the next chunk of an upload (opening its next file as needed); true once every file is sent
+    fn upload_chunk(upload: &mut Upload, buffer: &mut [u8]) -> Result<bool> {
+        if upload.current.is_none() {
+            let Some(path) = upload.pending.next() else {
+                return Ok(true);
+            };
+            if !path.is_file() {
+                anyhow::bail!("{} is not a file", path.display());
+            }
+            let name = path.file_name().context("File has no name")?.to_string_lossy();
+            let remote_path = format!("{}/{}", upload.remote_dir.trim_end_matches('/'), name);
+            let local = std::fs::File::open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            let remote = upload
+                .sftp
+                .create(Path::new(&remote_path))
+                .with_context(|| format!("Failed to create {}", remote_path))?;
+            upload.current = Some((local, remote, remote_path));
+        }
+        let Some((local, remote, remote_path)) = upload.current.as_mut() else {
+            return Ok(false);
+        };
+        let n = local.read(buffer).with_context(|| format!("Failed to read the file for {}", remote_path))?;
+        if n == 0 {
+            upload.current = None;
+        } else {
+            remote.write_all(&buffer[..n]).with_context(|| format!("Failed to write {}", remote_path))?;
+        }
+        Ok(false)
+    }
+
+    fn finish_upload(config: &SessionConfig, upload: Upload, result: Result<()>) {
+        let result = result
+            .map(|()| format!("Uploaded {} file(s) to {}", upload.count, upload.remote_dir))
+            .map_err(|e| format!("Upload failed: {:#}", e));
+        if let Err(e) = &result {
+            debug::log(&format!("[SSH {}] {}", config.id, e));
+        }
+        let _ = upload.event_tx.send(SshEvent::UploadFinished(result));
+    }
+
     fn handle_resize(config: &SessionConfig, session: &Session, channel: &mut Channel, cols: u32, rows: u32) -> Result<()> {
         match config.resize_method {
             ResizeMethod::Ssh => {
@@ -292,10 +668,11 @@ impl SshConnection {
     }
 
     pub fn backspace_sequence(&self) -> &[u8] {
-        match self.config.backspace_key {
-            BackspaceKey::Del => &[0x7F],
-            BackspaceKey::CtrlH => &[0x08],
-        }
+        self.config.backspace_sequence()
+    }
+
+    pub fn delete_sequence(&self) -> &[u8] {
+        self.config.delete_sequence()
     }
 
     #[allow(dead_code)]
@@ -304,6 +681,26 @@ impl SshConnection {
     }
 }
 
+/// Check in the background whether the host accepts TCP connections.
+/// Used to notice when the network comes back for a dropped session.
+pub fn probe_reachability(host: &str, port: u16) -> Receiver<bool> {
+    let (tx, rx) = mpsc::channel();
+    let address = format!("{}:{}", host, port);
+    thread::spawn(move || {
+        let _ = tx.send(is_reachable(&address, Duration::from_secs(REACHABILITY_PROBE_TIMEOUT_SECS)));
+    });
+    rx
+}
+
+/// Whether a TCP connection to `address` (host:port) opens within `timeout`
+fn is_reachable(address: &str, timeout: Duration) -> bool {
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, timeout).is_ok())
+}
+
 impl Drop for SshConnection {
     fn drop(&mut self) {
         self.disconnect();
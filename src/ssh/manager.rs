@@ -1,24 +1,85 @@
-use super::connection::{ConnectionState, SshConnection, SshEvent};
-use crate::config::{AutoReconnect, SessionConfig};
+use super::connection::{probe_reachability, ConnectionState, SshConnection, SshEvent};
+use crate::config::{parse_escaped_sequence, AutoReconnect, BackspaceKey, ConnectionProfile, ReconnectHistory, ReconnectPolicy, ScrollOnOutput, SessionConfig, SessionKind};
+use crate::debug;
 use crate::terminal::emulator::TerminalEmulator;
 use crate::terminal::renderer::TerminalRenderer;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+// How often a dropped session checks whether its host is reachable again
+const REACHABILITY_CHECK_INTERVAL_SECS: u64 = 10;
+// Output kept while waiting for a login script pattern
+const LOGIN_SCRIPT_BUFFER_LIMIT: usize = 4096;
+// Silence after sending XOFF (Ctrl+S) before the session is shown as paused
+const XOFF_HINT_DELAY_MS: u64 = 1000;
+// Flow control bytes sent by Ctrl+S and Ctrl+Q
+const XOFF: u8 = 0x13;
+const XON: u8 = 0x11;
+// How long after an automatic-mode Backspace its echo is checked for a literal ^?
+const BACKSPACE_PROBE_WINDOW_MS: u64 = 500;
+const DEL: u8 = 0x7F;
+const CTRL_H: u8 = 0x08;
+// A connection has to stay up this long before its reconnect attempts count from zero again,
+// so a shell that exits right away can't reconnect forever
+const STABLE_CONNECTION_SECS: u64 = 30;
+
+/// Connection news for one session, shown to the user as a toast
+pub struct Notice {
+    pub text: String,
+    pub is_error: bool,
+}
+
 pub struct ManagedSession {
     pub id: Uuid,
     pub config: SessionConfig,
     pub connection: Option<SshConnection>,
     pub emulator: TerminalEmulator,
     pub renderer: TerminalRenderer,
-    pub title: String,
+    // Title set by the server with OSC 0/2
+    remote_title: Option<String>,
     pub error_message: Option<String>,
+    // Progress or outcome of the last file upload
+    pub transfer_status: Option<String>,
     reconnect_pending: ReconnectState,
     reconnect_attempts: u32,
+    // When the current connection came up
+    connected_since: Option<Instant>,
+    // Waiting for a free slot under the connecting-sessions limit
+    connect_queued: bool,
     is_focused: bool,
     should_close: bool,
     scroll_offset: usize,
     was_at_bottom: bool,
+    scroll_locked: bool,
+    // Time of the last user input (or output, if configured) for the idle timeout
+    last_activity: Cell<Instant>,
+    // When XOFF was last sent with no output since; cleared by XON or any output
+    xoff_sent: Cell<Option<Instant>>,
+    // Automatic Backspace: when DEL was last sent, and whether the shell echoed it as ^?
+    backspace_probe: Cell<Option<Instant>>,
+    backspace_ctrl_h: Cell<bool>,
+    idle_disconnected: bool,
+    // Reachability polling while waiting to reconnect
+    reachability_probe: Option<Receiver<bool>>,
+    last_reachability_check: Option<Instant>,
+    network_was_down: bool,
     last_viewport_size: Option<(usize, usize)>,
+    // Index of the login script step being waited on, and the output seen since the last match
+    login_script_step: Option<usize>,
+    login_script_output: String,
+    // Set once the first connection succeeds, so later connections count as reconnects
+    has_connected: bool,
+    // Reconnect outcomes waiting to be shown as toasts
+    notices: Vec<Notice>,
+    // Keyboard-interactive instructions and prompts waiting for the user's answers
+    auth_prompt: Option<(String, Vec<(String, bool)>)>,
+    // Connection profiles from the app config, shared with the session manager
+    profiles: Rc<Vec<ConnectionProfile>>,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -30,7 +91,6 @@ enum ReconnectState {
 
 impl ManagedSession {
     pub fn new(config: SessionConfig) -> Self {
-        let title = config.name.clone();
         let emulator = TerminalEmulator::new(&config);
         let renderer = TerminalRenderer::new(config.font_size, config.font.clone(), config.cursor_type.clone());
         // Generate a new unique ID for this connection instance
@@ -42,27 +102,64 @@ impl ManagedSession {
             connection: None,
             emulator,
             renderer,
-            title,
+            remote_title: None,
             error_message: None,
+            transfer_status: None,
             reconnect_pending: ReconnectState::None,
             reconnect_attempts: 0,
+            connected_since: None,
+            connect_queued: false,
             is_focused: false,
             should_close: false,
             scroll_offset: 0,
             was_at_bottom: true,
+            scroll_locked: false,
+            last_activity: Cell::new(Instant::now()),
+            xoff_sent: Cell::new(None),
+            backspace_probe: Cell::new(None),
+            backspace_ctrl_h: Cell::new(false),
+            idle_disconnected: false,
+            reachability_probe: None,
+            last_reachability_check: None,
+            network_was_down: false,
             last_viewport_size: None,
+            login_script_step: None,
+            login_script_output: String::new(),
+            has_connected: false,
+            notices: Vec::new(),
+            auth_prompt: None,
+            profiles: Rc::default(),
         }
     }
 
+    /// The session's settings with its connection profile applied, as a new connection uses them
+    fn connect_config(&self) -> SessionConfig {
+        let profile = self.config.profile_id.and_then(|id| self.profiles.iter().find(|p| p.id == id));
+        self.config.with_profile(profile)
+    }
+
     pub fn connect(&mut self) {
         // Disconnect any existing connection first
         if self.connection.is_some() {
             self.disconnect();
         }
         self.error_message = None;
+        self.idle_disconnected = false;
+        self.last_activity.set(Instant::now());
+        self.network_was_down = false;
         self.reconnect_pending = ReconnectState::None;
         self.reconnect_attempts = 0;
-        self.connection = Some(SshConnection::new(self.config.clone()));
+        self.connected_since = None;
+        self.connect_queued = false;
+        self.auth_prompt = None;
+        self.connection = Some(SshConnection::new(self.connect_config()));
+    }
+
+    /// Drop any connection and wait for the session manager to start a new one
+    fn queue_connect(&mut self) {
+        self.disconnect();
+        self.error_message = None;
+        self.connect_queued = true;
     }
 
     pub fn disconnect(&mut self) {
@@ -70,8 +167,10 @@ impl ManagedSession {
             conn.disconnect();
         }
         self.connection = None;
+        self.connect_queued = false;
         self.reconnect_pending = ReconnectState::None;
         self.reconnect_attempts = 0;
+        self.auth_prompt = None;
     }
 
     pub fn update_config(&mut self, config: SessionConfig) {
@@ -81,6 +180,22 @@ impl ManagedSession {
         self.config = config;
     }
 
+    /// Tab title rendered from the session's title template.
+    /// `{title}` is the remote title, or the session name until the server sets one.
+    pub fn title(&self, folder: &str) -> String {
+        let remote_title = self.remote_title.as_deref().unwrap_or(&self.config.name);
+        if self.config.title_template.trim().is_empty() {
+            return remote_title.to_string();
+        }
+        self.config.title_template
+            .replace("{name}", &self.config.name)
+            .replace("{user}", &self.config.username)
+            .replace("{host}", &self.config.host)
+            .replace("{port}", &self.config.port.to_string())
+            .replace("{folder}", folder)
+            .replace("{title}", remote_title)
+    }
+
     pub fn set_focused(&mut self, focused: bool) {
         let was_focused = self.is_focused;
         self.is_focused = focused;
@@ -90,17 +205,22 @@ impl ManagedSession {
         }
     }
 
-    fn should_auto_reconnect(&self) -> bool {
-        match self.config.auto_reconnect {
-            AutoReconnect::Manual => false,
-            AutoReconnect::OnTabFocus | AutoReconnect::Immediate => {
-                self.reconnect_attempts < self.config.reconnect_max_attempts
-            }
-        }
+    fn should_auto_reconnect(&self, natural: bool) -> bool {
+        let allowed = match self.config.reconnect_policy {
+            ReconnectPolicy::Never => false,
+            ReconnectPolicy::OnError => !natural,
+            ReconnectPolicy::Always => true,
+        };
+        allowed
+            && self.config.auto_reconnect != AutoReconnect::Manual
+            && self.reconnect_attempts < self.config.reconnect_max_attempts
     }
 
-    fn schedule_reconnect(&mut self) {
-        if !self.should_auto_reconnect() {
+    fn schedule_reconnect(&mut self, natural: bool) {
+        if self.connected_since.take().is_some_and(|since| since.elapsed() >= Duration::from_secs(STABLE_CONNECTION_SECS)) {
+            self.reconnect_attempts = 0;
+        }
+        if !self.should_auto_reconnect(natural) {
             return;
         }
         match self.config.auto_reconnect {
@@ -125,7 +245,49 @@ impl ManagedSession {
         }
         self.reconnect_attempts += 1;
         self.reconnect_pending = ReconnectState::None;
-        self.connection = Some(SshConnection::new(self.config.clone()));
+        self.connection = Some(SshConnection::new(self.connect_config()));
+    }
+
+    /// Reset the terminal for a new shell as configured, whichever path reconnected
+    fn apply_reconnect_history(&mut self) {
+        let keep_scrollback = match self.config.reconnect_history {
+            ReconnectHistory::Clear => false,
+            ReconnectHistory::KeepScrollback => true,
+            ReconnectHistory::KeepAll => return,
+        };
+        self.emulator.reset(keep_scrollback);
+        self.scroll_offset = usize::MAX;
+        self.was_at_bottom = true;
+    }
+
+    /// Poll the host in the background; true once it becomes reachable after being down
+    fn network_returned(&mut self) -> bool {
+        // A local shell has no host to wait for
+        if self.config.kind == SessionKind::LocalShell {
+            return false;
+        }
+        if let Some(probe) = &self.reachability_probe {
+            match probe.try_recv() {
+                Ok(reachable) => {
+                    self.reachability_probe = None;
+                    if !reachable {
+                        self.network_was_down = true;
+                    } else if self.network_was_down {
+                        self.network_was_down = false;
+                        return true;
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => self.reachability_probe = None,
+            }
+        }
+        let due = self.last_reachability_check
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs(REACHABILITY_CHECK_INTERVAL_SECS));
+        if self.reachability_probe.is_none() && due {
+            self.last_reachability_check = Some(Instant::now());
+            self.reachability_probe = Some(probe_reachability(&self.config.host, self.config.port));
+        }
+        false
     }
 
     pub fn update(&mut self) -> bool {
@@ -134,6 +296,18 @@ impl ManagedSession {
             self.attempt_reconnect();
             return true;
         }
+        // Don't wait for focus when connectivity comes back (e.g. after a Wi-Fi change)
+        if self.reconnect_pending == ReconnectState::PendingOnFocus && self.network_returned() {
+            debug::log(&format!("Network returned, reconnecting {}", self.config.name));
+            self.attempt_reconnect();
+            return true;
+        }
+        if self.idle_timeout_expired() {
+            debug::log(&format!("Disconnecting idle session {}", self.config.name));
+            self.disconnect();
+            self.idle_disconnected = true;
+            return true;
+        }
         // Collect events from connection
         let mut events = Vec::new();
         if let Some(connection) = &self.connection {
@@ -146,48 +320,218 @@ impl ManagedSession {
         for event in events {
             match event {
                 SshEvent::Connected => {
+                    if self.has_connected {
+                        self.apply_reconnect_history();
+                    }
+                    if self.reconnect_attempts > 0 {
+                        self.notices.push(Notice {
+                            text: format!("{} reconnected", self.config.name),
+                            is_error: false,
+                        });
+                    }
+                    self.has_connected = true;
                     self.error_message = None;
-                    self.reconnect_attempts = 0;
+                    self.connected_since = Some(Instant::now());
                     self.last_viewport_size = None;
+                    self.login_script_step = (!self.config.login_script.is_empty()).then_some(0);
+                    self.login_script_output.clear();
+                    // A new shell may have different terminal settings
+                    self.backspace_probe.set(None);
+                    self.backspace_ctrl_h.set(false);
                 }
                 SshEvent::Data(data) => {
+                    self.xoff_sent.set(None);
+                    self.check_backspace_echo(&data);
                     self.emulator.process(&data);
-                    if self.was_at_bottom || self.config.reset_scroll_on_output {
+                    self.send_responses();
+                    self.advance_login_script(&data);
+                    if self.config.idle_output_counts_as_activity {
+                        self.last_activity.set(Instant::now());
+                    }
+                    let scrollback_cleared = self.emulator.take_scrollback_cleared();
+                    // Scroll lock keeps the viewport where the user left it
+                    let follow = match self.config.scroll_on_output {
+                        ScrollOnOutput::Never => false,
+                        ScrollOnOutput::AtBottom => self.was_at_bottom,
+                        ScrollOnOutput::Always => true,
+                    };
+                    if scrollback_cleared || (!self.scroll_locked && follow) {
                         self.scroll_offset = usize::MAX;
                         self.was_at_bottom = true;
                     }
                     if let Some(new_title) = self.emulator.take_title() {
-                        self.title = new_title;
+                        self.remote_title = Some(new_title);
                     }
                 }
                 SshEvent::Disconnected { natural } => {
                     // Clear the connection since the thread has exited
                     self.connection = None;
-                    if natural {
+                    if natural && self.config.reconnect_policy != ReconnectPolicy::Always {
                         // Natural close (user exited shell) - mark for removal
                         self.should_close = true;
                     } else {
                         // Irregular close (network error, etc.) - keep tab open, mark as disconnected
                         if self.error_message.is_none() {
-                            self.schedule_reconnect();
+                            self.schedule_reconnect(natural);
                         }
                     }
                 }
                 SshEvent::Error(msg) => {
+                    self.auth_prompt = None;
+                    if self.reconnect_attempts > 0 {
+                        let last = self.reconnect_attempts >= self.config.reconnect_max_attempts;
+                        self.notices.push(Notice {
+                            text: format!(
+                                "{} failed to reconnect ({}/{}){}",
+                                self.config.name,
+                                self.reconnect_attempts,
+                                self.config.reconnect_max_attempts,
+                                if last { ", giving up" } else { "" }
+                            ),
+                            is_error: true,
+                        });
+                    }
                     self.error_message = Some(msg);
-                    self.schedule_reconnect();
+                    self.schedule_reconnect(false);
+                }
+                SshEvent::UploadFinished(result) => {
+                    self.transfer_status = Some(result.unwrap_or_else(|e| e));
+                }
+                SshEvent::AuthPrompt { instructions, prompts } => {
+                    self.auth_prompt = Some((instructions, prompts));
                 }
             }
         }
         had_events
     }
 
+    /// Upload files over SFTP into the shell's working directory when it is known (OSC 7),
+    /// otherwise into the remote home directory
+    pub fn upload_files(&mut self, files: Vec<PathBuf>) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        let remote_dir = self.emulator.current_directory().unwrap_or(".").to_string();
+        self.transfer_status = Some(format!("Uploading {} file(s)...", files.len()));
+        connection.upload(files, remote_dir);
+    }
+
     pub fn send(&self, data: &[u8]) {
         if let Some(connection) = &self.connection {
+            self.last_activity.set(Instant::now());
+            for &byte in data {
+                match byte {
+                    XOFF => self.xoff_sent.set(Some(Instant::now())),
+                    XON => self.xoff_sent.set(None),
+                    _ => {}
+                }
+            }
+            if data == [DEL] && self.config.backspace_key == BackspaceKey::Auto && !self.backspace_ctrl_h.get() {
+                self.backspace_probe.set(Some(Instant::now()));
+            }
             connection.send(data);
         }
     }
 
+    /// Answer status and attribute queries from the output. Written straight to the
+    /// connection, since automatic replies aren't user activity.
+    fn send_responses(&mut self) {
+        let responses = self.emulator.take_responses();
+        if let Some(connection) = &self.connection {
+            for response in responses {
+                connection.send(&response);
+            }
+        }
+    }
+
+    /// A DEL echoed back as a literal ^? means the server's erase character is Ctrl+H,
+    /// so automatic Backspace switches to it for the rest of the connection
+    fn check_backspace_echo(&self, data: &[u8]) {
+        let Some(sent) = self.backspace_probe.take() else {
+            return;
+        };
+        if sent.elapsed() < Duration::from_millis(BACKSPACE_PROBE_WINDOW_MS) && data.windows(2).any(|w| w == b"^?") {
+            debug::log(&format!("[{}] Backspace echoed as ^?, sending Ctrl+H", self.config.name));
+            self.backspace_ctrl_h.set(true);
+        }
+    }
+
+    /// True while waiting to see whether a sent XOFF paused the output
+    pub fn xoff_pending(&self) -> bool {
+        self.xoff_sent.get().is_some()
+    }
+
+    /// Output has been silent since Ctrl+S was sent, so the server has likely paused it.
+    /// Programs that use Ctrl+S themselves (editors) redraw and clear this right away.
+    pub fn output_paused(&self) -> bool {
+        self.xoff_sent.get()
+            .is_some_and(|sent| sent.elapsed() >= Duration::from_millis(XOFF_HINT_DELAY_MS))
+    }
+
+    /// Match output against the login script, sending responses until every step has run
+    fn advance_login_script(&mut self, data: &[u8]) {
+        let Some(mut step) = self.login_script_step else {
+            return;
+        };
+        self.login_script_output.push_str(&String::from_utf8_lossy(data));
+        while let Some(expect) = self.config.login_script.get(step) {
+            let Some(pos) = self.login_script_output.find(&expect.pattern) else {
+                break;
+            };
+            debug::log(&format!("Login script step {} matched for {}", step + 1, self.config.name));
+            self.send(&parse_escaped_sequence(&expect.response));
+            // Later steps only match output after this one's pattern
+            self.login_script_output.drain(..pos + expect.pattern.len());
+            step += 1;
+        }
+        if step >= self.config.login_script.len() {
+            self.login_script_step = None;
+            self.login_script_output.clear();
+            return;
+        }
+        self.login_script_step = Some(step);
+        if self.login_script_output.len() > LOGIN_SCRIPT_BUFFER_LIMIT {
+            let mut cut = self.login_script_output.len() - LOGIN_SCRIPT_BUFFER_LIMIT;
+            while !self.login_script_output.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.login_script_output.drain(..cut);
+        }
+    }
+
+    fn idle_timeout_expired(&self) -> bool {
+        let Some(minutes) = self.config.idle_disconnect_minutes else {
+            return false;
+        };
+        self.connection.is_some()
+            && self.last_activity.get().elapsed() >= Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    /// Keyboard-interactive instructions and prompts (with whether each answer may be shown)
+    /// the server is waiting on
+    pub fn auth_prompt(&self) -> Option<(&str, &[(String, bool)])> {
+        self.auth_prompt.as_ref().map(|(instructions, prompts)| (instructions.as_str(), prompts.as_slice()))
+    }
+
+    pub fn answer_auth_prompt(&mut self, answers: Vec<String>) {
+        if self.auth_prompt.take().is_some() {
+            if let Some(connection) = &self.connection {
+                connection.send_auth_response(answers);
+            }
+        }
+    }
+
+    /// Give up on the login; the tab stays open to reconnect from
+    pub fn cancel_auth_prompt(&mut self) {
+        self.disconnect();
+        self.error_message = Some(String::from("Authentication cancelled"));
+    }
+
+    /// True after the idle timeout closed the connection (cleared on reconnect)
+    pub fn idle_disconnected(&self) -> bool {
+        self.idle_disconnected
+    }
+
     #[allow(dead_code)]
     pub fn send_key(&self, key: &str) {
         if let Some(connection) = &self.connection {
@@ -200,6 +544,8 @@ impl ManagedSession {
         if self.should_close {
             // Natural close - still show as disconnected until tab is closed
             ConnectionState::Disconnected
+        } else if self.connect_queued {
+            ConnectionState::Connecting
         } else {
             self.connection
                 .as_ref()
@@ -208,6 +554,23 @@ impl ManagedSession {
         }
     }
 
+    /// Text for the locally drawn status line: session name, connection state and clock
+    pub fn status_line_text(&self) -> String {
+        let state = match self.state() {
+            ConnectionState::Connected => String::from("connected"),
+            ConnectionState::Connecting => String::from("connecting"),
+            ConnectionState::Authenticating => String::from("waiting for login"),
+            ConnectionState::Disconnected => String::from("disconnected"),
+            ConnectionState::Error(err) => format!("error: {}", err),
+        };
+        format!(
+            "[{}] {} | {}",
+            self.config.name,
+            state,
+            chrono::Local::now().format("%H:%M")
+        )
+    }
+
     pub fn should_close(&self) -> bool {
         self.should_close
     }
@@ -217,13 +580,44 @@ impl ManagedSession {
         matches!(self.state(), ConnectionState::Connected)
     }
 
+    /// Dropped or failed, as opposed to connected, connecting or closed by the shell exiting
+    pub fn is_down(&self) -> bool {
+        !self.should_close && matches!(self.state(), ConnectionState::Disconnected | ConnectionState::Error(_))
+    }
+
+    /// SSH session this tab's shell runs on, while connected
+    pub fn connection_link(&self) -> Option<Uuid> {
+        self.connection.as_ref().filter(|_| self.is_connected()).map(|c| c.link_id())
+    }
+
+    /// Send a signal to the foreground job; over SSH only signals with a control character are delivered
+    pub fn send_signal(&self, name: &str) {
+        if let Some(connection) = &self.connection {
+            self.last_activity.set(Instant::now());
+            connection.send_signal(name);
+        }
+    }
+
     pub fn backspace_sequence(&self) -> &[u8] {
+        if self.backspace_ctrl_h.get() && !self.config.swap_backspace_delete {
+            return &[CTRL_H];
+        }
         self.connection
             .as_ref()
             .map(|c| c.backspace_sequence())
             .unwrap_or(&[0x7F])
     }
 
+    pub fn delete_sequence(&self) -> &[u8] {
+        if self.backspace_ctrl_h.get() && self.config.swap_backspace_delete {
+            return &[CTRL_H];
+        }
+        self.connection
+            .as_ref()
+            .map(|c| c.delete_sequence())
+            .unwrap_or(b"\x1b[3~")
+    }
+
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
     }
@@ -238,12 +632,23 @@ impl ManagedSession {
         self.was_at_bottom = true;
     }
 
+    pub fn is_scroll_locked(&self) -> bool {
+        self.scroll_locked
+    }
+
+    pub fn set_scroll_locked(&mut self, locked: bool) {
+        self.scroll_locked = locked;
+        if !locked {
+            // Resume following output
+            self.reset_scroll_to_bottom();
+        }
+    }
+
     #[allow(dead_code)]
     pub fn is_reconnecting(&self) -> bool {
         self.reconnect_pending != ReconnectState::None
     }
 
-    #[allow(dead_code)]
     pub fn reconnect_status(&self) -> Option<String> {
         match self.reconnect_pending {
             ReconnectState::None => None,
@@ -260,6 +665,28 @@ impl ManagedSession {
         }
     }
 
+    /// Tab tooltip explaining whether and when this session reconnects on its own
+    pub fn reconnect_summary(&self) -> String {
+        let mut summary = format!("Auto-reconnect: {}", self.config.reconnect_policy.label());
+        if self.config.reconnect_policy != ReconnectPolicy::Never {
+            let when = match self.config.auto_reconnect {
+                AutoReconnect::Immediate => "immediately",
+                AutoReconnect::OnTabFocus | AutoReconnect::Manual => "on focus",
+            };
+            summary.push_str(&format!(" ({})", when));
+        }
+        if let Some(status) = self.reconnect_status() {
+            summary.push('\n');
+            summary.push_str(&status);
+        } else if self.reconnect_attempts > 0 {
+            summary.push_str(&format!(
+                "\nReconnect attempts: {}/{}",
+                self.reconnect_attempts, self.config.reconnect_max_attempts
+            ));
+        }
+        summary
+    }
+
     pub fn check_and_handle_resize(&mut self, cols: usize, rows: usize, send_to_server: bool) -> bool {
         let new_size = (cols, rows);
         if self.last_viewport_size == Some(new_size) {
@@ -280,7 +707,7 @@ impl ManagedSession {
         let total_lines = buffer.total_lines();
         buffer.resize(cols, rows);
         let new_max_scroll = total_lines.saturating_sub(rows);
-        if was_at_bottom {
+        if was_at_bottom && !self.scroll_locked {
             self.scroll_offset = usize::MAX;
         } else {
             self.scroll_offset = current_scroll_offset.min(new_max_scroll);
@@ -292,6 +719,13 @@ impl ManagedSession {
 pub struct SessionManager {
     sessions: Vec<ManagedSession>,
     active_index: Option<usize>,
+    // Session ids, most recently activated first
+    mru: Vec<Uuid>,
+    // Position in `mru` while Ctrl+Tab cycling is in progress
+    mru_cycle: Option<usize>,
+    // Most sessions starting a connection at once (0 = no limit)
+    max_connecting: usize,
+    connection_profiles: Rc<Vec<ConnectionProfile>>,
 }
 
 impl SessionManager {
@@ -299,27 +733,88 @@ impl SessionManager {
         Self {
             sessions: Vec::new(),
             active_index: None,
+            mru: Vec::new(),
+            mru_cycle: None,
+            max_connecting: 0,
+            connection_profiles: Rc::default(),
+        }
+    }
+
+    pub fn set_max_connecting(&mut self, max: usize) {
+        self.max_connecting = max;
+    }
+
+    /// Profiles applied to sessions when they connect; open connections keep what they started with
+    pub fn set_connection_profiles(&mut self, profiles: &[ConnectionProfile]) {
+        self.connection_profiles = Rc::new(profiles.to_vec());
+        for session in &mut self.sessions {
+            session.profiles = self.connection_profiles.clone();
         }
     }
 
     pub fn add_session(&mut self, config: SessionConfig) -> Uuid {
         // Each connection gets its own unique ID (generated in ManagedSession::new)
         // so multiple connections to the same stored session are allowed
-        let session = ManagedSession::new(config);
+        self.push_session(ManagedSession::new(config))
+    }
+
+    /// Open a tab with a new shell on the connection of session `id`, without logging in again.
+    /// None when that session is not connected.
+    pub fn open_shell_on(&mut self, id: Uuid) -> Option<Uuid> {
+        let parent = self.sessions.iter().find(|s| s.id == id)?;
+        let connection = parent.connection.as_ref()?.open_shell(parent.config.clone())?;
+        let mut session = ManagedSession::new(parent.config.clone());
+        session.connection = Some(connection);
+        Some(self.push_session(session))
+    }
+
+    fn push_session(&mut self, mut session: ManagedSession) -> Uuid {
+        session.profiles = self.connection_profiles.clone();
         let id = session.id;
         self.sessions.push(session);
         if self.active_index.is_none() {
             self.active_index = Some(self.sessions.len() - 1);
+            self.mru.insert(0, id);
+        } else {
+            self.mru.push(id);
         }
         id
     }
 
     pub fn connect_session(&mut self, id: Uuid) {
         if let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) {
+            session.queue_connect();
+        }
+        self.start_queued_connections();
+    }
+
+    /// Start queued connections while fewer than `max_connecting` sessions are connecting
+    fn start_queued_connections(&mut self) {
+        // Tabs waiting on the user in a login prompt don't hold up the queue
+        let connecting = self.sessions.iter()
+            .filter(|s| s.connection.as_ref().is_some_and(|c| matches!(c.state(), ConnectionState::Connecting)))
+            .count();
+        let free = match self.max_connecting {
+            0 => usize::MAX,
+            max => max.saturating_sub(connecting),
+        };
+        for session in self.sessions.iter_mut().filter(|s| s.connect_queued).take(free) {
             session.connect();
         }
     }
 
+    pub fn disconnected_count(&self) -> usize {
+        self.sessions.iter().filter(|s| s.is_down()).count()
+    }
+
+    /// Reconnect every dropped tab, leaving connected ones alone
+    pub fn reconnect_disconnected(&mut self) {
+        for session in self.sessions.iter_mut().filter(|s| s.is_down()) {
+            session.queue_connect();
+        }
+        self.start_queued_connections();
+    }
+
     #[allow(dead_code)]
     pub fn disconnect_session(&mut self, id: Uuid) {
         if let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) {
@@ -331,6 +826,8 @@ impl SessionManager {
         if let Some(index) = self.sessions.iter().position(|s| s.id == id) {
             self.sessions[index].disconnect();
             self.sessions.remove(index);
+            self.mru.retain(|&mru_id| mru_id != id);
+            self.mru_cycle = None;
             if let Some(active) = self.active_index {
                 if active >= self.sessions.len() {
                     self.active_index = if self.sessions.is_empty() {
@@ -354,9 +851,15 @@ impl SessionManager {
                 self.active_index = Some(i);
             }
         }
+        self.record_mru();
     }
 
     pub fn set_active_index(&mut self, index: usize) {
+        self.focus_index(index);
+        self.record_mru();
+    }
+
+    fn focus_index(&mut self, index: usize) {
         if index < self.sessions.len() {
             self.active_index = Some(index);
             // Update focus state
@@ -366,6 +869,39 @@ impl SessionManager {
         }
     }
 
+    /// Move the active session to the front of the MRU order (deferred while cycling)
+    fn record_mru(&mut self) {
+        if self.mru_cycle.is_some() {
+            return;
+        }
+        if let Some(id) = self.active_session().map(|s| s.id) {
+            self.mru.retain(|&mru_id| mru_id != id);
+            self.mru.insert(0, id);
+        }
+    }
+
+    /// Step through tabs in most-recently-used order without reordering until committed
+    pub fn cycle_mru(&mut self, forward: bool) {
+        let count = self.mru.len();
+        if count == 0 {
+            return;
+        }
+        let position = self.mru_cycle.unwrap_or(0);
+        let position = if forward { (position + 1) % count } else { (position + count - 1) % count };
+        self.mru_cycle = Some(position);
+        let id = self.mru[position];
+        if let Some(index) = self.sessions.iter().position(|s| s.id == id) {
+            self.focus_index(index);
+        }
+    }
+
+    /// End Ctrl+Tab cycling, making the tab it landed on the most recent one
+    pub fn commit_mru_cycle(&mut self) {
+        if self.mru_cycle.take().is_some() {
+            self.record_mru();
+        }
+    }
+
     pub fn active_session(&self) -> Option<&ManagedSession> {
         self.active_index.and_then(|i| self.sessions.get(i))
     }
@@ -397,6 +933,7 @@ impl SessionManager {
     }
 
     pub fn update_all(&mut self) -> bool {
+        self.start_queued_connections();
         let mut had_activity = false;
         for session in &mut self.sessions {
             if session.update() {
@@ -434,6 +971,21 @@ impl SessionManager {
     }
 
 
+    /// Number of other connected tabs sharing each session's SSH connection
+    pub fn shared_connection_counts(&self) -> HashMap<Uuid, usize> {
+        let mut tabs_per_link: HashMap<Uuid, usize> = HashMap::new();
+        for link in self.sessions.iter().filter_map(|s| s.connection_link()) {
+            *tabs_per_link.entry(link).or_default() += 1;
+        }
+        self.sessions.iter()
+            .filter_map(|s| Some((s.id, tabs_per_link.get(&s.connection_link()?)? - 1)))
+            .collect()
+    }
+
+    pub fn collect_notices(&mut self) -> Vec<Notice> {
+        self.sessions.iter_mut().flat_map(|s| std::mem::take(&mut s.notices)).collect()
+    }
+
     pub fn collect_pending_bells(&mut self) -> Vec<crate::config::BellNotification> {
         let mut bells = Vec::new();
         for session in &mut self.sessions {
@@ -443,6 +995,34 @@ impl SessionManager {
         }
         bells
     }
+
+    /// A session waiting for keyboard-interactive answers, preferring the active one
+    pub fn auth_prompt_session(&self) -> Option<Uuid> {
+        self.active_session()
+            .filter(|s| s.auth_prompt.is_some())
+            .or_else(|| self.sessions.iter().find(|s| s.auth_prompt.is_some()))
+            .map(|s| s.id)
+    }
+
+    /// Drain clipboard writes from all sessions (OSC 52), returning the most recent
+    pub fn take_clipboard_write(&mut self) -> Option<String> {
+        self.sessions.iter_mut().filter_map(|s| s.emulator.take_clipboard_write()).last()
+    }
+
+    /// Drain server resize requests, returning the active session's (rows, cols) if any.
+    /// Requests from background tabs are dropped so they cannot resize the window later.
+    pub fn take_active_resize_request(&mut self) -> Option<(usize, usize)> {
+        let active_index = self.active_index;
+        let mut request = None;
+        for (index, session) in self.sessions.iter_mut().enumerate() {
+            if let Some(size) = session.emulator.take_resize_request() {
+                if Some(index) == active_index {
+                    request = Some(size);
+                }
+            }
+        }
+        request
+    }
 }
 
 impl Default for SessionManager {
@@ -27,12 +27,17 @@ const TAB_SPACING: f32 = 2.0;
 const INACTIVE_TAB_ALPHA: u8 = 80;
 const ACTIVE_BRIGHTEN_AMOUNT: f32 = 0.3;
 const ACTIVE_DARKEN_AMOUNT: f32 = 0.3;
+const SHARED_BADGE_WIDTH: f32 = 18.0;
+const RECONNECT_ALL_COLOR: Color32 = Color32::from_rgb(244, 67, 54);
 
 #[derive(Clone)]
 pub enum TabAction {
     Select(Uuid),
     Close(Uuid),
     Reconnect(Uuid),
+    ReconnectAs(Uuid),
+    ReconnectAll,
+    NewShell(Uuid),
     EditSettings(Uuid),
     None,
 }
@@ -57,23 +62,34 @@ impl TabBar {
     pub fn show_with_data(
         &mut self,
         ui: &mut Ui,
-        sessions: &[(Uuid, String, ConnectionState, Color32)],
+        sessions: &[(Uuid, String, ConnectionState, Color32, usize, String)],
         active_id: Option<Uuid>,
+        disconnected: usize,
     ) -> TabAction {
         let mut action = TabAction::None;
         let mut tab_rects: Vec<(Uuid, egui::Rect)> = Vec::new();
         ui.horizontal(|ui| {
             ui.set_height(TAB_HEIGHT);
             ui.spacing_mut().item_spacing.x = TAB_SPACING;
-            for (index, (id, title, state, accent)) in sessions.iter().enumerate() {
+            for (id, title, state, accent, shared_with, tooltip) in sessions {
                 let is_active = active_id == Some(*id);
-                let (tab_action, rect) = self.show_tab(ui, *id, title, state, *accent, is_active, index);
-                tab_rects.push((*id, rect));
+                let (tab_action, response) = self.show_tab(ui, *id, title, state, *accent, *shared_with, is_active);
+                tab_rects.push((*id, response.rect));
+                response.on_hover_text(format!("{}\n{}", title, tooltip));
                 match tab_action {
                     TabAction::None => {}
                     other => action = other,
                 }
             }
+            if disconnected > 0 {
+                // After sleep or a network drop, bring every dead tab back at once
+                let label = egui::RichText::new(format!("⟳ {}", disconnected)).color(RECONNECT_ALL_COLOR);
+                let response = ui.add(egui::Button::new(label).frame(false))
+                    .on_hover_text(format!("Reconnect {} disconnected tab(s)", disconnected));
+                if response.clicked() {
+                    action = TabAction::ReconnectAll;
+                }
+            }
         });
         action
     }
@@ -85,14 +101,15 @@ impl TabBar {
         title: &str,
         state: &ConnectionState,
         accent: Color32,
+        shared_with: usize,
         is_active: bool,
-        _index: usize,
-    ) -> (TabAction, egui::Rect) {
+    ) -> (TabAction, egui::Response) {
         let mut action = TabAction::None;
         let display_title = self.truncate_title(title, TAB_MAX_WIDTH - TAB_PADDING * 2.0 - CLOSE_BUTTON_SIZE, ui);
         let status_color = match state {
             ConnectionState::Connected => Color32::from_rgb(0, 200, 83),
             ConnectionState::Connecting => Color32::from_rgb(255, 193, 7),
+            ConnectionState::Authenticating => Color32::from_rgb(33, 150, 243),
             ConnectionState::Disconnected => Color32::from_rgb(158, 158, 158),
             ConnectionState::Error(_) => Color32::from_rgb(244, 67, 54),
         };
@@ -121,6 +138,7 @@ impl TabBar {
         let desired_width = (ui.fonts_mut(|f| f.glyph_width(&egui::FontId::default(), 'M')) * display_title.len() as f32
             + TAB_PADDING * 2.0
             + STATUS_INDICATOR_SIZE
+            + if shared_with > 0 { SHARED_BADGE_WIDTH } else { 0.0 }
             + CLOSE_BUTTON_SIZE
             + TAB_SPACING * 2.0)
             .clamp(TAB_MIN_WIDTH, TAB_MAX_WIDTH);
@@ -141,6 +159,14 @@ impl TabBar {
                     action = TabAction::Reconnect(id);
                     ui.close();
                 }
+                if ui.button("Reconnect As...").clicked() {
+                    action = TabAction::ReconnectAs(id);
+                    ui.close();
+                }
+                if ui.button("New Shell on This Connection").clicked() {
+                    action = TabAction::NewShell(id);
+                    ui.close();
+                }
                 if ui.button("Edit Settings...").clicked() {
                     action = TabAction::EditSettings(id);
                     ui.close();
@@ -192,6 +218,22 @@ impl TabBar {
             egui::pos2(rect.max.x - TAB_PADDING - CLOSE_BUTTON_SIZE / 2.0, rect.center().y),
             Vec2::splat(CLOSE_BUTTON_SIZE),
         );
+        if shared_with > 0 {
+            // Link badge for tabs opened with "New Shell on This Connection" and their parent
+            let badge_rect = egui::Rect::from_center_size(
+                egui::pos2(close_rect.min.x - SHARED_BADGE_WIDTH / 2.0, rect.center().y),
+                Vec2::new(SHARED_BADGE_WIDTH, CLOSE_BUTTON_SIZE),
+            );
+            painter.text(
+                badge_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "🔗",
+                egui::FontId::new(11.0, egui::FontFamily::Proportional),
+                text_color.gamma_multiply(0.8),
+            );
+            ui.interact(badge_rect, ui.id().with(("shared", id)), egui::Sense::hover())
+                .on_hover_text(format!("Shares its SSH connection with {} other tab(s)", shared_with));
+        }
         let close_response = ui.interact(close_rect, ui.id().with(("close", id)), egui::Sense::click());
         let close_hovered = close_response.hovered();
         if close_hovered {
@@ -214,7 +256,7 @@ impl TabBar {
         if close_response.clicked() {
             action = TabAction::Close(id);
         }
-        (action, rect)
+        (action, response)
     }
 
     fn truncate_title(&self, title: &str, max_width: f32, ui: &Ui) -> String {
@@ -1,6 +1,12 @@
 use egui::Color32;
 use super::buffer::CellStyle;
 
+// OSC payloads carry inline images, so allow large strings but not unbounded ones
+const MAX_OSC_STRING_LEN: usize = 24 * 1024 * 1024;
+// Parameters and intermediates kept per sequence; extra ones are dropped
+const MAX_PARAMS: usize = 32;
+const MAX_INTERMEDIATES: usize = 4;
+
 const ANSI_COLORS: [Color32; 16] = [
     Color32::from_rgb(0, 0, 0),       // Black
     Color32::from_rgb(170, 0, 0),     // Red
@@ -24,10 +30,11 @@ const ANSI_COLORS: [Color32; 16] = [
 pub enum AnsiAction {
     Print(char),
     Execute(u8),
-    CsiDispatch { params: Vec<u16>, intermediates: Vec<u8>, final_byte: char },
+    // Parameters left empty in the sequence are None, so an explicit 0 can be told apart
+    CsiDispatch { params: Vec<Option<u16>>, intermediates: Vec<u8>, final_byte: char },
     EscDispatch { intermediates: Vec<u8>, final_byte: char },
     OscDispatch { params: Vec<String> },
-    DcsHook { params: Vec<u16>, intermediates: Vec<u8>, final_byte: char },
+    DcsHook { params: Vec<Option<u16>>, intermediates: Vec<u8>, final_byte: char },
     DcsPut(u8),
     DcsUnhook,
 }
@@ -52,8 +59,8 @@ enum State {
 
 pub struct AnsiParser {
     state: State,
-    params: Vec<u16>,
-    current_param: u16,
+    params: Vec<Option<u16>>,
+    current_param: Option<u16>,
     intermediates: Vec<u8>,
     osc_string: String,
 }
@@ -63,7 +70,7 @@ impl AnsiParser {
         Self {
             state: State::Ground,
             params: Vec::new(),
-            current_param: 0,
+            current_param: None,
             intermediates: Vec::new(),
             osc_string: String::new(),
         }
@@ -90,14 +97,27 @@ impl AnsiParser {
 
     fn clear(&mut self) {
         self.params.clear();
-        self.current_param = 0;
+        self.current_param = None;
         self.intermediates.clear();
         self.osc_string.clear();
     }
 
     fn collect_param(&mut self) {
-        self.params.push(self.current_param);
-        self.current_param = 0;
+        let param = self.current_param.take();
+        if self.params.len() < MAX_PARAMS {
+            self.params.push(param);
+        }
+    }
+
+    fn collect_intermediate(&mut self, byte: u8) {
+        if self.intermediates.len() < MAX_INTERMEDIATES {
+            self.intermediates.push(byte);
+        }
+    }
+
+    fn push_digit(&mut self, byte: u8) {
+        let value = self.current_param.unwrap_or(0);
+        self.current_param = Some(value.saturating_mul(10).saturating_add((byte - 0x30) as u16));
     }
 
     fn ground(&mut self, byte: u8) -> Option<AnsiAction> {
@@ -139,7 +159,7 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F => Some(AnsiAction::Execute(byte)),
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::EscapeIntermediate;
                 None
             }
@@ -185,7 +205,7 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F => Some(AnsiAction::Execute(byte)),
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 None
             }
             0x30..=0x7E => {
@@ -212,12 +232,12 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F => Some(AnsiAction::Execute(byte)),
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::CsiIntermediate;
                 None
             }
             0x30..=0x39 => {
-                self.current_param = (byte - 0x30) as u16;
+                self.current_param = Some((byte - 0x30) as u16);
                 self.state = State::CsiParam;
                 None
             }
@@ -231,7 +251,7 @@ impl AnsiParser {
                 None
             }
             0x3C..=0x3F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::CsiParam;
                 None
             }
@@ -261,12 +281,12 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F => Some(AnsiAction::Execute(byte)),
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::CsiIntermediate;
                 None
             }
             0x30..=0x39 => {
-                self.current_param = self.current_param.saturating_mul(10).saturating_add((byte - 0x30) as u16);
+                self.push_digit(byte);
                 None
             }
             0x3A => {
@@ -307,7 +327,7 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F => Some(AnsiAction::Execute(byte)),
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 None
             }
             0x30..=0x3F => {
@@ -360,12 +380,12 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F | 0x7F => None,
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::DcsIntermediate;
                 None
             }
             0x30..=0x39 => {
-                self.current_param = (byte - 0x30) as u16;
+                self.current_param = Some((byte - 0x30) as u16);
                 self.state = State::DcsParam;
                 None
             }
@@ -379,7 +399,7 @@ impl AnsiParser {
                 None
             }
             0x3C..=0x3F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::DcsParam;
                 None
             }
@@ -408,12 +428,12 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F | 0x7F => None,
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 self.state = State::DcsIntermediate;
                 None
             }
             0x30..=0x39 => {
-                self.current_param = self.current_param.saturating_mul(10).saturating_add((byte - 0x30) as u16);
+                self.push_digit(byte);
                 None
             }
             0x3A | 0x3C..=0x3F => {
@@ -449,7 +469,7 @@ impl AnsiParser {
         match byte {
             0x00..=0x17 | 0x19 | 0x1C..=0x1F | 0x7F => None,
             0x20..=0x2F => {
-                self.intermediates.push(byte);
+                self.collect_intermediate(byte);
                 None
             }
             0x30..=0x3F => {
@@ -525,7 +545,9 @@ impl AnsiParser {
                 Some(AnsiAction::OscDispatch { params })
             }
             0x20..=0x7F => {
-                self.osc_string.push(byte as char);
+                if self.osc_string.len() < MAX_OSC_STRING_LEN {
+                    self.osc_string.push(byte as char);
+                }
                 None
             }
             0x1B => {
@@ -535,7 +557,9 @@ impl AnsiParser {
                 Some(AnsiAction::OscDispatch { params })
             }
             _ => {
-                self.osc_string.push(byte as char);
+                if self.osc_string.len() < MAX_OSC_STRING_LEN {
+                    self.osc_string.push(byte as char);
+                }
                 None
             }
         }
@@ -563,8 +587,10 @@ pub fn parse_sgr(params: &[u16], style: &mut CellStyle, default_fg: Color32) {
     while i < params.len() {
         match params[i] {
             0 => {
+                // A hyperlink is not an attribute; it lasts until OSC 8 ends it
                 *style = CellStyle {
                     fg: default_fg,
+                    link: style.link,
                     ..CellStyle::default()
                 };
             }
@@ -633,6 +659,11 @@ pub fn parse_sgr(params: &[u16], style: &mut CellStyle, default_fg: Color32) {
     }
 }
 
+/// Bright counterpart of one of the 8 base ANSI colors, for "bold is bright"
+pub fn bright_variant(color: Color32) -> Option<Color32> {
+    ANSI_COLORS[..8].iter().position(|&c| c == color).map(|i| ANSI_COLORS[i + 8])
+}
+
 fn color_from_256(index: u16) -> Color32 {
     let index = index as usize;
     if index < 16 {
@@ -658,3 +689,56 @@ impl Default for AnsiParser {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Vec<AnsiAction> {
+        let mut parser = AnsiParser::new();
+        bytes.iter().filter_map(|&byte| parser.parse(byte)).collect()
+    }
+
+    #[test]
+    fn params_beyond_the_cap_are_dropped() {
+        let sequence = format!("\x1b[{}m", vec!["1"; 100].join(";"));
+        let actions = parse(sequence.as_bytes());
+        assert_eq!(actions, vec![AnsiAction::CsiDispatch {
+            params: vec![Some(1); MAX_PARAMS],
+            intermediates: Vec::new(),
+            final_byte: 'm',
+        }]);
+    }
+
+    #[test]
+    fn intermediates_beyond_the_cap_are_dropped() {
+        let mut sequence = b"\x1b[1".to_vec();
+        sequence.extend([b' '; 50]);
+        sequence.push(b'q');
+        let actions = parse(&sequence);
+        assert_eq!(actions, vec![AnsiAction::CsiDispatch {
+            params: vec![Some(1)],
+            intermediates: vec![b' '; MAX_INTERMEDIATES],
+            final_byte: 'q',
+        }]);
+
+        let mut sequence = b"\x1b".to_vec();
+        sequence.extend([b'('; 50]);
+        sequence.extend(b"0x");
+        let actions = parse(&sequence);
+        assert_eq!(actions, vec![
+            AnsiAction::EscDispatch { intermediates: vec![b'('; MAX_INTERMEDIATES], final_byte: '0' },
+            AnsiAction::Print('x'),
+        ]);
+    }
+
+    #[test]
+    fn oversized_params_saturate() {
+        let actions = parse(b"\x1b[99999999999;5H");
+        assert_eq!(actions, vec![AnsiAction::CsiDispatch {
+            params: vec![Some(u16::MAX), Some(5)],
+            intermediates: Vec::new(),
+            final_byte: 'H',
+        }]);
+    }
+}
@@ -1,10 +1,34 @@
 use crate::debug;
+use super::graphics::{ImagePixels, PlacedImage};
 use egui::Color32;
-use std::collections::VecDeque;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 const DEFAULT_COLS: usize = 80;
 const DEFAULT_ROWS: usize = 24;
 const MIN_BUFFER_SIZE: usize = 1000;
+const DEFAULT_TAB_WIDTH: usize = 8;
+// Prompt positions remembered for jumping; the oldest is dropped beyond this
+const MAX_PROMPT_MARKS: usize = 1000;
+// Pixel memory of the inline images kept per buffer; the oldest are dropped beyond this
+const MAX_IMAGE_BYTES: usize = 256 * 1024 * 1024;
+// Hyperlink targets kept per buffer; the oldest is dropped beyond this
+const MAX_LINKS: usize = 4096;
+// Largest grid, whatever size is asked for; every line is allocated at the full width
+const MAX_COLS: usize = 2048;
+const MAX_ROWS: usize = 1024;
+// Placeholder stored in the cell covered by the right half of a double-width character
+pub const WIDE_CHAR_SPACER: char = '\0';
+
+/// Emoji and pictographs occupy two cells so following text stays aligned
+pub fn is_wide_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x1F300..=0x1F64F   // Misc symbols and pictographs, emoticons
+        | 0x1F680..=0x1F6FF // Transport and map symbols
+        | 0x1F900..=0x1F9FF // Supplemental symbols and pictographs
+        | 0x1FA70..=0x1FAFF // Symbols and pictographs extended-A
+    )
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CellStyle {
@@ -17,6 +41,8 @@ pub struct CellStyle {
     pub inverse: bool,
     pub dim: bool,
     pub blink: bool,
+    // OSC 8 hyperlink, an id into the buffer's link table
+    pub link: Option<u32>,
 }
 
 impl Default for CellStyle {
@@ -31,6 +57,7 @@ impl Default for CellStyle {
             inverse: false,
             dim: false,
             blink: false,
+            link: None,
         }
     }
 }
@@ -126,6 +153,9 @@ impl Line {
 
     pub fn clear_range(&mut self, start: usize, end: usize, style: CellStyle) {
         let end = end.min(self.cells.len());
+        if start >= end {
+            return;
+        }
         for cell in &mut self.cells[start..end] {
             cell.ch = ' ';
             cell.style = style;
@@ -163,6 +193,76 @@ impl Default for CursorPosition {
     }
 }
 
+/// Character set designated to G0 or G1 with ESC ( / ESC )
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Charset {
+    #[default]
+    Ascii,
+    // Line drawing characters in place of 0x5F-0x7E ("ESC ( 0")
+    DecSpecialGraphics,
+}
+
+impl Charset {
+    /// Charset for the final byte of a designation; sets other than line drawing are shown as ASCII
+    pub fn from_designator(final_byte: char) -> Self {
+        match final_byte {
+            '0' => Charset::DecSpecialGraphics,
+            _ => Charset::Ascii,
+        }
+    }
+
+    fn map(self, ch: char) -> char {
+        if self == Charset::Ascii {
+            return ch;
+        }
+        match ch {
+            '_' => ' ',
+            '`' => '◆',
+            'a' => '▒',
+            'b' => '␉',
+            'c' => '␌',
+            'd' => '␍',
+            'e' => '␊',
+            'f' => '°',
+            'g' => '±',
+            'h' => '␤',
+            'i' => '␋',
+            'j' => '┘',
+            'k' => '┐',
+            'l' => '┌',
+            'm' => '└',
+            'n' => '┼',
+            'o' => '⎺',
+            'p' => '⎻',
+            'q' => '─',
+            'r' => '⎼',
+            's' => '⎽',
+            't' => '├',
+            'u' => '┤',
+            'v' => '┴',
+            'w' => '┬',
+            'x' => '│',
+            'y' => '≤',
+            'z' => '≥',
+            '{' => 'π',
+            '|' => '≠',
+            '}' => '£',
+            '~' => '·',
+            _ => ch,
+        }
+    }
+}
+
+/// Everything DECSC saves and DECRC restores
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    position: CursorPosition,
+    style: CellStyle,
+    origin_mode: bool,
+    charsets: [Charset; 2],
+    shift_out: bool,
+}
+
 /// Unified terminal buffer - one continuous buffer where the "screen" is a
 /// fixed-size viewport into the buffer. The server_screen_start tracks where the
 /// server's view begins in the buffer.
@@ -177,14 +277,57 @@ pub struct TerminalBuffer {
     server_screen_start: usize,
     // Cursor position relative to the screen (0 = first screen line, rows-1 = last)
     cursor: CursorPosition,
-    saved_cursor: CursorPosition,
+    // None until DECSC, or after a reset
+    saved_cursor: Option<SavedCursor>,
     current_style: CellStyle,
+    // G0 and G1, and whether SO has switched printing to G1
+    charsets: [Charset; 2],
+    shift_out: bool,
     // Scroll region (relative to screen, 0-based)
     scroll_top: usize,
     scroll_bottom: usize,
+    // Left/right margins (0-based, inclusive) - full width unless set by the server
+    left_margin: usize,
+    right_margin: usize,
     origin_mode: bool,
     default_fg: Color32,
     default_bg: Color32,
+    // Set when the scrollback was dropped so the view can reset its scroll offset
+    scrollback_cleared: bool,
+    tab_width: usize,
+    // Lines dropped from the front of the buffer so far; keeps command marks stable
+    lines_dropped: usize,
+    // Shell integration (OSC 133) positions as (line + lines_dropped, col)
+    command_output_start: Option<(usize, usize)>,
+    last_command_output: Option<((usize, usize), (usize, usize))>,
+    // Lines where a prompt was seen, as line + lines_dropped (OSC 133;A or the prompt pattern)
+    prompt_marks: VecDeque<usize>,
+    // User pattern that recognizes prompt lines on shells without OSC 133
+    prompt_pattern: Option<Regex>,
+    images: Vec<TerminalImage>,
+    next_image_id: u64,
+    links: BTreeMap<u32, Link>,
+    // Reverse index of `links`, so a repeated URL reuses its id
+    link_ids: HashMap<String, u32>,
+    next_link_id: u32,
+}
+
+/// Target of an OSC 8 hyperlink
+struct Link {
+    url: String,
+    // Last line written with the link, as line + lines_dropped; it is dropped once that line is
+    last_line: usize,
+}
+
+/// A picture drawn over a block of cells (inline image protocols)
+pub struct TerminalImage {
+    pub id: u64,
+    // Top-left cell as (line + lines_dropped, col), like the command marks
+    anchor: (usize, usize),
+    pub cols: usize,
+    pub rows: usize,
+    pub pixels: ImagePixels,
+    size: [usize; 2],
 }
 
 impl TerminalBuffer {
@@ -204,13 +347,29 @@ impl TerminalBuffer {
             max_lines,
             server_screen_start: 0, // Server's screen starts at the beginning of the buffer
             cursor: CursorPosition::default(),
-            saved_cursor: CursorPosition::default(),
+            saved_cursor: None,
             current_style: style,
+            charsets: [Charset::Ascii; 2],
+            shift_out: false,
             scroll_top: 0,
             scroll_bottom: rows - 1,
+            left_margin: 0,
+            right_margin: cols - 1,
             origin_mode: false,
             default_fg,
             default_bg,
+            scrollback_cleared: false,
+            tab_width: DEFAULT_TAB_WIDTH,
+            lines_dropped: 0,
+            command_output_start: None,
+            last_command_output: None,
+            prompt_marks: VecDeque::new(),
+            prompt_pattern: None,
+            images: Vec::new(),
+            next_image_id: 0,
+            links: BTreeMap::new(),
+            link_ids: HashMap::new(),
+            next_link_id: 0,
         }
     }
 
@@ -230,26 +389,68 @@ impl TerminalBuffer {
         theoretical_end.min(self.lines.len().saturating_sub(1))
     }
 
+    /// Change how many history lines are kept; excess old lines are dropped at once
+    pub fn set_max_scrollback(&mut self, max_scrollback: usize) {
+        self.max_lines = max_scrollback.max(MIN_BUFFER_SIZE) + self.rows;
+        self.trim_buffer();
+    }
+
     fn trim_buffer(&mut self) {
+        let lines_dropped = self.lines_dropped;
         while self.lines.len() > self.max_lines {
             self.lines.pop_front();
             // Adjust server_screen_start since we removed a line from the front
             self.server_screen_start = self.server_screen_start.saturating_sub(1);
+            self.lines_dropped += 1;
         }
+        if self.lines_dropped != lines_dropped {
+            self.drop_stale_links();
+        }
+    }
+
+    /// Forget links only written on dropped lines, except one still being written
+    fn drop_stale_links(&mut self) {
+        let (first_line, open) = (self.lines_dropped, self.current_style.link);
+        let link_ids = &mut self.link_ids;
+        self.links.retain(|&id, link| {
+            let keep = link.last_line >= first_line || Some(id) == open;
+            if !keep {
+                link_ids.remove(&link.url);
+            }
+            keep
+        });
     }
 
     /// Ensure buffer has enough lines to access the given index
     /// Called when server sends data that requires more lines
     fn ensure_line_exists(&mut self, buffer_idx: usize) {
         while self.lines.len() <= buffer_idx {
-            self.lines.push_back(Line::with_style(self.cols, self.current_style));
+            self.lines.push_back(Line::with_style(self.cols, self.blank_style()));
+        }
+    }
+
+    /// Ensure the first `count` rows of the server's screen exist in the buffer.
+    /// Erase operations use this so that clearing a fresh screen still blanks the
+    /// whole viewport and later cursor-addressed output lands on the right row.
+    fn ensure_screen_rows(&mut self, count: usize) {
+        if count > 0 {
+            self.ensure_line_exists(self.server_screen_to_buffer(count.min(self.rows) - 1));
         }
     }
 
     pub fn put_char(&mut self, ch: char) {
-        // Handle line wrap
-        if self.cursor.col >= self.cols {
-            self.cursor.col = 0;
+        let ch = self.charsets[self.shift_out as usize].map(ch);
+        let wide = is_wide_char(ch);
+        // Text started inside the side margins wraps at the right margin, back to the left one
+        let (start, edge) = if self.has_side_margins() && self.cursor.col <= self.right_margin + 1 {
+            (self.left_margin, self.right_margin + 1)
+        } else {
+            (0, self.cols)
+        };
+        // Handle line wrap (a wide character that doesn't fit moves to the next line)
+        // On a one-column screen a wide character can never fit, so it doesn't wrap from column 0
+        if self.cursor.col >= edge || (wide && self.cursor.col > start && self.cursor.col + 1 >= edge) {
+            self.cursor.col = start;
             self.new_line();
         }
         // Ensure cursor row is valid
@@ -260,14 +461,26 @@ impl TerminalBuffer {
         // Now write the character
         let col = self.cursor.col;
         let style = self.current_style;
+        if let Some(link) = style.link.and_then(|id| self.links.get_mut(&id)) {
+            link.last_line = idx + self.lines_dropped;
+        }
+        let cols = self.cols;
         if let Some(line) = self.lines.get_mut(idx) {
             line.set(col, Cell::new(ch, style));
             self.cursor.col += 1;
+            // On a one-column screen the second half is cut off, keeping the cursor at the edge
+            if wide && col + 1 < cols {
+                line.set(col + 1, Cell::new(WIDE_CHAR_SPACER, style));
+                self.cursor.col += 1;
+            }
         }
     }
 
     pub fn new_line(&mut self) {
-        if self.cursor.row >= self.scroll_bottom {
+        self.check_prompt_line();
+        if self.cursor.row == self.scroll_bottom && self.has_side_margins() && !self.inside_side_margins() {
+            // Outside the side margins the bottom margin neither scrolls nor moves on
+        } else if self.cursor.row >= self.scroll_bottom {
             self.scroll_up(1);
         } else {
             self.cursor.row += 1;
@@ -275,7 +488,8 @@ impl TerminalBuffer {
     }
 
     pub fn carriage_return(&mut self) {
-        self.cursor.col = 0;
+        // Returns to the left margin, unless the cursor is already left of it
+        self.cursor.col = if self.origin_mode || self.cursor.col >= self.left_margin { self.left_margin } else { 0 };
     }
 
     pub fn backspace(&mut self) {
@@ -285,17 +499,26 @@ impl TerminalBuffer {
     }
 
     pub fn tab(&mut self) {
-        const TAB_WIDTH: usize = 8;
-        let next_tab = ((self.cursor.col / TAB_WIDTH) + 1) * TAB_WIDTH;
+        let next_tab = ((self.cursor.col / self.tab_width) + 1) * self.tab_width;
         self.cursor.col = next_tab.min(self.cols.saturating_sub(1));
     }
 
+    pub fn set_tab_width(&mut self, width: usize) {
+        self.tab_width = width.max(1);
+    }
+
     pub fn scroll_up(&mut self, count: usize) {
+        // Scrolling further than the region is tall only adds blank lines
+        let count = count.min(self.scroll_bottom - self.scroll_top + 1);
+        if self.has_side_margins() {
+            self.shift_block(self.scroll_top, self.scroll_bottom, count, true);
+            return;
+        }
         for _ in 0..count {
             if self.scroll_top == 0 {
                 // Scrolling full screen - add new line at bottom
                 // The old top line becomes history, server_screen_start advances
-                self.lines.push_back(Line::with_style(self.cols, self.current_style));
+                self.lines.push_back(Line::with_style(self.cols, self.blank_style()));
                 self.server_screen_start += 1;
                 self.trim_buffer();
             } else {
@@ -305,57 +528,173 @@ impl TerminalBuffer {
                 if top_idx < self.lines.len() {
                     self.lines.remove(top_idx);
                     let insert_idx = bottom_idx.min(self.lines.len());
-                    self.lines.insert(insert_idx, Line::with_style(self.cols, self.current_style));
+                    self.lines.insert(insert_idx, Line::with_style(self.cols, self.blank_style()));
                 }
             }
         }
     }
 
     pub fn scroll_down(&mut self, count: usize) {
+        let count = count.min(self.scroll_bottom - self.scroll_top + 1);
+        if self.has_side_margins() {
+            self.shift_block(self.scroll_top, self.scroll_bottom, count, false);
+            return;
+        }
         for _ in 0..count {
             let top_idx = self.server_screen_to_buffer(self.scroll_top);
             let bottom_idx = self.server_screen_to_buffer(self.scroll_bottom);
             if bottom_idx < self.lines.len() {
                 self.lines.remove(bottom_idx);
-                self.lines.insert(top_idx, Line::with_style(self.cols, self.current_style));
+                self.lines.insert(top_idx, Line::with_style(self.cols, self.blank_style()));
             }
         }
     }
 
+    /// Left/right margins (DECSLRM) narrower than the screen; scrolling, line and character
+    /// insertion and deletion then only move the cells between them
+    fn has_side_margins(&self) -> bool {
+        self.left_margin > 0 || self.right_margin + 1 < self.cols
+    }
+
+    fn inside_side_margins(&self) -> bool {
+        (self.left_margin..=self.right_margin).contains(&self.cursor.col)
+    }
+
+    /// Move the cells between the side margins in screen rows `top..=bottom` up (or down)
+    /// by `count` rows, blanking the rows left behind
+    fn shift_block(&mut self, top: usize, bottom: usize, count: usize, up: bool) {
+        self.ensure_line_exists(self.server_screen_to_buffer(bottom));
+        let blank = Cell { ch: ' ', style: self.blank_style() };
+        let rows: Vec<usize> = if up { (top..=bottom).collect() } else { (top..=bottom).rev().collect() };
+        for (i, &row) in rows.iter().enumerate() {
+            let dst = self.server_screen_to_buffer(row);
+            let src = rows.get(i + count).map(|&row| self.server_screen_to_buffer(row));
+            for col in self.left_margin..=self.right_margin {
+                let cell = src
+                    .and_then(|src| self.lines[src].get(col).cloned())
+                    .unwrap_or_else(|| blank.clone());
+                self.lines[dst].set(col, cell);
+            }
+        }
+    }
+
+    /// Move the cursor to a 0-based position. In origin mode the position is
+    /// relative to the scroll region and margins, and clamped to stay inside them.
     pub fn set_cursor_position(&mut self, row: usize, col: usize) {
-        let row = if self.origin_mode {
+        self.set_cursor_row(row);
+        self.set_cursor_col(col);
+    }
+
+    /// Move the cursor to a 0-based row, keeping the column (VPA)
+    pub fn set_cursor_row(&mut self, row: usize) {
+        self.cursor.row = if self.origin_mode {
             (self.scroll_top + row).min(self.scroll_bottom)
         } else {
             row.min(self.rows.saturating_sub(1))
         };
-        self.cursor.row = row;
-        self.cursor.col = col.min(self.cols.saturating_sub(1));
+    }
+
+    /// Move the cursor to a 0-based column, keeping the row (CHA/HPA)
+    pub fn set_cursor_col(&mut self, col: usize) {
+        self.cursor.col = if self.origin_mode {
+            (self.left_margin + col).min(self.right_margin)
+        } else {
+            col.min(self.cols.saturating_sub(1))
+        };
+    }
+
+    /// Cursor position as seen by the server, i.e. relative to the region in origin mode
+    pub fn cursor_relative(&self) -> CursorPosition {
+        // A cursor waiting to wrap sits one past the edge; reports give the last column instead
+        if self.origin_mode {
+            CursorPosition {
+                row: self.cursor.row.saturating_sub(self.scroll_top),
+                col: self.cursor.col.min(self.right_margin).saturating_sub(self.left_margin),
+            }
+        } else {
+            CursorPosition { row: self.cursor.row, col: self.cursor.col.min(self.cols - 1) }
+        }
     }
 
     pub fn move_cursor_up(&mut self, count: usize) {
-        let min_row = if self.origin_mode { self.scroll_top } else { 0 };
+        // Relative moves stop at the top margin when starting inside the region
+        let min_row = if self.origin_mode || self.cursor.row >= self.scroll_top { self.scroll_top } else { 0 };
         self.cursor.row = self.cursor.row.saturating_sub(count).max(min_row);
     }
 
     pub fn move_cursor_down(&mut self, count: usize) {
-        let max_row = if self.origin_mode { self.scroll_bottom } else { self.rows - 1 };
+        let max_row = if self.origin_mode || self.cursor.row <= self.scroll_bottom {
+            self.scroll_bottom
+        } else {
+            self.rows.saturating_sub(1)
+        };
         self.cursor.row = (self.cursor.row + count).min(max_row);
     }
 
     pub fn move_cursor_left(&mut self, count: usize) {
-        self.cursor.col = self.cursor.col.saturating_sub(count);
+        let min_col = if self.origin_mode || self.cursor.col >= self.left_margin { self.left_margin } else { 0 };
+        self.cursor.col = self.cursor.col.saturating_sub(count).max(min_col);
     }
 
     pub fn move_cursor_right(&mut self, count: usize) {
-        self.cursor.col = (self.cursor.col + count).min(self.cols.saturating_sub(1));
+        let max_col = if self.origin_mode || self.cursor.col <= self.right_margin {
+            self.right_margin
+        } else {
+            self.cols.saturating_sub(1)
+        };
+        self.cursor.col = (self.cursor.col + count).min(max_col);
     }
 
+    /// DECSC: save the position along with the style, origin mode and character sets
     pub fn save_cursor(&mut self) {
-        self.saved_cursor = self.cursor;
+        self.saved_cursor = Some(SavedCursor {
+            position: self.cursor,
+            style: self.current_style,
+            origin_mode: self.origin_mode,
+            charsets: self.charsets,
+            shift_out: self.shift_out,
+        });
     }
 
+    /// DECRC: with nothing saved, home the cursor and return to the default state
     pub fn restore_cursor(&mut self) {
-        self.cursor = self.saved_cursor;
+        match self.saved_cursor {
+            Some(saved) => {
+                self.cursor = saved.position;
+                self.current_style = saved.style;
+                self.origin_mode = saved.origin_mode;
+                self.charsets = saved.charsets;
+                self.shift_out = saved.shift_out;
+            }
+            None => {
+                self.cursor = CursorPosition::default();
+                self.reset_style();
+                self.origin_mode = false;
+                self.reset_charsets();
+            }
+        }
+    }
+
+    /// Forget the DECSC state so a later restore homes the cursor
+    pub fn clear_saved_cursor(&mut self) {
+        self.saved_cursor = None;
+    }
+
+    /// Designate `charset` to G0 (`slot` 0) or G1 (`slot` 1)
+    pub fn designate_charset(&mut self, slot: usize, charset: Charset) {
+        if let Some(designated) = self.charsets.get_mut(slot) {
+            *designated = charset;
+        }
+    }
+
+    /// SO prints from G1, SI returns to G0
+    pub fn set_shift_out(&mut self, shift_out: bool) {
+        self.shift_out = shift_out;
+    }
+
+    pub fn reset_charsets(&mut self) {
+        self.charsets = [Charset::Ascii; 2];
+        self.shift_out = false;
     }
 
     pub fn erase_in_display(&mut self, mode: u8) {
@@ -363,8 +702,7 @@ impl TerminalBuffer {
         let cursor_col = self.cursor.col;
         let cols = self.cols;
         let rows = self.rows;
-        let style = self.current_style;
-        // Only erase lines that exist - do NOT create new lines
+        let style = self.blank_style();
         let screen_start = self.server_screen_start;
         debug::log(&format!(
             "[ERASE] mode={}, cursor=({},{}), rows={}, screen_start={}, total_lines={}",
@@ -378,6 +716,7 @@ impl TerminalBuffer {
                     screen_start + cursor_row, cursor_row, 
                     screen_start + rows.saturating_sub(1), rows.saturating_sub(1)
                 ));
+                self.ensure_screen_rows(rows);
                 if let Some(line) = self.lines.get_mut(screen_start + cursor_row) {
                     line.clear_range(cursor_col, cols, style);
                 }
@@ -389,6 +728,7 @@ impl TerminalBuffer {
             }
             1 => {
                 // Erase from start of display to cursor
+                self.ensure_screen_rows(cursor_row + 1);
                 for row in 0..cursor_row {
                     if let Some(line) = self.lines.get_mut(screen_start + row) {
                         line.clear(style);
@@ -400,11 +740,17 @@ impl TerminalBuffer {
             }
             2 | 3 => {
                 // Erase entire display
+                self.ensure_screen_rows(rows);
                 for row in 0..rows {
                     if let Some(line) = self.lines.get_mut(screen_start + row) {
                         line.clear(style);
                     }
                 }
+                self.drop_screen_images();
+                // Mode 3 additionally erases the saved lines (scrollback)
+                if mode == 3 {
+                    self.clear_scrollback();
+                }
             }
             _ => {}
         }
@@ -413,9 +759,9 @@ impl TerminalBuffer {
     pub fn erase_in_line(&mut self, mode: u8) {
         let cursor_col = self.cursor.col;
         let cols = self.cols;
-        let style = self.current_style;
+        let style = self.blank_style();
         let idx = self.server_screen_to_buffer(self.cursor.row);
-        // Only erase if line exists - do NOT create new lines
+        self.ensure_line_exists(idx);
         if let Some(line) = self.lines.get_mut(idx) {
             match mode {
                 0 => line.clear_range(cursor_col, cols, style),
@@ -427,19 +773,39 @@ impl TerminalBuffer {
     }
 
     pub fn insert_lines(&mut self, count: usize) {
+        // IL/DL do nothing with the cursor outside the scroll region
+        if self.cursor.row < self.scroll_top || self.cursor.row > self.scroll_bottom {
+            return;
+        }
         let count = count.min(self.scroll_bottom - self.cursor.row + 1);
+        if self.has_side_margins() {
+            if self.inside_side_margins() {
+                self.shift_block(self.cursor.row, self.scroll_bottom, count, false);
+            }
+            return;
+        }
         for _ in 0..count {
             let bottom_idx = self.server_screen_to_buffer(self.scroll_bottom);
             let cursor_idx = self.server_screen_to_buffer(self.cursor.row);
             if bottom_idx < self.lines.len() {
                 self.lines.remove(bottom_idx);
             }
-            self.lines.insert(cursor_idx, Line::with_style(self.cols, self.current_style));
+            self.lines.insert(cursor_idx, Line::with_style(self.cols, self.blank_style()));
         }
     }
 
     pub fn delete_lines(&mut self, count: usize) {
+        // IL/DL do nothing with the cursor outside the scroll region
+        if self.cursor.row < self.scroll_top || self.cursor.row > self.scroll_bottom {
+            return;
+        }
         let count = count.min(self.scroll_bottom - self.cursor.row + 1);
+        if self.has_side_margins() {
+            if self.inside_side_margins() {
+                self.shift_block(self.cursor.row, self.scroll_bottom, count, true);
+            }
+            return;
+        }
         for _ in 0..count {
             let cursor_idx = self.server_screen_to_buffer(self.cursor.row);
             let bottom_idx = self.server_screen_to_buffer(self.scroll_bottom);
@@ -447,45 +813,64 @@ impl TerminalBuffer {
                 self.lines.remove(cursor_idx);
             }
             let insert_idx = bottom_idx.min(self.lines.len());
-            self.lines.insert(insert_idx, Line::with_style(self.cols, self.current_style));
+            self.lines.insert(insert_idx, Line::with_style(self.cols, self.blank_style()));
         }
     }
 
     pub fn insert_chars(&mut self, count: usize) {
         let cursor_col = self.cursor.col;
-        let style = self.current_style;
+        let style = self.blank_style();
         let idx = self.server_screen_to_buffer(self.cursor.row);
+        let Some(end) = self.char_shift_end() else {
+            return;
+        };
         // Only operate on lines that exist - do NOT create new lines
         if let Some(line) = self.lines.get_mut(idx) {
+            // Shifting more than the rest of the line (or margin) just blanks it
+            let end = end.min(line.len());
+            let count = count.min(end.saturating_sub(cursor_col));
             for _ in 0..count {
-                if cursor_col < line.len() {
-                    line.cells.pop();
-                    line.cells.insert(cursor_col, Cell { ch: ' ', style });
-                }
+                line.cells.remove(end - 1);
+                line.cells.insert(cursor_col, Cell { ch: ' ', style });
             }
         }
     }
 
     pub fn delete_chars(&mut self, count: usize) {
         let cursor_col = self.cursor.col;
-        let style = self.current_style;
+        let style = self.blank_style();
         let idx = self.server_screen_to_buffer(self.cursor.row);
+        let Some(end) = self.char_shift_end() else {
+            return;
+        };
         // Only operate on lines that exist - do NOT create new lines
         if let Some(line) = self.lines.get_mut(idx) {
+            let end = end.min(line.len());
+            let count = count.min(end.saturating_sub(cursor_col));
             for _ in 0..count {
-                if cursor_col < line.len() {
-                    line.cells.remove(cursor_col);
-                    line.cells.push(Cell { ch: ' ', style });
-                }
+                line.cells.remove(cursor_col);
+                line.cells.insert(end - 1, Cell { ch: ' ', style });
             }
         }
     }
 
+    /// Column after the last one ICH/DCH shift: the end of the line, or the right margin when
+    /// side margins are set; None when the cursor is outside them
+    fn char_shift_end(&self) -> Option<usize> {
+        if !self.has_side_margins() {
+            Some(usize::MAX)
+        } else if self.inside_side_margins() {
+            Some(self.right_margin + 1)
+        } else {
+            None
+        }
+    }
+
     pub fn erase_chars(&mut self, count: usize) {
         let cursor_col = self.cursor.col;
-        let style = self.current_style;
+        let style = self.blank_style();
         let idx = self.server_screen_to_buffer(self.cursor.row);
-        // Only operate on lines that exist - do NOT create new lines
+        self.ensure_line_exists(idx);
         if let Some(line) = self.lines.get_mut(idx) {
             let end_col = (cursor_col + count).min(line.len());
             for col in cursor_col..end_col {
@@ -511,14 +896,24 @@ impl TerminalBuffer {
         self.scroll_bottom = self.rows.saturating_sub(1);
     }
 
+    pub fn set_margins(&mut self, left: usize, right: usize) {
+        let left = left.min(self.cols.saturating_sub(1));
+        let right = right.min(self.cols.saturating_sub(1));
+        if left < right {
+            self.left_margin = left;
+            self.right_margin = right;
+        }
+    }
+
+    pub fn reset_margins(&mut self) {
+        self.left_margin = 0;
+        self.right_margin = self.cols.saturating_sub(1);
+    }
+
     pub fn set_origin_mode(&mut self, enabled: bool) {
         self.origin_mode = enabled;
-        if enabled {
-            self.cursor.row = self.scroll_top;
-        } else {
-            self.cursor.row = 0;
-        }
-        self.cursor.col = 0;
+        // DECOM homes the cursor to the region origin (or the screen origin when disabled)
+        self.set_cursor_position(0, 0);
     }
 
     pub fn set_style(&mut self, style: CellStyle) {
@@ -529,6 +924,48 @@ impl TerminalBuffer {
         self.current_style
     }
 
+    /// Style for cells blanked by erase, insert and scroll: the current colors without the link
+    fn blank_style(&self) -> CellStyle {
+        CellStyle {
+            link: None,
+            ..self.current_style
+        }
+    }
+
+    /// OSC 8: start writing text that links to `url`, or stop with None
+    pub fn set_link(&mut self, url: Option<&str>) {
+        self.current_style.link = url.map(|url| self.link_id(url));
+    }
+
+    fn link_id(&mut self, url: &str) -> u32 {
+        if let Some(&id) = self.link_ids.get(url) {
+            return id;
+        }
+        if self.links.len() >= MAX_LINKS {
+            // Evict the oldest link, but never the one text is still being written with
+            let open = self.current_style.link;
+            if let Some(oldest) = self.links.keys().copied().find(|&id| Some(id) != open) {
+                if let Some(link) = self.links.remove(&oldest) {
+                    self.link_ids.remove(&link.url);
+                }
+            }
+        }
+        let id = self.next_link_id;
+        self.next_link_id = self.next_link_id.wrapping_add(1);
+        self.links.insert(id, Link {
+            url: url.to_string(),
+            last_line: self.cursor_mark().0,
+        });
+        self.link_ids.insert(url.to_string(), id);
+        id
+    }
+
+    /// URL of the hyperlink on a cell, if any
+    pub fn link_at(&self, line: usize, col: usize) -> Option<&str> {
+        let id = self.lines.get(line)?.get(col)?.style.link?;
+        self.links.get(&id).map(|link| link.url.as_str())
+    }
+
     pub fn reset_style(&mut self) {
         self.current_style = CellStyle {
             fg: self.default_fg,
@@ -540,6 +977,15 @@ impl TerminalBuffer {
         self.cursor
     }
 
+    pub fn origin_mode(&self) -> bool {
+        self.origin_mode
+    }
+
+    /// Scroll region as 0-based inclusive (top, bottom) screen rows
+    pub fn scroll_region(&self) -> (usize, usize) {
+        (self.scroll_top, self.scroll_bottom)
+    }
+
     pub fn cols(&self) -> usize {
         self.cols
     }
@@ -579,15 +1025,159 @@ impl TerminalBuffer {
         self.lines.get(index)
     }
 
-    #[allow(dead_code)]
+    /// Drop all history lines above the server's screen
     pub fn clear_scrollback(&mut self) {
-        // Keep only the screen lines
-        while self.lines.len() > self.rows {
-            self.lines.pop_front();
+        let dropped = self.server_screen_start.min(self.lines.len());
+        self.lines.drain(..dropped);
+        self.lines_dropped += dropped;
+        self.server_screen_start = 0;
+        self.scrollback_cleared = true;
+        if dropped > 0 {
+            self.drop_stale_links();
         }
     }
 
-    pub fn get_text_range(&self, start_row: usize, start_col: usize, end_row: usize, end_col: usize) -> String {
+    pub fn take_scrollback_cleared(&mut self) -> bool {
+        std::mem::take(&mut self.scrollback_cleared)
+    }
+
+    /// Number of lines trimmed from the front so far; `index + lines_dropped()` is a
+    /// stable line number that survives scrollback trimming
+    pub fn lines_dropped(&self) -> usize {
+        self.lines_dropped
+    }
+
+    fn cursor_mark(&self) -> (usize, usize) {
+        (self.server_screen_to_buffer(self.cursor.row) + self.lines_dropped, self.cursor.col)
+    }
+
+    /// Place an image with its top-left corner at the cursor, then move the cursor
+    /// to the start of the line below it
+    pub fn place_image(&mut self, image: PlacedImage) {
+        self.ensure_line_exists(self.server_screen_to_buffer(self.cursor.row));
+        let lines_dropped = self.lines_dropped;
+        self.images.retain(|img| img.anchor.0 >= lines_dropped);
+        let image_bytes = |[width, height]: [usize; 2]| width * height * std::mem::size_of::<Color32>();
+        let mut total = image_bytes(image.size) + self.images.iter().map(|img| image_bytes(img.size)).sum::<usize>();
+        while total > MAX_IMAGE_BYTES && !self.images.is_empty() {
+            total -= image_bytes(self.images.remove(0).size);
+        }
+        let rows = image.rows;
+        self.images.push(TerminalImage {
+            id: self.next_image_id,
+            anchor: self.cursor_mark(),
+            cols: image.cols,
+            rows,
+            pixels: image.pixels,
+            size: image.size,
+        });
+        self.next_image_id += 1;
+        for _ in 0..rows {
+            self.new_line();
+        }
+        self.carriage_return();
+    }
+
+    /// Images still in the buffer, with the buffer line and column of their top-left cell
+    pub fn images(&self) -> impl Iterator<Item = (usize, usize, &TerminalImage)> {
+        self.images.iter().filter_map(|img| {
+            let line = img.anchor.0.checked_sub(self.lines_dropped)?;
+            Some((line, img.anchor.1, img))
+        })
+    }
+
+    /// Forget images anchored on the server's screen (it was erased)
+    pub fn drop_screen_images(&mut self) {
+        let screen_mark = self.server_screen_start + self.lines_dropped;
+        self.images.retain(|img| img.anchor.0 < screen_mark);
+    }
+
+    pub fn set_prompt_pattern(&mut self, pattern: Option<Regex>) {
+        self.prompt_pattern = pattern;
+    }
+
+    /// OSC 133;A - a prompt starts on the cursor line
+    pub fn mark_prompt(&mut self) {
+        self.add_prompt_mark(self.cursor_mark().0);
+    }
+
+    fn add_prompt_mark(&mut self, mark: usize) {
+        if self.prompt_marks.back() == Some(&mark) {
+            return;
+        }
+        if self.prompt_marks.len() >= MAX_PROMPT_MARKS {
+            self.prompt_marks.pop_front();
+        }
+        self.prompt_marks.push_back(mark);
+    }
+
+    /// The cursor is leaving its line: record it as a prompt if it matches the prompt pattern
+    fn check_prompt_line(&mut self) {
+        let Some(pattern) = &self.prompt_pattern else {
+            return;
+        };
+        let idx = self.server_screen_to_buffer(self.cursor.row);
+        let is_prompt = self.lines.get(idx).is_some_and(|line| pattern.is_match(line.to_string().trim_end()));
+        if is_prompt {
+            self.add_prompt_mark(idx + self.lines_dropped);
+        }
+    }
+
+    /// Buffer line indices of known prompts, oldest first
+    pub fn prompt_lines(&self) -> impl Iterator<Item = usize> + '_ {
+        self.prompt_marks.iter().filter_map(|mark| mark.checked_sub(self.lines_dropped))
+    }
+
+    /// Heuristic output range for shells without OSC 133: the lines between the last
+    /// matched prompt and the cursor line
+    fn output_since_last_prompt(&self) -> Option<(usize, usize, usize, usize)> {
+        self.prompt_pattern.as_ref()?;
+        let cursor_line = self.server_screen_to_buffer(self.cursor.row);
+        let prompt_line = self.prompt_lines().filter(|&line| line < cursor_line).last()?;
+        let (start_row, end_row) = (prompt_line + 1, cursor_line.checked_sub(1)?);
+        if start_row > end_row {
+            return None;
+        }
+        let end_col = self.get_line(end_row)?.len().saturating_sub(1);
+        Some((start_row, 0, end_row, end_col))
+    }
+
+    /// OSC 133;C - command output begins at the cursor
+    pub fn mark_command_start(&mut self) {
+        self.command_output_start = Some(self.cursor_mark());
+    }
+
+    /// OSC 133;D - command finished; its output ends just before the cursor
+    pub fn mark_command_end(&mut self) {
+        if let Some(start) = self.command_output_start.take() {
+            self.last_command_output = Some((start, self.cursor_mark()));
+        }
+    }
+
+    /// Range of the most recent complete command output as
+    /// (start_row, start_col, end_row, end_col), inclusive, in buffer line indices
+    pub fn last_command_output(&self) -> Option<(usize, usize, usize, usize)> {
+        let Some(((start_mark, start_col), (end_mark, end_col))) = self.last_command_output else {
+            return self.output_since_last_prompt();
+        };
+        let start_row = start_mark.checked_sub(self.lines_dropped)?;
+        let end_row = end_mark.checked_sub(self.lines_dropped)?;
+        // The end mark is exclusive: step back one cell, onto the previous line if needed
+        let (end_row, end_col) = if end_col > 0 {
+            (end_row, end_col - 1)
+        } else {
+            let prev_row = end_row.checked_sub(1)?;
+            (prev_row, self.get_line(prev_row)?.len().saturating_sub(1))
+        };
+        if (end_row, end_col) < (start_row, start_col) {
+            return None;
+        }
+        Some((start_row, start_col, end_row, end_col))
+    }
+
+    /// Text between two inclusive positions. Trailing whitespace is trimmed from each line
+    /// unless `preserve_trailing_spaces` is set, which keeps cells verbatim (tables, ASCII art).
+    pub fn get_text_range(&self, start_row: usize, start_col: usize, end_row: usize, end_col: usize, preserve_trailing_spaces: bool) -> String {
         let mut result = String::new();
         for row in start_row..=end_row {
             if let Some(line) = self.get_line(row) {
@@ -596,12 +1186,17 @@ impl TerminalBuffer {
                 let mut line_text = String::new();
                 for col in col_start..col_end.min(line.len()) {
                     if let Some(cell) = line.get(col) {
-                        line_text.push(cell.ch);
+                        if cell.ch != WIDE_CHAR_SPACER {
+                            line_text.push(cell.ch);
+                        }
                     }
                 }
-                // Trim trailing whitespace from each line before adding to result
-                let trimmed = line_text.trim_end();
-                result.push_str(trimmed);
+                // Trim trailing whitespace unless the caller wants cells verbatim
+                if preserve_trailing_spaces {
+                    result.push_str(&line_text);
+                } else {
+                    result.push_str(line_text.trim_end());
+                }
                 if row < end_row && !line.is_wrapped() {
                     result.push('\n');
                 }
@@ -624,8 +1219,314 @@ impl TerminalBuffer {
     }
 
     pub fn resize(&mut self, cols: usize, rows: usize) {
-        self.cols = cols;
-        self.rows = rows;
-        self.scroll_bottom = rows.saturating_sub(1);
+        // A window dragged down to nothing still leaves a 1x1 screen to write into
+        self.cols = cols.clamp(1, MAX_COLS);
+        self.rows = rows.clamp(1, MAX_ROWS);
+        self.reset_scroll_region();
+        self.reset_margins();
+        // The column may sit one past the edge (pending wrap), the row must be on screen
+        self.cursor.row = self.cursor.row.min(self.rows - 1);
+        self.cursor.col = self.cursor.col.min(self.cols);
+        if let Some(saved) = &mut self.saved_cursor {
+            saved.position.row = saved.position.row.min(self.rows - 1);
+            saved.position.col = saved.position.col.min(self.cols);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(cols: usize, rows: usize) -> TerminalBuffer {
+        let mut buffer = TerminalBuffer::new(100, Color32::WHITE, Color32::BLACK);
+        buffer.resize(cols, rows);
+        buffer
+    }
+
+    fn cursor(buffer: &TerminalBuffer) -> (usize, usize) {
+        (buffer.cursor().row, buffer.cursor().col)
+    }
+
+    #[test]
+    fn images_beyond_the_memory_cap_drop_the_oldest() {
+        let mut buffer = buffer(20, 10);
+        // 64 MiB each by their pixel size; the pixels themselves never arrive
+        for _ in 0..5 {
+            buffer.place_image(PlacedImage { pixels: ImagePixels::default(), size: [4096, 4096], cols: 1, rows: 1 });
+        }
+        let ids: Vec<u64> = buffer.images().map(|(_, _, image)| image.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn resize_clamps_to_the_size_limits() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_cursor_position(9, 19);
+        buffer.resize(100_000, 50_000);
+        assert_eq!((buffer.cols(), buffer.rows()), (MAX_COLS, MAX_ROWS));
+        assert_eq!(buffer.scroll_region(), (0, MAX_ROWS - 1));
+        buffer.set_cursor_position(usize::MAX, usize::MAX);
+        assert_eq!(cursor(&buffer), (MAX_ROWS - 1, MAX_COLS - 1));
+        write(&mut buffer, "a");
+        buffer.resize(0, 0);
+        assert_eq!((buffer.cols(), buffer.rows()), (1, 1));
+        assert_eq!(cursor(&buffer).0, 0);
+    }
+
+    #[test]
+    fn origin_mode_addresses_rows_from_the_scroll_region() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_scroll_region(2, 6);
+        buffer.set_origin_mode(true);
+        assert_eq!(cursor(&buffer), (2, 0));
+        buffer.set_cursor_position(3, 4);
+        assert_eq!(cursor(&buffer), (5, 4));
+        assert_eq!(buffer.cursor_relative(), CursorPosition { row: 3, col: 4 });
+        // Past the region stops at its bottom margin
+        buffer.set_cursor_row(50);
+        assert_eq!(cursor(&buffer), (6, 4));
+        assert_eq!(buffer.cursor_relative().row, 4);
+    }
+
+    #[test]
+    fn origin_mode_addresses_columns_from_the_margins() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_margins(5, 12);
+        buffer.set_origin_mode(true);
+        assert_eq!(cursor(&buffer), (0, 5));
+        buffer.set_cursor_col(3);
+        assert_eq!(cursor(&buffer), (0, 8));
+        assert_eq!(buffer.cursor_relative(), CursorPosition { row: 0, col: 3 });
+        buffer.set_cursor_col(50);
+        assert_eq!(cursor(&buffer), (0, 12));
+        assert_eq!(buffer.cursor_relative().col, 7);
+    }
+
+    #[test]
+    fn origin_mode_addresses_inside_region_and_margins_together() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_scroll_region(3, 7);
+        buffer.set_margins(4, 9);
+        buffer.set_origin_mode(true);
+        buffer.set_cursor_position(0, 0);
+        assert_eq!(cursor(&buffer), (3, 4));
+        buffer.set_cursor_position(100, 100);
+        assert_eq!(cursor(&buffer), (7, 9));
+        assert_eq!(buffer.cursor_relative(), CursorPosition { row: 4, col: 5 });
+    }
+
+    #[test]
+    fn without_origin_mode_addressing_ignores_region_and_margins() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_scroll_region(3, 7);
+        buffer.set_margins(4, 9);
+        buffer.set_cursor_position(1, 2);
+        assert_eq!(cursor(&buffer), (1, 2));
+        assert_eq!(buffer.cursor_relative(), CursorPosition { row: 1, col: 2 });
+        buffer.set_cursor_position(100, 100);
+        assert_eq!(cursor(&buffer), (9, 19));
+        // Leaving origin mode homes the cursor to the screen origin
+        buffer.set_origin_mode(true);
+        buffer.set_origin_mode(false);
+        assert_eq!(cursor(&buffer), (0, 0));
+    }
+
+    #[test]
+    fn invalid_margins_are_ignored() {
+        let mut buffer = buffer(20, 10);
+        buffer.set_margins(4, 9);
+        // Left not before right, and a right margin past the edge clamps to it
+        buffer.set_margins(9, 4);
+        buffer.set_margins(6, 6);
+        buffer.set_origin_mode(true);
+        assert_eq!(cursor(&buffer), (0, 4));
+        buffer.set_margins(2, 500);
+        buffer.set_cursor_col(100);
+        assert_eq!(cursor(&buffer), (0, 19));
+    }
+
+    fn screen_line(buffer: &TerminalBuffer, row: usize) -> String {
+        buffer.get_line(buffer.scrollback_len() + row).map(|line| line.to_string()).unwrap_or_default()
+    }
+
+    fn write(buffer: &mut TerminalBuffer, text: &str) {
+        for ch in text.chars() {
+            buffer.put_char(ch);
+        }
+    }
+
+    #[test]
+    fn linked_text_reports_its_url() {
+        let mut buffer = buffer(20, 5);
+        write(&mut buffer, "a");
+        buffer.set_link(Some("https://example.com"));
+        write(&mut buffer, "bc");
+        buffer.set_link(None);
+        write(&mut buffer, "d");
+        assert_eq!(buffer.link_at(0, 0), None);
+        assert_eq!(buffer.link_at(0, 1), Some("https://example.com"));
+        assert_eq!(buffer.link_at(0, 2), Some("https://example.com"));
+        assert_eq!(buffer.link_at(0, 3), None);
+        // The same URL later reuses its id
+        buffer.set_link(Some("https://example.com"));
+        assert_eq!(buffer.links.len(), 1);
+    }
+
+    #[test]
+    fn sgr_reset_keeps_an_open_link() {
+        let mut buffer = buffer(20, 5);
+        buffer.set_link(Some("https://example.com"));
+        let mut style = buffer.current_style();
+        crate::terminal::ansi::parse_sgr(&[0], &mut style, Color32::WHITE);
+        buffer.set_style(style);
+        write(&mut buffer, "a");
+        assert_eq!(buffer.link_at(0, 0), Some("https://example.com"));
+    }
+
+    #[test]
+    fn trimming_drops_links_on_dropped_lines() {
+        let mut buffer = buffer(20, 2);
+        buffer.set_link(Some("https://old.example"));
+        write(&mut buffer, "a");
+        buffer.set_link(Some("https://open.example"));
+        for _ in 0..1100 {
+            buffer.new_line();
+        }
+        assert!(buffer.lines_dropped() > 0);
+        let urls: Vec<&str> = buffer.links.values().map(|link| link.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://open.example"]);
+        assert_eq!(buffer.link_ids.len(), 1);
+    }
+
+    #[test]
+    fn link_eviction_spares_the_open_link() {
+        let mut buffer = buffer(20, 5);
+        buffer.set_link(Some("https://open.example"));
+        let open = buffer.current_style().link;
+        for i in 0..MAX_LINKS {
+            buffer.link_id(&format!("https://example.com/{i}"));
+        }
+        assert_eq!(buffer.links.len(), MAX_LINKS);
+        assert!(buffer.links.contains_key(&open.unwrap()));
+        assert!(!buffer.link_ids.contains_key("https://example.com/0"));
+        write(&mut buffer, "a");
+        assert_eq!(buffer.link_at(0, 0), Some("https://open.example"));
+    }
+
+    #[test]
+    fn one_by_one_screen_wraps_and_scrolls() {
+        let mut buffer = buffer(1, 1);
+        write(&mut buffer, "abc");
+        assert_eq!(screen_line(&buffer, 0), "c");
+        assert_eq!(buffer.scrollback_len(), 2);
+        // The cursor waits past the edge until the next character wraps
+        assert_eq!(cursor(&buffer), (0, 1));
+    }
+
+    #[test]
+    fn one_by_one_screen_edits_without_panicking() {
+        let mut buffer = buffer(1, 1);
+        write(&mut buffer, "a");
+        buffer.carriage_return();
+        buffer.insert_chars(5);
+        assert_eq!(screen_line(&buffer, 0), "");
+        write(&mut buffer, "b");
+        buffer.carriage_return();
+        buffer.delete_chars(5);
+        assert_eq!(screen_line(&buffer, 0), "");
+        write(&mut buffer, "c");
+        buffer.insert_lines(3);
+        assert_eq!(screen_line(&buffer, 0), "");
+        write(&mut buffer, "d");
+        buffer.delete_lines(3);
+        assert_eq!(screen_line(&buffer, 0), "");
+        // Pending wrap: ICH/DCH past the edge change nothing
+        buffer.carriage_return();
+        write(&mut buffer, "e");
+        buffer.insert_chars(1);
+        buffer.delete_chars(1);
+        assert_eq!(screen_line(&buffer, 0), "e");
+    }
+
+    #[test]
+    fn one_by_one_screen_takes_wide_characters() {
+        let mut buffer = buffer(1, 1);
+        write(&mut buffer, "🚀😀");
+        // A wide character can never fit, so it is written cut off rather than wrapping forever
+        assert_eq!(screen_line(&buffer, 0), "😀");
+        assert_eq!(cursor(&buffer), (0, 1));
+    }
+
+    #[test]
+    fn one_column_screen_wraps_every_character() {
+        let mut buffer = buffer(1, 4);
+        write(&mut buffer, "abcd");
+        assert_eq!(
+            (0..4).map(|row| screen_line(&buffer, row)).collect::<Vec<_>>(),
+            ["a", "b", "c", "d"]
+        );
+        assert_eq!(buffer.scrollback_len(), 0);
+        write(&mut buffer, "🚀");
+        assert_eq!(screen_line(&buffer, 3), "🚀");
+        assert_eq!(buffer.scrollback_len(), 1);
+    }
+
+    #[test]
+    fn one_column_screen_inserts_and_deletes_lines() {
+        let mut buffer = buffer(1, 4);
+        write(&mut buffer, "abcd");
+        buffer.set_cursor_position(1, 0);
+        buffer.insert_lines(2);
+        assert_eq!(
+            (0..4).map(|row| screen_line(&buffer, row)).collect::<Vec<_>>(),
+            ["a", "", "", "b"]
+        );
+        buffer.delete_lines(10);
+        assert_eq!(
+            (0..4).map(|row| screen_line(&buffer, row)).collect::<Vec<_>>(),
+            ["a", "", "", ""]
+        );
+        buffer.set_cursor_position(0, 0);
+        buffer.insert_chars(3);
+        buffer.delete_chars(3);
+        assert_eq!(screen_line(&buffer, 0), "");
+    }
+
+    #[test]
+    fn one_row_screen_scrolls_on_every_line_break() {
+        let mut buffer = buffer(4, 1);
+        write(&mut buffer, "abcdef");
+        assert_eq!(screen_line(&buffer, 0), "ef");
+        buffer.new_line();
+        assert_eq!(screen_line(&buffer, 0), "");
+        assert_eq!(cursor(&buffer), (0, 2));
+    }
+
+    #[test]
+    fn one_row_screen_edits_characters() {
+        let mut buffer = buffer(4, 1);
+        write(&mut buffer, "abcd");
+        buffer.set_cursor_col(1);
+        buffer.insert_chars(2);
+        assert_eq!(screen_line(&buffer, 0), "a  b");
+        buffer.delete_chars(1);
+        assert_eq!(screen_line(&buffer, 0), "a b");
+        buffer.insert_chars(100);
+        assert_eq!(screen_line(&buffer, 0), "a");
+        buffer.insert_lines(5);
+        assert_eq!(screen_line(&buffer, 0), "");
+        write(&mut buffer, "xy");
+        buffer.delete_lines(5);
+        assert_eq!(screen_line(&buffer, 0), "");
+    }
+
+    #[test]
+    fn one_row_screen_wraps_wide_characters_at_the_edge() {
+        let mut buffer = buffer(4, 1);
+        write(&mut buffer, "abc🚀");
+        // The wide character doesn't fit in the last column, so it starts the next line
+        assert_eq!(screen_line(&buffer, 0), format!("🚀{}", WIDE_CHAR_SPACER));
+        assert_eq!(cursor(&buffer), (0, 2));
     }
 }
@@ -1,6 +1,8 @@
 use super::buffer::TerminalBuffer;
-use super::vt100::Vt100Mode;
+use super::vt100::{MouseTracking, Vt100Mode};
 use crate::config::{BellNotification, SessionConfig, TerminalMode};
+use crate::debug;
+use regex::Regex;
 
 pub struct TerminalEmulator {
     buffer: TerminalBuffer,
@@ -9,22 +11,34 @@ pub struct TerminalEmulator {
     bell_notification: BellNotification,
     bell_pending: bool,
     title: Option<String>,
+    clipboard_write: Option<String>,
+    resize_request: Option<(usize, usize)>,
+    responses: Vec<Vec<u8>>,
 }
 
 impl TerminalEmulator {
     pub fn new(config: &SessionConfig) -> Self {
-        let buffer = TerminalBuffer::new(
+        let mut buffer = TerminalBuffer::new(
             config.scrollback_lines,
             config.foreground(),
             config.background(),
         );
+        buffer.set_tab_width(config.tab_width);
+        buffer.set_prompt_pattern(prompt_pattern(config));
+        let mut vt100 = Vt100Mode::new();
+        vt100.set_encoding(config.encoding);
+        vt100.set_lone_cr_newline(config.lone_cr_as_newline);
+        vt100.set_sixel_enabled(config.sixel_graphics);
         Self {
             buffer,
-            vt100: Vt100Mode::new(),
+            vt100,
             mode: config.terminal_mode.clone(),
             bell_notification: config.bell_notification.clone(),
             bell_pending: false,
             title: None,
+            clipboard_write: None,
+            resize_request: None,
+            responses: Vec::new(),
         }
     }
 
@@ -38,10 +52,40 @@ impl TerminalEmulator {
                 if let Some(title) = self.vt100.take_title() {
                     self.title = Some(title);
                 }
+                if let Some(text) = self.vt100.take_clipboard_write() {
+                    self.clipboard_write = Some(text);
+                }
+                if let Some(size) = self.vt100.take_resize_request() {
+                    self.resize_request = Some(size);
+                }
+                self.responses.extend(self.vt100.take_responses());
             }
         }
     }
 
+    /// Pixel size of one cell, used to size inline images
+    pub fn set_cell_pixel_size(&mut self, width: f32, height: f32) {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.set_cell_pixel_size(width, height),
+        }
+    }
+
+    /// Return the terminal to its initial state: the screen is cleared and all modes are
+    /// reset to defaults. Scrollback is dropped as well unless `keep_scrollback` is set.
+    pub fn reset(&mut self, keep_scrollback: bool) {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.reset(&mut self.buffer),
+        }
+        if !keep_scrollback {
+            self.buffer.clear_scrollback();
+        }
+        self.bell_pending = false;
+        self.title = None;
+        self.clipboard_write = None;
+        self.resize_request = None;
+        self.responses.clear();
+    }
+
     pub fn buffer(&self) -> &TerminalBuffer {
         &self.buffer
     }
@@ -65,10 +109,35 @@ impl TerminalEmulator {
         }
     }
 
+    /// Remote working directory, when the shell reports it (OSC 7)
+    pub fn current_directory(&self) -> Option<&str> {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.current_directory(),
+        }
+    }
+
     pub fn take_title(&mut self) -> Option<String> {
         self.title.take()
     }
 
+    /// Text the remote side set as the clipboard contents (OSC 52)
+    pub fn take_clipboard_write(&mut self) -> Option<String> {
+        self.clipboard_write.take()
+    }
+
+    /// Replies the terminal owes the server (device status and attributes)
+    pub fn take_responses(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.responses)
+    }
+
+    pub fn take_resize_request(&mut self) -> Option<(usize, usize)> {
+        self.resize_request.take()
+    }
+
+    pub fn take_scrollback_cleared(&mut self) -> bool {
+        self.buffer.take_scrollback_cleared()
+    }
+
     pub fn cursor_keys_application(&self) -> bool {
         match self.mode {
             TerminalMode::VT100 => self.vt100.cursor_keys_application(),
@@ -81,16 +150,71 @@ impl TerminalEmulator {
         }
     }
 
+    pub fn mouse_tracking(&self) -> MouseTracking {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.mouse_tracking(),
+        }
+    }
+
+    pub fn mouse_sgr(&self) -> bool {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.mouse_sgr(),
+        }
+    }
+
+    pub fn alternate_screen(&self) -> bool {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.alternate_screen(),
+        }
+    }
+
     pub fn reverse_video(&self) -> bool {
         match self.mode {
             TerminalMode::VT100 => self.vt100.reverse_video(),
         }
     }
 
+    pub fn auto_wrap(&self) -> bool {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.auto_wrap(),
+        }
+    }
+
+    pub fn insert_mode(&self) -> bool {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.insert_mode(),
+        }
+    }
+
+    pub fn linefeed_newline(&self) -> bool {
+        match self.mode {
+            TerminalMode::VT100 => self.vt100.linefeed_newline(),
+        }
+    }
+
 
     pub fn update_config(&mut self, config: &SessionConfig) {
         self.buffer.set_default_colors(config.foreground(), config.background());
+        self.buffer.set_tab_width(config.tab_width);
+        self.buffer.set_max_scrollback(config.scrollback_lines);
+        self.buffer.set_prompt_pattern(prompt_pattern(config));
+        self.vt100.set_encoding(config.encoding);
+        self.vt100.set_lone_cr_newline(config.lone_cr_as_newline);
+        self.vt100.set_sixel_enabled(config.sixel_graphics);
         self.bell_notification = config.bell_notification.clone();
     }
 }
 
+/// Compile the session's prompt pattern; an empty or invalid pattern turns detection off
+fn prompt_pattern(config: &SessionConfig) -> Option<Regex> {
+    if config.prompt_pattern.is_empty() {
+        return None;
+    }
+    match Regex::new(&config.prompt_pattern) {
+        Ok(pattern) => Some(pattern),
+        Err(e) => {
+            debug::log(&format!("Invalid prompt pattern {:?}: {}", config.prompt_pattern, e));
+            None
+        }
+    }
+}
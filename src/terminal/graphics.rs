@@ -0,0 +1,458 @@
+use egui::{Color32, ColorImage};
+use image::ImageReader;
+use std::io::Cursor;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+
+// Limits for images sent by the server
+const MAX_INLINE_IMAGE_BYTES: usize = 16 * 1024 * 1024;
+const MAX_IMAGE_DIMENSION: u32 = 4096;
+const SIXEL_PALETTE_SIZE: usize = 256;
+const SIXEL_MAX_PARAMS: usize = 16;
+
+// VT340 default color registers (the first 16; the rest start black)
+const SIXEL_DEFAULT_COLORS: [Color32; 16] = [
+    Color32::from_rgb(0, 0, 0),
+    Color32::from_rgb(51, 51, 204),
+    Color32::from_rgb(204, 36, 36),
+    Color32::from_rgb(51, 204, 51),
+    Color32::from_rgb(204, 51, 204),
+    Color32::from_rgb(51, 204, 204),
+    Color32::from_rgb(204, 204, 51),
+    Color32::from_rgb(135, 135, 135),
+    Color32::from_rgb(66, 66, 66),
+    Color32::from_rgb(84, 84, 153),
+    Color32::from_rgb(153, 66, 66),
+    Color32::from_rgb(84, 153, 84),
+    Color32::from_rgb(153, 84, 153),
+    Color32::from_rgb(84, 153, 153),
+    Color32::from_rgb(153, 153, 84),
+    Color32::from_rgb(204, 204, 204),
+];
+
+/// Pixels of an image, set once decoding finishes; iTerm2 images decode on a worker thread
+pub type ImagePixels = Arc<OnceLock<Arc<ColorImage>>>;
+
+/// Image with its pixel size and the block of cells it covers
+pub struct PlacedImage {
+    pub pixels: ImagePixels,
+    pub size: [usize; 2],
+    pub cols: usize,
+    pub rows: usize,
+}
+
+/// Cell metrics needed to turn pixel sizes into cell counts
+#[derive(Debug, Clone, Copy)]
+pub struct CellGeometry {
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+/// Parse the body of an iTerm2 `OSC 1337 ; File=[args]:base64` sequence.
+/// Only inline PNG/JPEG images are displayed; file downloads are ignored. The size comes from
+/// the image header right away, while the pixels are decoded in the background.
+pub fn decode_iterm2_file(body: &str, geometry: CellGeometry) -> Option<PlacedImage> {
+    let (args, data) = body.strip_prefix("File=")?.split_once(':')?;
+    let mut inline = false;
+    let mut width = None;
+    let mut height = None;
+    let mut preserve_aspect = true;
+    for arg in args.split(';') {
+        let Some((key, value)) = arg.split_once('=') else {
+            continue;
+        };
+        match key {
+            "inline" => inline = value == "1",
+            "width" => width = Some(value),
+            "height" => height = Some(value),
+            "preserveAspectRatio" => preserve_aspect = value != "0",
+            _ => {}
+        }
+    }
+    if !inline || data.len() / 4 * 3 > MAX_INLINE_IMAGE_BYTES {
+        return None;
+    }
+    let bytes = decode_base64(data)?;
+    let size = image_dimensions(&bytes)?;
+    let [image_w, image_h] = size;
+    let terminal_w = geometry.cols as f32 * geometry.cell_width;
+    let terminal_h = geometry.rows as f32 * geometry.cell_height;
+    let requested_w = width.and_then(|w| parse_dimension(w, geometry.cell_width, terminal_w));
+    let requested_h = height.and_then(|h| parse_dimension(h, geometry.cell_height, terminal_h));
+    let aspect = image_w as f32 / image_h.max(1) as f32;
+    let (mut target_w, mut target_h) = match (requested_w, requested_h) {
+        (Some(w), Some(h)) if preserve_aspect => {
+            // Fit inside the requested box
+            let scale = (w / image_w as f32).min(h / image_h as f32);
+            (image_w as f32 * scale, image_h as f32 * scale)
+        }
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, w / aspect),
+        (None, Some(h)) => (h * aspect, h),
+        (None, None) => (image_w as f32, image_h as f32),
+    };
+    // Never wider or taller than the terminal
+    if target_w > terminal_w {
+        target_h *= terminal_w / target_w;
+        target_w = terminal_w;
+    }
+    if target_h > terminal_h {
+        target_w *= terminal_h / target_h;
+        target_h = terminal_h;
+    }
+    let pixels = ImagePixels::default();
+    let slot = pixels.clone();
+    thread::spawn(move || {
+        if let Some(image) = decode_image(&bytes) {
+            let _ = slot.set(Arc::new(image));
+        }
+    });
+    Some(PlacedImage {
+        pixels,
+        size,
+        cols: ((target_w / geometry.cell_width).ceil() as usize).max(1),
+        rows: ((target_h / geometry.cell_height).ceil() as usize).max(1),
+    })
+}
+
+/// Incremental decoder for the data of a `DCS ... q` Sixel sequence.
+/// Pixels that are never drawn stay transparent, so the terminal background shows through.
+pub struct SixelDecoder {
+    palette: Vec<Color32>,
+    color: Color32,
+    // Pixel rows, each as long as its rightmost drawn pixel
+    pixels: Vec<Vec<Color32>>,
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    repeat: usize,
+    // Command introducer ('#', '!' or '"') whose numeric parameters are being read
+    command: Option<u8>,
+    params: Vec<u32>,
+}
+
+impl Default for SixelDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SixelDecoder {
+    pub fn new() -> Self {
+        let mut palette = SIXEL_DEFAULT_COLORS.to_vec();
+        palette.resize(SIXEL_PALETTE_SIZE, Color32::BLACK);
+        Self {
+            color: palette[0],
+            palette,
+            pixels: Vec::new(),
+            width: 0,
+            height: 0,
+            x: 0,
+            y: 0,
+            repeat: 1,
+            command: None,
+            params: Vec::new(),
+        }
+    }
+
+    pub fn put(&mut self, byte: u8) {
+        if self.command.is_some() {
+            match byte {
+                b'0'..=b'9' => {
+                    if self.params.is_empty() {
+                        self.params.push(0);
+                    }
+                    if let Some(last) = self.params.last_mut() {
+                        *last = last.saturating_mul(10).saturating_add(u32::from(byte - b'0'));
+                    }
+                    return;
+                }
+                b';' => {
+                    if self.params.is_empty() {
+                        self.params.push(0);
+                    }
+                    // No sixel command takes more than five parameters
+                    if self.params.len() < SIXEL_MAX_PARAMS {
+                        self.params.push(0);
+                    }
+                    return;
+                }
+                _ => self.finish_command(),
+            }
+        }
+        match byte {
+            b'#' | b'!' | b'"' => {
+                self.command = Some(byte);
+                self.params.clear();
+            }
+            b'$' => self.x = 0,
+            b'-' => {
+                self.x = 0;
+                self.y += 6;
+            }
+            0x3F..=0x7E => {
+                let bits = byte - 0x3F;
+                for _ in 0..std::mem::replace(&mut self.repeat, 1) {
+                    for bit in 0..6 {
+                        if bits & (1 << bit) != 0 {
+                            self.set_pixel(self.x, self.y + bit);
+                        }
+                    }
+                    self.x += 1;
+                    if self.x <= MAX_IMAGE_DIMENSION as usize {
+                        self.width = self.width.max(self.x);
+                    }
+                }
+                if bits != 0 {
+                    self.height = self.height.max((self.y + 6).min(MAX_IMAGE_DIMENSION as usize));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish_command(&mut self) {
+        let param = |i: usize| self.params.get(i).copied().unwrap_or(0);
+        match self.command.take() {
+            Some(b'!') => self.repeat = (param(0) as usize).clamp(1, MAX_IMAGE_DIMENSION as usize),
+            Some(b'#') => {
+                let register = param(0) as usize % SIXEL_PALETTE_SIZE;
+                if self.params.len() >= 5 {
+                    let (a, b, c) = (param(2), param(3), param(4));
+                    self.palette[register] = match param(1) {
+                        1 => hls_to_color(a, b, c),
+                        _ => Color32::from_rgb(percent_to_u8(a), percent_to_u8(b), percent_to_u8(c)),
+                    };
+                }
+                self.color = self.palette[register];
+            }
+            Some(b'"') => {
+                // Raster attributes: Pan;Pad;Ph;Pv give the image size up front
+                let max = MAX_IMAGE_DIMENSION as usize;
+                self.width = self.width.max((param(2) as usize).min(max));
+                self.height = self.height.max((param(3) as usize).min(max));
+            }
+            _ => {}
+        }
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize) {
+        let max = MAX_IMAGE_DIMENSION as usize;
+        if x >= max || y >= max {
+            return;
+        }
+        if self.pixels.len() <= y {
+            self.pixels.resize_with(y + 1, Vec::new);
+        }
+        let row = &mut self.pixels[y];
+        if row.len() <= x {
+            row.resize(x + 1, Color32::TRANSPARENT);
+        }
+        row[x] = self.color;
+    }
+
+    /// Build the image and its cell footprint; None when nothing was drawn
+    pub fn finish(mut self, geometry: CellGeometry) -> Option<PlacedImage> {
+        self.finish_command();
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            let row = self.pixels.get(y).map_or(&[][..], Vec::as_slice);
+            let drawn = row.len().min(self.width);
+            pixels.extend_from_slice(&row[..drawn]);
+            pixels.resize(pixels.len() + self.width - drawn, Color32::TRANSPARENT);
+        }
+        let size = [self.width, self.height];
+        Some(PlacedImage {
+            pixels: Arc::new(OnceLock::from(Arc::new(ColorImage::new(size, pixels)))),
+            size,
+            cols: ((self.width as f32 / geometry.cell_width).ceil() as usize).max(1),
+            rows: ((self.height as f32 / geometry.cell_height).ceil() as usize).max(1),
+        })
+    }
+}
+
+fn percent_to_u8(value: u32) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// Sixel HLS: hue in degrees with blue at 0, lightness and saturation in percent
+fn hls_to_color(hue: u32, lightness: u32, saturation: u32) -> Color32 {
+    let h = ((hue + 240) % 360) as f32 / 360.0;
+    let l = lightness.min(100) as f32 / 100.0;
+    let s = saturation.min(100) as f32 / 100.0;
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return Color32::from_rgb(v, v, v);
+    }
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    Color32::from_rgb(channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+}
+
+/// Size in pixels from `N` (cells), `Npx`, `N%` (of the terminal) or `auto`
+fn parse_dimension(value: &str, cell_size: f32, terminal_size: f32) -> Option<f32> {
+    if value == "auto" {
+        None
+    } else if let Some(px) = value.strip_suffix("px") {
+        px.parse::<f32>().ok()
+    } else if let Some(percent) = value.strip_suffix('%') {
+        percent.parse::<f32>().ok().map(|p| terminal_size * p / 100.0)
+    } else {
+        value.parse::<f32>().ok().map(|cells| cells * cell_size)
+    }
+}
+
+fn image_reader(bytes: &[u8]) -> Option<ImageReader<Cursor<&[u8]>>> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format().ok()?;
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    reader.limits(limits);
+    Some(reader)
+}
+
+/// Width and height from the image header, without decoding the pixels
+fn image_dimensions(bytes: &[u8]) -> Option<[usize; 2]> {
+    let (width, height) = image_reader(bytes)?.into_dimensions().ok()?;
+    (width <= MAX_IMAGE_DIMENSION && height <= MAX_IMAGE_DIMENSION).then_some([width as usize, height as usize])
+}
+
+fn decode_image(bytes: &[u8]) -> Option<ColorImage> {
+    let rgba = image_reader(bytes)?.decode().ok()?.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Some(ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+}
+
+/// Standard base64 (with or without padding); whitespace is skipped
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut accumulator: u32 = 0;
+    let mut bits = 0;
+    for byte in text.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            b' ' | b'\t' | b'\r' | b'\n' => continue,
+            _ => return None,
+        };
+        // Only the low bits still waiting to be emitted matter
+        accumulator = ((accumulator << 6) | u32::from(value)) & 0xFFFF;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((accumulator >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1x1 opaque PNG
+    const PIXEL_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+
+    fn geometry() -> CellGeometry {
+        CellGeometry { cell_width: 10.0, cell_height: 20.0, cols: 80, rows: 24 }
+    }
+
+    #[test]
+    fn base64_decodes_with_and_without_padding() {
+        assert_eq!(decode_base64("aGVsbG8=").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(decode_base64("aGVsbG8").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(decode_base64("aGVs\r\nbG8h").as_deref(), Some(&b"hello!"[..]));
+        assert_eq!(decode_base64("").as_deref(), Some(&b""[..]));
+    }
+
+    #[test]
+    fn base64_rejects_characters_outside_the_alphabet() {
+        assert_eq!(decode_base64("aGV*bG8="), None);
+    }
+
+    #[test]
+    fn iterm2_inline_image_is_sized_and_decoded() {
+        let placed = decode_iterm2_file(&format!("File=name=eA==;inline=1:{}", PIXEL_PNG), geometry()).unwrap();
+        assert_eq!(placed.size, [1, 1]);
+        assert_eq!((placed.cols, placed.rows), (1, 1));
+        assert_eq!(placed.pixels.wait().size, [1, 1]);
+    }
+
+    #[test]
+    fn iterm2_width_in_cells_keeps_the_aspect_ratio() {
+        let placed = decode_iterm2_file(&format!("File=inline=1;width=3:{}", PIXEL_PNG), geometry()).unwrap();
+        // 30 pixels wide and, being square, 30 high: one and a half 20-pixel rows
+        assert_eq!((placed.cols, placed.rows), (3, 2));
+    }
+
+    #[test]
+    fn iterm2_ignores_downloads_and_non_images() {
+        assert!(decode_iterm2_file(&format!("File=inline=0:{}", PIXEL_PNG), geometry()).is_none());
+        assert!(decode_iterm2_file("File=inline=1:aGVsbG8=", geometry()).is_none());
+        assert!(decode_iterm2_file("inline=1:aGVsbG8=", geometry()).is_none());
+    }
+
+    #[test]
+    fn sixel_draws_columns_in_the_selected_color() {
+        let mut decoder = SixelDecoder::new();
+        // Color 1, two full sixel columns, then one pixel at the top of the next band
+        for &byte in b"#1~~-@" {
+            decoder.put(byte);
+        }
+        let placed = decoder.finish(geometry()).unwrap();
+        // Bands are six pixels high, so the second one still counts in full
+        assert_eq!(placed.size, [2, 12]);
+        assert_eq!((placed.cols, placed.rows), (1, 1));
+        let image = placed.pixels.get().unwrap();
+        assert_eq!(image.pixels[0], SIXEL_DEFAULT_COLORS[1]);
+        assert_eq!(image.pixels[2 * 5 + 1], SIXEL_DEFAULT_COLORS[1]);
+        assert_eq!(image.pixels[2 * 6], SIXEL_DEFAULT_COLORS[1]);
+        assert_eq!(image.pixels[2 * 6 + 1], Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn sixel_color_definitions_and_repeats() {
+        let mut decoder = SixelDecoder::new();
+        // Register 5 as pure RGB red, then three columns with only the top pixel
+        for &byte in b"#5;2;100;0;0!3@" {
+            decoder.put(byte);
+        }
+        let placed = decoder.finish(geometry()).unwrap();
+        assert_eq!(placed.size, [3, 6]);
+        let image = placed.pixels.get().unwrap();
+        assert!(image.pixels[..3].iter().all(|&pixel| pixel == Color32::from_rgb(255, 0, 0)));
+        assert_eq!(image.pixels[3], Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn sixel_without_pixels_is_dropped() {
+        let mut decoder = SixelDecoder::new();
+        for &byte in b"#1?$-" {
+            decoder.put(byte);
+        }
+        assert!(decoder.finish(geometry()).is_none());
+    }
+}
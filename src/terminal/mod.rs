@@ -1,6 +1,7 @@
 pub mod ansi;
 pub mod buffer;
 pub mod emulator;
+pub mod graphics;
 pub mod renderer;
 pub mod vt100;
 
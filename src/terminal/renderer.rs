@@ -1,8 +1,13 @@
-use super::buffer::TerminalBuffer;
+use super::ansi::bright_variant;
+use super::buffer::{CellStyle, TerminalBuffer, WIDE_CHAR_SPACER};
 use super::emulator::TerminalEmulator;
 use crate::config::CursorType;
 use crate::selection::Selection;
-use egui::{Color32, FontFamily, FontId, Pos2, Rect, Response, Sense, Ui, Vec2};
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, FontFamily, FontId, MouseWheelUnit, Painter, Pos2, Rect, Response, Sense, TextureHandle, TextureOptions, Ui, Vec2};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 // Rendering constants
 const CURSOR_BLINK_INTERVAL_SECS: f64 = 0.5;
@@ -18,14 +23,112 @@ const CURSOR_VERTICAL_WIDTH_MULTIPLIER: f32 = 0.1;
 const CURSOR_VERTICAL_MIN_WIDTH: f32 = 1.0;
 const SCROLLBAR_WIDTH: f32 = 12.0;
 const SCROLLBAR_MIN_THUMB_HEIGHT: f32 = 20.0;
+// How far the scrollbar track and thumb are blended from the background toward the accent
+const SCROLLBAR_TRACK_TINT: f32 = 0.12;
+const SCROLLBAR_THUMB_TINT: f32 = 0.45;
+const SCROLLBAR_THUMB_HOVER_TINT: f32 = 0.75;
+// Selection is a translucent overlay, so glyphs keep their own colors
+const SELECTION_OVERLAY_ALPHA: u8 = 110;
+// Below this luminance difference from the background the highlight would vanish
+const SELECTION_MIN_CONTRAST: f32 = 0.15;
+const SEARCH_HIGHLIGHT_COLOR: Color32 = Color32::from_rgba_premultiplied(120, 100, 0, 120);
+const COPY_CURSOR_STROKE_WIDTH: f32 = 2.0;
+// Opacity of the background-colored veil over an unfocused terminal
+const UNFOCUSED_DIM_ALPHA: u8 = 110;
+const DEFAULT_SCROLL_LINES_PER_NOTCH: f32 = 3.0;
+// Touchpad travel (in points) that counts as one Ctrl+scroll zoom step
+const ZOOM_POINTS_PER_STEP: f32 = 50.0;
+// History rows that must remain above the activity pane for it to be shown
+const MIN_HISTORY_ROWS: usize = 3;
+// Horizontal offset of the second pass when a bold face has to be faked
+const SYNTHETIC_BOLD_OFFSET: f32 = 1.0;
+// How often to check on inline images still being decoded
+const IMAGE_DECODE_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+// Font families registered for styled faces of the terminal font
+pub const TERMINAL_BOLD_FAMILY: &str = "terminal_bold";
+pub const TERMINAL_ITALIC_FAMILY: &str = "terminal_italic";
+pub const TERMINAL_BOLD_ITALIC_FAMILY: &str = "terminal_bold_italic";
+
+/// A glyph to draw in performance mode, where a line's text is laid out in one go
+#[derive(Clone, Copy, Hash)]
+struct LineGlyph {
+    col: usize,
+    ch: char,
+    color: Color32,
+    bold: bool,
+    italic: bool,
+    // Takes two cells (followed by a spacer)
+    wide: bool,
+}
+
+/// Laid out text of a line; synthesized bold glyphs get a second, offset job
+struct LineLayout {
+    job: LayoutJob,
+    bold_job: Option<LayoutJob>,
+}
+
+/// Which styled faces of the terminal font were found on the system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StyledFaces {
+    pub bold: bool,
+    pub italic: bool,
+    pub bold_italic: bool,
+}
 
 pub struct TerminalRenderer {
     font_size: f32,
     cell_width: f32,
     cell_height: f32,
+    // Cell height as a multiple of the font size
+    line_height: f32,
     cursor_blink_time: f64,
     cursor_visible: bool,
     cursor_type: CursorType,
+    scroll_lines_per_notch: f32,
+    invert_scroll: bool,
+    // Fractional lines left over from pixel-precise (touchpad) scrolling
+    scroll_remainder: f32,
+    zoom_remainder: f32,
+    zoom_steps: i32,
+    // While the program takes mouse reports, the wheel is collected for it instead of scrolling
+    report_wheel: bool,
+    wheel_lines: i32,
+    // Locally drawn status line below the terminal (None = hidden)
+    status_line: Option<String>,
+    // Current find match as (line, start col, end col exclusive)
+    search_highlight: Option<(usize, usize, usize)>,
+    // Keyboard copy mode cursor as (line, col)
+    copy_cursor: Option<(usize, usize)>,
+    activity_pane_rows: Option<usize>,
+    // Split in effect during the last render as (first screen row, first buffer line) of the live pane
+    live_pane: Option<(usize, usize)>,
+    styled_faces: StyledFaces,
+    bold_font: bool,
+    italic_font: bool,
+    bold_is_bright: bool,
+    dim_unfocused: bool,
+    // Session accent the scrollbar is tinted with
+    scrollbar_accent: Color32,
+    selection_color: Color32,
+    // Underline of hyperlinked (OSC 8) text
+    link_color: Color32,
+    always_show_scrollbar: bool,
+    // Empty space kept between the widget edge and the cell grid
+    padding: f32,
+    // Grid size pinned by the session (cols, rows) instead of following the window
+    fixed_size: Option<(usize, usize)>,
+    // Cap on the grid width; a wider area centers the terminal
+    max_columns: Option<usize>,
+    // Uploaded inline images by buffer image id
+    image_textures: HashMap<u64, TextureHandle>,
+    // Performance mode: one galley per line instead of one text shape per cell
+    cache_line_layouts: bool,
+    // Line layouts by hash of the line's glyphs, with the frame they were last drawn in
+    line_layouts: RefCell<HashMap<u64, (u64, LineLayout)>>,
+    layout_frame: u64,
+    // Hash of the terminal and fallback font names the cached layouts were measured with
+    font_family_key: u64,
 }
 
 impl TerminalRenderer {
@@ -35,9 +138,39 @@ impl TerminalRenderer {
             font_size,
             cell_width: font_size * CELL_WIDTH_MULTIPLIER,
             cell_height: font_size * CELL_HEIGHT_MULTIPLIER,
+            line_height: CELL_HEIGHT_MULTIPLIER,
             cursor_blink_time: 0.0,
             cursor_visible: true,
             cursor_type,
+            scroll_lines_per_notch: DEFAULT_SCROLL_LINES_PER_NOTCH,
+            invert_scroll: false,
+            scroll_remainder: 0.0,
+            zoom_remainder: 0.0,
+            zoom_steps: 0,
+            report_wheel: false,
+            wheel_lines: 0,
+            status_line: None,
+            search_highlight: None,
+            copy_cursor: None,
+            activity_pane_rows: None,
+            live_pane: None,
+            styled_faces: StyledFaces::default(),
+            bold_font: false,
+            italic_font: false,
+            bold_is_bright: false,
+            dim_unfocused: false,
+            scrollbar_accent: Color32::GRAY,
+            selection_color: Color32::from_rgb(0, 92, 128),
+            link_color: Color32::from_rgb(90, 170, 255),
+            always_show_scrollbar: false,
+            padding: 0.0,
+            fixed_size: None,
+            max_columns: None,
+            image_textures: HashMap::new(),
+            cache_line_layouts: false,
+            line_layouts: RefCell::new(HashMap::new()),
+            layout_frame: 0,
+            font_family_key: 0,
         }
     }
 
@@ -46,14 +179,92 @@ impl TerminalRenderer {
         if self.font_size != font_size {
             self.font_size = font_size;
             self.cell_width = font_size * CELL_WIDTH_MULTIPLIER;
-            self.cell_height = font_size * CELL_HEIGHT_MULTIPLIER;
+            self.cell_height = font_size * self.line_height;
+        }
+    }
+
+    pub fn set_line_height(&mut self, multiplier: f32) {
+        if self.line_height != multiplier {
+            self.line_height = multiplier;
+            self.cell_height = self.font_size * multiplier;
         }
     }
 
+    /// Vertical shift that keeps glyphs centered in cells taller or shorter than the default
+    fn text_offset(&self) -> f32 {
+        (self.cell_height - self.font_size * CELL_HEIGHT_MULTIPLIER) / 2.0
+    }
+
     pub fn update_cursor_type(&mut self, cursor_type: CursorType) {
         self.cursor_type = cursor_type;
     }
 
+    pub fn set_scroll_options(&mut self, lines_per_notch: u32, inverted: bool) {
+        self.scroll_lines_per_notch = lines_per_notch.max(1) as f32;
+        self.invert_scroll = inverted;
+    }
+
+    /// Font size steps requested with Ctrl+wheel since the last call (positive = larger)
+    pub fn take_zoom_steps(&mut self) -> i32 {
+        std::mem::take(&mut self.zoom_steps)
+    }
+
+    /// Collect wheel movement for mouse reports instead of scrolling the view
+    pub fn set_report_wheel(&mut self, enabled: bool) {
+        self.report_wheel = enabled;
+        if !enabled {
+            self.wheel_lines = 0;
+        }
+    }
+
+    /// Wheel notches collected for mouse reporting since the last call (positive = up)
+    pub fn take_wheel_steps(&mut self) -> i32 {
+        let per_step = (self.scroll_lines_per_notch as i32).max(1);
+        let steps = self.wheel_lines / per_step;
+        self.wheel_lines -= steps * per_step;
+        steps
+    }
+
+    /// Convert this frame's wheel events into lines to scroll (positive = towards history).
+    /// Line-based wheels scroll by the configured notch size (a page with Shift held), while
+    /// pixel-precise devices keep the OS-provided smooth deltas. Ctrl+wheel is collected as zoom.
+    fn consume_wheel(&mut self, ui: &Ui, viewport_rows: usize) -> i32 {
+        let page = viewport_rows.max(1) as f32;
+        let mut lines = 0.0;
+        ui.input(|i| {
+            for event in &i.events {
+                let egui::Event::MouseWheel { unit, delta, modifiers } = event else {
+                    continue;
+                };
+                // Some platforms turn Shift+wheel into horizontal scrolling
+                let mut dy = if delta.y == 0.0 && modifiers.shift { delta.x } else { delta.y };
+                if self.invert_scroll {
+                    dy = -dy;
+                }
+                if modifiers.command || modifiers.ctrl {
+                    match unit {
+                        MouseWheelUnit::Point => self.zoom_remainder += dy / ZOOM_POINTS_PER_STEP,
+                        _ => self.zoom_remainder += dy.signum(),
+                    }
+                    let steps = self.zoom_remainder.trunc();
+                    self.zoom_remainder -= steps;
+                    self.zoom_steps += steps as i32;
+                    continue;
+                }
+                lines += match unit {
+                    MouseWheelUnit::Point => dy / self.cell_height,
+                    MouseWheelUnit::Line if modifiers.shift => dy * page,
+                    MouseWheelUnit::Line => dy * self.scroll_lines_per_notch,
+                    MouseWheelUnit::Page => dy * page,
+                };
+            }
+        });
+        let total = lines + self.scroll_remainder;
+        let whole = total.trunc();
+        self.scroll_remainder = total - whole;
+        whole as i32
+    }
+
     #[allow(dead_code)]
     pub fn calculate_size(&self, cols: usize, rows: usize) -> Vec2 {
         Vec2::new(
@@ -62,11 +273,258 @@ impl TerminalRenderer {
         )
     }
 
-    #[allow(dead_code)]
+    /// Size of one cell in points
+    pub fn cell_size(&self) -> Vec2 {
+        Vec2::new(self.cell_width, self.cell_height)
+    }
+
+    pub fn set_padding(&mut self, padding: u32) {
+        self.padding = padding as f32;
+    }
+
+    /// Keep the grid at `cols` x `rows` whatever the window size; the terminal is centered
+    pub fn set_fixed_size(&mut self, size: Option<(u16, u16)>) {
+        self.fixed_size = size.map(|(cols, rows)| (usize::from(cols).max(1), usize::from(rows).max(1)));
+    }
+
+    /// Limit the grid to `cols` columns; when the window is wider the terminal is centered
+    pub fn set_max_columns(&mut self, cols: Option<u16>) {
+        self.max_columns = cols.map(|cols| usize::from(cols).max(1));
+    }
+
+    /// Columns that fit in `width`, within the maximum
+    fn columns_for_width(&self, width: f32) -> usize {
+        let cols = (width / self.cell_width.max(1.0)).floor() as usize;
+        self.max_columns.map_or(cols, |max| cols.min(max))
+    }
+
+    /// Top-left corner of the cell grid inside a rect returned by `render`
+    pub fn grid_origin(&self, rect: Rect) -> Pos2 {
+        rect.min + Vec2::splat(self.padding)
+    }
+
     pub fn calculate_grid_size(&self, available_size: Vec2) -> (usize, usize) {
-        let cols = (available_size.x / self.cell_width.max(1.0)).floor() as usize;
+        if let Some(size) = self.fixed_size {
+            return size;
+        }
+        let available_size = available_size - Vec2::splat(2.0 * self.padding);
+        let cols = self.columns_for_width(available_size.x);
         let rows = (available_size.y / self.cell_height.max(1.0)).floor() as usize;
-        (cols.max(1), rows.max(1))
+        (cols.max(1), rows.saturating_sub(self.reserved_rows()).max(1))
+    }
+
+    pub fn set_status_line(&mut self, text: Option<String>) {
+        self.status_line = text;
+    }
+
+    pub fn set_search_highlight(&mut self, highlight: Option<(usize, usize, usize)>) {
+        self.search_highlight = highlight;
+    }
+
+    pub fn set_copy_cursor(&mut self, cursor: Option<(usize, usize)>) {
+        self.copy_cursor = cursor;
+    }
+
+    /// Keep this many live bottom rows visible below the history while scrolled back
+    pub fn set_activity_pane(&mut self, rows: Option<usize>) {
+        self.activity_pane_rows = rows;
+    }
+
+    /// Choose faces for bold/italic cells; bold without a bold face is drawn twice
+    pub fn set_font_styles(&mut self, faces: StyledFaces, bold_font: bool, italic_font: bool) {
+        self.styled_faces = faces;
+        self.bold_font = bold_font;
+        self.italic_font = italic_font;
+    }
+
+    /// Terminal font and fallback font; cached line layouts measured with others are dropped
+    pub fn set_font_family(&mut self, font: &str, fallback: &str) {
+        let mut hasher = DefaultHasher::new();
+        (font, fallback).hash(&mut hasher);
+        let key = hasher.finish();
+        if self.font_family_key != key {
+            self.font_family_key = key;
+            self.line_layouts.get_mut().clear();
+        }
+    }
+
+    pub fn set_bold_is_bright(&mut self, enabled: bool) {
+        self.bold_is_bright = enabled;
+    }
+
+    pub fn set_dim_unfocused(&mut self, enabled: bool) {
+        self.dim_unfocused = enabled;
+    }
+
+    pub fn set_cache_line_layouts(&mut self, enabled: bool) {
+        self.cache_line_layouts = enabled;
+        if !enabled {
+            self.line_layouts.get_mut().clear();
+        }
+    }
+
+    pub fn set_selection_color(&mut self, color: Color32) {
+        self.selection_color = color;
+    }
+
+    pub fn set_link_color(&mut self, color: Color32) {
+        self.link_color = color;
+    }
+
+    /// Selection overlay for cells on `background`; a color too close to it is swapped for white or black
+    fn selection_overlay(&self, background: Color32) -> Color32 {
+        let luminance = |c: Color32| (0.299 * c.r() as f32 + 0.587 * c.g() as f32 + 0.114 * c.b() as f32) / 255.0;
+        let background_luminance = luminance(background);
+        let color = if (luminance(self.selection_color) - background_luminance).abs() >= SELECTION_MIN_CONTRAST {
+            self.selection_color
+        } else if background_luminance < 0.5 {
+            Color32::WHITE
+        } else {
+            Color32::BLACK
+        };
+        Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), SELECTION_OVERLAY_ALPHA)
+    }
+
+    pub fn set_scrollbar_style(&mut self, accent: Color32, always_visible: bool) {
+        self.scrollbar_accent = accent;
+        self.always_show_scrollbar = always_visible;
+    }
+
+    /// Cell colors as (fg, bg), with bold base colors brightened when "bold is bright" is on
+    fn cell_colors(&self, style: &CellStyle, default_bg: Color32) -> (Color32, Color32) {
+        let bright = (self.bold_is_bright && style.bold)
+            .then(|| bright_variant(style.fg))
+            .flatten();
+        match bright {
+            Some(fg) => CellStyle { fg, ..*style }.effective_colors(default_bg),
+            None => style.effective_colors(default_bg),
+        }
+    }
+
+    /// Font for a cell's style, and whether bold has to be synthesized
+    fn font_for_style(&self, style: &CellStyle) -> (FontId, bool) {
+        self.font_for(style.bold, style.italic)
+    }
+
+    fn font_for(&self, bold: bool, italic: bool) -> (FontId, bool) {
+        let bold = bold && self.bold_font;
+        let italic = italic && self.italic_font;
+        let faces = self.styled_faces;
+        let (family, synthetic_bold) = match (bold, italic) {
+            (true, true) if faces.bold_italic => (Some(TERMINAL_BOLD_ITALIC_FAMILY), false),
+            (true, true) if faces.italic => (Some(TERMINAL_ITALIC_FAMILY), true),
+            (true, _) if faces.bold => (Some(TERMINAL_BOLD_FAMILY), false),
+            (true, _) => (None, true),
+            (false, true) if faces.italic => (Some(TERMINAL_ITALIC_FAMILY), false),
+            _ => (None, false),
+        };
+        let family = family.map_or(FontFamily::Monospace, |name| FontFamily::Name(name.into()));
+        (FontId::new(self.font_size, family), synthetic_bold)
+    }
+
+    fn paint_glyph(&self, painter: &egui::Painter, pos: Pos2, ch: char, style: &CellStyle, color: Color32) {
+        let pos = pos + Vec2::new(0.0, self.text_offset());
+        let (font_id, synthetic_bold) = self.font_for_style(style);
+        if synthetic_bold {
+            painter.text(pos + Vec2::new(SYNTHETIC_BOLD_OFFSET, 0.0), egui::Align2::LEFT_TOP, ch, font_id.clone(), color);
+        }
+        painter.text(pos, egui::Align2::LEFT_TOP, ch, font_id, color);
+    }
+
+    /// Performance mode: draw a line's glyphs as one galley, reusing the layout while the line is unchanged.
+    /// egui keeps the galley of a job drawn every frame and drops it when the font atlas is rebuilt,
+    /// so only the job is cached here; that saves the per-glyph font lookups and text shapes.
+    fn paint_line_glyphs(&self, painter: &Painter, pos: Pos2, glyphs: &[LineGlyph]) {
+        if glyphs.is_empty() {
+            return;
+        }
+        // Glyphs are snapped to whole pixels like the per-cell path, which depends on where the line starts
+        let x_fraction = pos.x.fract();
+        let mut hasher = DefaultHasher::new();
+        glyphs.hash(&mut hasher);
+        // Font settings change the layout too
+        let faces = self.styled_faces;
+        (faces.bold, faces.italic, faces.bold_italic, self.bold_font, self.italic_font).hash(&mut hasher);
+        (self.font_size.to_bits(), self.font_family_key, x_fraction.to_bits()).hash(&mut hasher);
+        let mut layouts = self.line_layouts.borrow_mut();
+        let (last_used, layout) = layouts.entry(hasher.finish())
+            .or_insert_with(|| (0, self.layout_line(painter, glyphs, x_fraction)));
+        *last_used = self.layout_frame;
+        let pos = Pos2::new(pos.x.floor(), pos.y + self.text_offset());
+        if let Some(job) = &layout.bold_job {
+            painter.galley(pos + Vec2::new(SYNTHETIC_BOLD_OFFSET, 0.0), painter.layout_job(job.clone()), Color32::PLACEHOLDER);
+        }
+        painter.galley(pos, painter.layout_job(layout.job.clone()), Color32::PLACEHOLDER);
+    }
+
+    fn layout_line(&self, painter: &Painter, glyphs: &[LineGlyph], x_fraction: f32) -> LineLayout {
+        let synthetic_bold: Vec<LineGlyph> = glyphs.iter()
+            .filter(|glyph| self.font_for(glyph.bold, glyph.italic).1)
+            .copied()
+            .collect();
+        LineLayout {
+            job: self.line_job(painter, glyphs, x_fraction),
+            bold_job: (!synthetic_bold.is_empty()).then(|| self.line_job(painter, &synthetic_bold, x_fraction)),
+        }
+    }
+
+    /// Layout job putting each glyph at the left edge of its cell. Adjacent glyphs of one format
+    /// share a section whose letter spacing pads the font's advance out to the cell width;
+    /// anything else starts a new section, with leading space up to its cell. Cell edges are floored
+    /// as in the per-cell path, counting from the fractional pixel the line starts at.
+    fn line_job(&self, painter: &Painter, glyphs: &[LineGlyph], x_fraction: f32) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        // Where the glyph laid out last ends, and its cell edge and advance
+        let mut cursor = 0.0;
+        let mut previous: Option<(LineGlyph, f32, f32)> = None;
+        painter.fonts_mut(|fonts| {
+            for &glyph in glyphs {
+                let (font_id, _) = self.font_for(glyph.bold, glyph.italic);
+                let advance = fonts.glyph_width(&font_id, glyph.ch);
+                let x = (x_fraction + glyph.col as f32 * self.cell_width).floor();
+                // A run steps by exactly one cell width, so a floored edge off that step starts a new section
+                let continues_run = previous.is_some_and(|(prev, prev_x, prev_advance)| {
+                    prev.col + 1 == glyph.col
+                        && prev_x + self.cell_width == x
+                        && !prev.wide
+                        && prev.color == glyph.color
+                        && (prev.bold, prev.italic) == (glyph.bold, glyph.italic)
+                        && prev_advance == advance
+                });
+                match job.sections.last_mut().filter(|_| continues_run) {
+                    Some(section) => {
+                        job.text.push(glyph.ch);
+                        section.byte_range.end = job.text.len();
+                    }
+                    None => {
+                        let format = TextFormat {
+                            font_id,
+                            color: glyph.color,
+                            extra_letter_spacing: self.cell_width - advance,
+                            ..Default::default()
+                        };
+                        job.append(glyph.ch.encode_utf8(&mut [0; 4]), x - cursor, format);
+                    }
+                }
+                cursor = x + advance;
+                previous = Some((glyph, x, advance));
+            }
+        });
+        job
+    }
+
+    /// Screen row showing a buffer line, accounting for the activity pane split
+    fn screen_row_of(&self, line: usize, visible_start: usize, history_rows: usize) -> Option<usize> {
+        if (visible_start..visible_start + history_rows).contains(&line) {
+            return Some(line - visible_start);
+        }
+        let (first_row, first_line) = self.live_pane?;
+        (line >= first_line).then(|| first_row + line - first_line)
+    }
+
+    /// Rows taken from the terminal grid by locally drawn chrome
+    fn reserved_rows(&self) -> usize {
+        usize::from(self.status_line.is_some())
     }
 
     pub fn render(
@@ -80,9 +538,11 @@ impl TerminalRenderer {
         scroll_offset: usize,
     ) -> (Response, usize, bool, usize, usize) {
         let buffer = emulator.buffer();
-        let available = ui.available_size();
-        let viewport_cols = (available.x / self.cell_width).floor() as usize;
-        let viewport_rows = (available.y / self.cell_height).floor() as usize;
+        let available = ui.available_size() - Vec2::splat(2.0 * self.padding);
+        let (viewport_cols, viewport_rows) = self.fixed_size.unwrap_or_else(|| (
+            self.columns_for_width(available.x),
+            ((available.y / self.cell_height).floor() as usize).saturating_sub(self.reserved_rows()),
+        ));
         let terminal_width = viewport_cols as f32 * self.cell_width;
         let terminal_height = viewport_rows as f32 * self.cell_height;
         let total_lines = buffer.total_lines();
@@ -95,37 +555,65 @@ impl TerminalRenderer {
         if new_scroll_offset == usize::MAX {
             new_scroll_offset = max_scroll;
         }
-        let show_scrollbar = max_scroll > 0;
-        let content_width = terminal_width;
+        let show_scrollbar = max_scroll > 0 || self.always_show_scrollbar;
+        let content_width = terminal_width + 2.0 * self.padding;
         let total_width = content_width + if show_scrollbar { SCROLLBAR_WIDTH } else { 0.0 };
-        let desired_size = Vec2::new(total_width, terminal_height);
-        let (outer_rect, outer_response) = ui.allocate_exact_size(desired_size, Sense::click_and_drag());
-        let terminal_rect = Rect::from_min_size(outer_rect.min, Vec2::new(content_width, terminal_height));
-        if !ui.is_rect_visible(terminal_rect) {
+        let desired_size = Vec2::new(total_width, terminal_height + 2.0 * self.padding);
+        // Capped narrower than the area: the sides are letterboxed in the background color
+        let letterbox = (self.fixed_size.is_none() && self.max_columns.is_some() && total_width < ui.available_width())
+            .then(|| ui.available_rect_before_wrap());
+        let (outer_rect, outer_response) = if let Some(area) = letterbox {
+            let rect = Rect::from_min_size(
+                area.min + Vec2::new(((area.width() - total_width) / 2.0).floor(), 0.0),
+                desired_size,
+            );
+            (rect, ui.allocate_rect(rect, Sense::click_and_drag()))
+        } else if self.fixed_size.is_some() {
+            // Letterbox a fixed-size terminal in the middle of the available area
+            let reserved = Vec2::new(0.0, self.reserved_rows() as f32 * self.cell_height);
+            let slack = ((ui.available_size() - desired_size - reserved) / 2.0).max(Vec2::ZERO);
+            let rect = Rect::from_min_size(ui.available_rect_before_wrap().min + slack, desired_size);
+            (rect, ui.allocate_rect(rect, Sense::click_and_drag()))
+        } else {
+            ui.allocate_exact_size(desired_size, Sense::click_and_drag())
+        };
+        let padded_rect = Rect::from_min_size(outer_rect.min, Vec2::new(content_width, outer_rect.height()));
+        let terminal_rect = Rect::from_min_size(self.grid_origin(outer_rect), Vec2::new(terminal_width, terminal_height));
+        if !ui.is_rect_visible(padded_rect) {
             let is_at_bottom = new_scroll_offset >= max_scroll;
             return (outer_response, new_scroll_offset, is_at_bottom, viewport_cols, viewport_rows);
         }
         let pointer_pos = ui.ctx().pointer_latest_pos();
-        let is_over_terminal = pointer_pos.map_or(false, |p| p.x >= outer_rect.min.x && p.x < outer_rect.min.x + content_width && p.y >= outer_rect.min.y && p.y < outer_rect.max.y);
+        let is_over_terminal = pointer_pos.map_or(false, |p| padded_rect.contains(p));
         if is_over_terminal {
-            let scroll_delta = ui.input(|i| i.raw_scroll_delta.y);
-            if scroll_delta != 0.0 {
-                let lines_to_scroll = (scroll_delta / self.cell_height).round() as i32;
+            let lines_to_scroll = self.consume_wheel(ui, viewport_rows);
+            if self.report_wheel {
+                self.wheel_lines += lines_to_scroll;
+            } else if lines_to_scroll != 0 {
                 let new_offset_i32 = new_scroll_offset as i32 - lines_to_scroll;
                 let new_offset = new_offset_i32.max(0) as usize;
                 new_scroll_offset = new_offset.min(max_scroll);
             }
         }
         new_scroll_offset = new_scroll_offset.min(max_scroll);
+        // Scrolled back with the activity pane on: the bottom rows keep showing live output
+        self.live_pane = self.activity_pane_rows
+            .filter(|&rows| new_scroll_offset < max_scroll && rows + MIN_HISTORY_ROWS <= viewport_rows)
+            .map(|rows| (viewport_rows - rows, total_lines - rows));
+        let history_rows = self.live_pane.map_or(viewport_rows, |(first_row, _)| first_row);
         let visible_start = new_scroll_offset;
-        let visible_end = (visible_start + viewport_rows).min(total_lines);
+        let visible_end = (visible_start + history_rows).min(total_lines);
         let painter = ui.painter_at(terminal_rect);
         let bg_color = if emulator.reverse_video() {
             buffer.default_fg()
         } else {
             background
         };
-        painter.rect_filled(terminal_rect, 0.0, bg_color);
+        if let Some(area) = letterbox {
+            let sides = Rect::from_min_max(area.min, Pos2::new(area.max.x, outer_rect.max.y));
+            ui.painter().rect_filled(sides, 0.0, bg_color);
+        }
+        ui.painter_at(padded_rect).rect_filled(padded_rect, 0.0, bg_color);
         for line_idx in visible_start..visible_end {
             let screen_row = line_idx - visible_start;
             self.render_line(
@@ -139,17 +627,74 @@ impl TerminalRenderer {
                 invert_colors,
             );
         }
+        self.render_images(ui.ctx(), &painter, buffer, terminal_rect.min, visible_start, history_rows);
+        if let Some((first_row, first_line)) = self.live_pane {
+            for line_idx in first_line..total_lines {
+                self.render_line(
+                    &painter,
+                    buffer,
+                    line_idx,
+                    first_row + line_idx - first_line,
+                    terminal_rect.min,
+                    selection,
+                    emulator.reverse_video(),
+                    invert_colors,
+                );
+            }
+            let pane_origin = terminal_rect.min + Vec2::new(0.0, first_row as f32 * self.cell_height);
+            self.render_images(ui.ctx(), &painter, buffer, pane_origin, first_line, viewport_rows - first_row);
+            let split_y = (terminal_rect.min.y + first_row as f32 * self.cell_height).floor();
+            painter.hline(terminal_rect.x_range(), split_y, egui::Stroke::new(1.0, buffer.default_fg()));
+        }
+        if let Some((line, start_col, end_col)) = self.search_highlight {
+            if let Some(row) = self.screen_row_of(line, visible_start, history_rows) {
+                let row = row as f32;
+                let highlight_rect = Rect::from_min_max(
+                    terminal_rect.min + Vec2::new(start_col as f32 * self.cell_width, row * self.cell_height),
+                    terminal_rect.min + Vec2::new(end_col as f32 * self.cell_width, (row + 1.0) * self.cell_height),
+                );
+                painter.rect_filled(highlight_rect, 0.0, SEARCH_HIGHLIGHT_COLOR);
+            }
+        }
+        if let Some((line, col)) = self.copy_cursor {
+            if let Some(row) = self.screen_row_of(line, visible_start, history_rows) {
+                let cell_rect = Rect::from_min_size(
+                    terminal_rect.min + Vec2::new(col as f32 * self.cell_width, row as f32 * self.cell_height),
+                    Vec2::new(self.cell_width, self.cell_height),
+                );
+                painter.rect_stroke(
+                    cell_rect,
+                    0.0,
+                    egui::Stroke::new(COPY_CURSOR_STROKE_WIDTH, buffer.default_fg()),
+                    egui::StrokeKind::Inside,
+                );
+            }
+        }
         if focused && emulator.cursor_visible() {
             self.update_cursor_blink(ui.ctx().input(|i| i.time));
             if self.cursor_visible {
-                self.render_cursor(&painter, buffer, terminal_rect.min, viewport_rows, visible_start, emulator.reverse_video(), invert_colors);
+                // While split, the cursor belongs to the live pane
+                let (cursor_origin, cursor_rows, cursor_start) = match self.live_pane {
+                    Some((first_row, first_line)) => (
+                        terminal_rect.min + Vec2::new(0.0, first_row as f32 * self.cell_height),
+                        viewport_rows - first_row,
+                        first_line,
+                    ),
+                    None => (terminal_rect.min, viewport_rows, visible_start),
+                };
+                self.render_cursor(&painter, buffer, cursor_origin, cursor_rows, cursor_start, emulator.reverse_video(), invert_colors);
             }
             ui.ctx().request_repaint_after(std::time::Duration::from_millis(CURSOR_BLINK_INTERVAL_MS));
         }
+        // Fade the whole terminal while input goes elsewhere (a dialog or another window)
+        if self.dim_unfocused && !(focused && ui.ctx().input(|i| i.focused)) {
+            let veil = Color32::from_rgba_unmultiplied(background.r(), background.g(), background.b(), UNFOCUSED_DIM_ALPHA);
+            ui.painter().rect_filled(padded_rect, 0.0, veil);
+        }
         if show_scrollbar {
             let scrollbar_rect = Rect::from_min_size(
                 Pos2::new(outer_rect.min.x + content_width, outer_rect.min.y),
-                Vec2::new(SCROLLBAR_WIDTH, terminal_height),
+                Vec2::new(SCROLLBAR_WIDTH, outer_rect.height()),
             );
             let scrollbar_response = ui.allocate_response(scrollbar_rect.size(), Sense::click_and_drag());
             let new_offset = self.render_scrollbar(
@@ -166,10 +711,69 @@ impl TerminalRenderer {
                 new_scroll_offset = new_offset_val;
             }
         }
+        if let Some(text) = &self.status_line {
+            self.render_status_line(ui, text, total_width, buffer.default_fg(), background);
+        }
+        // Release textures of images that left the buffer
+        self.image_textures.retain(|id, _| buffer.images().any(|(_, _, image)| image.id == *id));
+        // Keep only the layouts of lines drawn this frame
+        let frame = self.layout_frame;
+        self.line_layouts.get_mut().retain(|_, (last_used, _)| *last_used == frame);
+        self.layout_frame += 1;
         let is_at_bottom = new_scroll_offset >= max_scroll;
         (outer_response, new_scroll_offset, is_at_bottom, viewport_cols, viewport_rows)
     }
 
+    /// Draw inline images overlapping `rows` screen rows that start at buffer line `first_line`
+    fn render_images(
+        &mut self,
+        ctx: &egui::Context,
+        painter: &Painter,
+        buffer: &TerminalBuffer,
+        origin: Pos2,
+        first_line: usize,
+        rows: usize,
+    ) {
+        let clip = Rect::from_min_size(origin, Vec2::new(painter.clip_rect().width(), rows as f32 * self.cell_height));
+        let painter = painter.with_clip_rect(clip.intersect(painter.clip_rect()));
+        for (line, col, image) in buffer.images() {
+            if line >= first_line + rows || line + image.rows <= first_line {
+                continue;
+            }
+            // Still decoding: look again shortly, since nothing else may trigger a repaint
+            let Some(pixels) = image.pixels.get() else {
+                ctx.request_repaint_after(IMAGE_DECODE_POLL);
+                continue;
+            };
+            let texture = self.image_textures.entry(image.id).or_insert_with(|| {
+                ctx.load_texture(format!("terminal_image_{}", image.id), pixels.clone(), TextureOptions::LINEAR)
+            });
+            let top_left = origin + Vec2::new(
+                col as f32 * self.cell_width,
+                (line as f32 - first_line as f32) * self.cell_height,
+            );
+            let rect = Rect::from_min_size(
+                top_left,
+                Vec2::new(image.cols as f32 * self.cell_width, image.rows as f32 * self.cell_height),
+            );
+            painter.image(texture.id(), rect, Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)), Color32::WHITE);
+        }
+    }
+
+    /// Paint the status line as a reverse-video bar; it never touches the terminal buffer
+    fn render_status_line(&self, ui: &mut Ui, text: &str, width: f32, foreground: Color32, background: Color32) {
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(width, self.cell_height), Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, foreground);
+        painter.text(
+            Pos2::new(rect.min.x + self.cell_width, rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            text,
+            FontId::new(self.font_size, FontFamily::Monospace),
+            background,
+        );
+    }
+
     fn render_scrollbar(
         &self,
         ui: &mut Ui,
@@ -179,10 +783,10 @@ impl TerminalRenderer {
         max_scroll: usize,
         total_lines: usize,
         viewport_rows: usize,
-        _background: Color32,
+        background: Color32,
     ) -> Option<usize> {
         let painter = ui.painter_at(rect);
-        let scrollbar_bg = Color32::from_rgba_unmultiplied(40, 40, 40, 255);
+        let scrollbar_bg = background.lerp_to_gamma(self.scrollbar_accent, SCROLLBAR_TRACK_TINT);
         painter.rect_filled(rect, 0.0, scrollbar_bg);
         if max_scroll == 0 {
             return None;
@@ -199,11 +803,8 @@ impl TerminalRenderer {
             Pos2::new(rect.min.x + 2.0, rect.min.y + thumb_position),
             Vec2::new(SCROLLBAR_WIDTH - 4.0, thumb_height),
         );
-        let thumb_color = if response.hovered() || response.dragged() {
-            Color32::from_rgba_unmultiplied(120, 120, 120, 255)
-        } else {
-            Color32::from_rgba_unmultiplied(80, 80, 80, 255)
-        };
+        let tint = if response.hovered() || response.dragged() { SCROLLBAR_THUMB_HOVER_TINT } else { SCROLLBAR_THUMB_TINT };
+        let thumb_color = background.lerp_to_gamma(self.scrollbar_accent, tint);
         painter.rect_filled(thumb_rect, 2.0, thumb_color);
         let mut new_scroll_offset = None;
         if response.dragged() {
@@ -247,6 +848,7 @@ impl TerminalRenderer {
         // Use floor to snap to pixel boundaries and avoid sub-pixel gaps
         let y = (origin.y + screen_row as f32 * self.cell_height).floor();
         let _scrollback_offset = buffer.scrollback_len();
+        let mut glyphs = Vec::new();
         for (col, cell) in line.cells().iter().enumerate() {
             let x = (origin.x + col as f32 * self.cell_width).floor();
             // Calculate next cell position to ensure no gaps
@@ -259,47 +861,40 @@ impl TerminalRenderer {
             let is_selected = selection.map_or(false, |sel| {
                 sel.contains(line_idx, col)
             });
-            let (mut fg, mut bg) = cell.style.effective_colors(buffer.default_bg());
+            let (mut fg, mut bg) = self.cell_colors(&cell.style, buffer.default_bg());
             if reverse_video {
                 std::mem::swap(&mut fg, &mut bg);
             }
-            let selection_bg = if is_selected {
-                std::mem::swap(&mut fg, &mut bg);
-                Some(bg)
-            } else {
-                None
-            };
             if invert_colors {
                 std::mem::swap(&mut fg, &mut bg);
             }
-            if bg != Color32::TRANSPARENT && bg != buffer.default_bg() && !is_selected {
+            if bg != Color32::TRANSPARENT && bg != buffer.default_bg() {
                 painter.rect_filled(cell_rect, 0.0, bg);
             }
-            if let Some(sel_bg) = selection_bg {
-                // Draw selection highlight 2 pixels above text, 2 pixels less from bottom
-                const SELECTION_TOP_OFFSET: f32 = 2.0;
-                const SELECTION_BOTTOM_OFFSET: f32 = 2.0;
-                let selection_rect = Rect::from_min_max(
-                    Pos2::new(cell_rect.min.x, cell_rect.min.y - SELECTION_TOP_OFFSET),
-                    Pos2::new(cell_rect.max.x, cell_rect.max.y - SELECTION_BOTTOM_OFFSET),
-                );
-                painter.rect_filled(selection_rect, 0.0, sel_bg);
+            if is_selected {
+                let under = if bg == Color32::TRANSPARENT { buffer.default_bg() } else { bg };
+                painter.rect_filled(cell_rect, 0.0, self.selection_overlay(under));
             }
-            if cell.ch != ' ' {
-                let font_id = FontId::new(self.font_size, FontFamily::Monospace);
-                painter.text(
-                    cell_rect.min,
-                    egui::Align2::LEFT_TOP,
-                    cell.ch,
-                    font_id,
-                    fg,
-                );
+            if cell.ch != ' ' && cell.ch != WIDE_CHAR_SPACER {
+                if self.cache_line_layouts {
+                    glyphs.push(LineGlyph {
+                        col,
+                        ch: cell.ch,
+                        color: fg,
+                        bold: cell.style.bold,
+                        italic: cell.style.italic,
+                        wide: line.cells().get(col + 1).is_some_and(|next| next.ch == WIDE_CHAR_SPACER),
+                    });
+                } else {
+                    self.paint_glyph(painter, cell_rect.min, cell.ch, &cell.style, fg);
+                }
             }
-            if cell.style.underline {
-                let underline_y = (next_y - UNDERLINE_OFFSET_PIXELS).floor();
+            if cell.style.underline || cell.style.link.is_some() {
+                let underline_y = (next_y - self.text_offset() - UNDERLINE_OFFSET_PIXELS).floor();
+                let color = if cell.style.link.is_some() { self.link_color } else { fg };
                 painter.line_segment(
                     [Pos2::new(x, underline_y), Pos2::new(next_x, underline_y)],
-                    egui::Stroke::new(UNDERLINE_STROKE_WIDTH, fg),
+                    egui::Stroke::new(UNDERLINE_STROKE_WIDTH, color),
                 );
             }
             if cell.style.strikethrough {
@@ -310,6 +905,7 @@ impl TerminalRenderer {
                 );
             }
         }
+        self.paint_line_glyphs(painter, Pos2::new(origin.x, y), &glyphs);
     }
 
     fn render_cursor(
@@ -360,7 +956,7 @@ impl TerminalRenderer {
                 // Get the line at cursor position (scrollback_len + cursor.row)
                 if let Some(line) = buffer.get_line(scrollback_len + cursor.row) {
                     if let Some(cell) = line.get(cursor.col) {
-                        if cell.ch != ' ' {
+                        if cell.ch != ' ' && cell.ch != WIDE_CHAR_SPACER {
                             let mut text_color = if reverse_video {
                                 buffer.default_fg()
                             } else {
@@ -376,7 +972,7 @@ impl TerminalRenderer {
                             }
                             let font_id = FontId::new(self.font_size, FontFamily::Monospace);
                             painter.text(
-                                Pos2::new(x, y),
+                                Pos2::new(x, y + self.text_offset()),
                                 egui::Align2::LEFT_TOP,
                                 cell.ch,
                                 font_id,
@@ -388,7 +984,7 @@ impl TerminalRenderer {
             }
             CursorType::Underline => {
                 let underline_thickness = (self.font_size * CURSOR_UNDERLINE_THICKNESS_MULTIPLIER).max(CURSOR_UNDERLINE_MIN_THICKNESS);
-                let underline_y = (y + self.cell_height - underline_thickness).floor();
+                let underline_y = (y + self.cell_height - self.text_offset() - underline_thickness).floor();
                 painter.rect_filled(
                     Rect::from_min_size(
                         Pos2::new(x, underline_y),
@@ -429,17 +1025,20 @@ impl TerminalRenderer {
 
 
     pub fn cell_at_pos(&self, pos: Pos2, origin: Pos2, buffer: &TerminalBuffer, rect_height: f32, scroll_offset: usize) -> Option<(usize, usize)> {
-        let relative = pos - origin;
+        let relative = pos - self.grid_origin(Rect::from_min_size(origin, Vec2::ZERO));
         if relative.x < 0.0 || relative.y < 0.0 {
             return None;
         }
         let col = (relative.x / self.cell_width) as usize;
         let row = (relative.y / self.cell_height) as usize;
-        let actual_rows = (rect_height / self.cell_height).floor() as usize;
+        let actual_rows = ((rect_height - 2.0 * self.padding) / self.cell_height).floor() as usize;
         if col >= buffer.cols() || row >= actual_rows {
             return None;
         }
-        let line_idx = scroll_offset + row;
+        let line_idx = match self.live_pane {
+            Some((first_row, first_line)) if row >= first_row => first_line + row - first_row,
+            _ => scroll_offset + row,
+        };
         if line_idx >= buffer.total_lines() {
             return None;
         }
@@ -472,6 +1071,7 @@ impl TerminalRenderer {
         // Use floor to snap to pixel boundaries and avoid sub-pixel gaps
         let y = (origin.y + screen_row as f32 * self.cell_height).floor();
         let _scrollback_offset = buffer.scrollback_len();
+        let mut glyphs = Vec::new();
         for (col, cell) in line.cells().iter().enumerate() {
             let x = (origin.x + col as f32 * self.cell_width).floor();
             // Calculate next cell position to ensure no gaps
@@ -484,34 +1084,40 @@ impl TerminalRenderer {
             let is_selected = selection.map_or(false, |sel| {
                 sel.contains(line_idx, col)
             });
-            let (mut fg, mut bg) = cell.style.effective_colors(buffer.default_bg());
+            let (mut fg, mut bg) = self.cell_colors(&cell.style, buffer.default_bg());
             if reverse_video {
                 std::mem::swap(&mut fg, &mut bg);
             }
-            if is_selected {
-                std::mem::swap(&mut fg, &mut bg);
-            }
             // Invert colors for bell blink
             std::mem::swap(&mut fg, &mut bg);
 
             if bg != Color32::TRANSPARENT && bg != buffer.default_bg() {
                 painter.rect_filled(cell_rect, 0.0, bg);
             }
-            if cell.ch != ' ' {
-                let font_id = FontId::new(self.font_size, FontFamily::Monospace);
-                painter.text(
-                    cell_rect.min,
-                    egui::Align2::LEFT_TOP,
-                    cell.ch,
-                    font_id,
-                    fg,
-                );
+            if is_selected {
+                let under = if bg == Color32::TRANSPARENT { buffer.default_bg() } else { bg };
+                painter.rect_filled(cell_rect, 0.0, self.selection_overlay(under));
+            }
+            if cell.ch != ' ' && cell.ch != WIDE_CHAR_SPACER {
+                if self.cache_line_layouts {
+                    glyphs.push(LineGlyph {
+                        col,
+                        ch: cell.ch,
+                        color: fg,
+                        bold: cell.style.bold,
+                        italic: cell.style.italic,
+                        wide: line.cells().get(col + 1).is_some_and(|next| next.ch == WIDE_CHAR_SPACER),
+                    });
+                } else {
+                    self.paint_glyph(painter, cell_rect.min, cell.ch, &cell.style, fg);
+                }
             }
-            if cell.style.underline {
-                let underline_y = (next_y - UNDERLINE_OFFSET_PIXELS).floor();
+            if cell.style.underline || cell.style.link.is_some() {
+                let underline_y = (next_y - self.text_offset() - UNDERLINE_OFFSET_PIXELS).floor();
+                let color = if cell.style.link.is_some() { self.link_color } else { fg };
                 painter.line_segment(
                     [Pos2::new(x, underline_y), Pos2::new(next_x, underline_y)],
-                    egui::Stroke::new(UNDERLINE_STROKE_WIDTH, fg),
+                    egui::Stroke::new(UNDERLINE_STROKE_WIDTH, color),
                 );
             }
             if cell.style.strikethrough {
@@ -522,6 +1128,7 @@ impl TerminalRenderer {
                 );
             }
         }
+        self.paint_line_glyphs(painter, Pos2::new(origin.x, y), &glyphs);
     }
 
 }
@@ -1,5 +1,7 @@
 use super::ansi::{parse_sgr, AnsiAction, AnsiParser};
-use super::buffer::TerminalBuffer;
+use super::buffer::{is_wide_char, Charset, TerminalBuffer};
+use super::graphics::{self, CellGeometry, SixelDecoder};
+use crate::config::Encoding;
 
 // VT100 mode flags
 const MODE_CURSOR_KEYS: u16 = 1;
@@ -11,8 +13,55 @@ const MODE_ORIGIN: u16 = 6;
 const MODE_AUTO_WRAP: u16 = 7;
 const MODE_AUTO_REPEAT: u16 = 8;
 const MODE_CURSOR_VISIBLE: u16 = 25;
+const MODE_LEFT_RIGHT_MARGIN: u16 = 69;
+const MODE_MOUSE_CLICKS: u16 = 1000;
+const MODE_MOUSE_DRAGS: u16 = 1002;
+const MODE_MOUSE_ALL_MOTION: u16 = 1003;
+const MODE_MOUSE_SGR: u16 = 1006;
 const MODE_BRACKETED_PASTE: u16 = 2004;
 
+/// Which mouse events the program has asked to be sent (modes 1000, 1002 and 1003)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum MouseTracking {
+    #[default]
+    Off,
+    Clicks,
+    // Clicks, and motion while a button is held
+    Drags,
+    AllMotion,
+}
+
+// Device attributes reported to programs that ask what terminal this is
+const PRIMARY_DEVICE_ATTRIBUTES: &str = "\x1b[?1;2c";
+const SECONDARY_DEVICE_ATTRIBUTES: &str = "\x1b[>0;10;0c";
+
+// Window manipulation (CSI Ps t)
+const WINDOW_OP_RESIZE_CHARS: u16 = 8;
+const MIN_RESIZE_ROWS: u16 = 2;
+const MAX_RESIZE_ROWS: u16 = 500;
+const MIN_RESIZE_COLS: u16 = 10;
+const MAX_RESIZE_COLS: u16 = 1000;
+
+// Code page 437 glyphs for bytes 0x80-0xFF
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+// Cell size assumed for inline images until the renderer reports one
+const DEFAULT_CELL_WIDTH: f32 = 8.0;
+const DEFAULT_CELL_HEIGHT: f32 = 16.0;
+
+// ANSI (non-private) mode flags
+const MODE_INSERT: u16 = 4;
+const MODE_LINEFEED_NEWLINE: u16 = 20;
+
 pub struct Vt100Mode {
     parser: AnsiParser,
     cursor_keys_application: bool,
@@ -20,9 +69,35 @@ pub struct Vt100Mode {
     cursor_visible: bool,
     reverse_video: bool,
     bracketed_paste: bool,
+    mouse_tracking: MouseTracking,
+    mouse_sgr: bool,
+    // A full-screen program switched to the alternate screen (modes 47, 1047 and 1049)
+    alternate_screen: bool,
+    insert_mode: bool,
+    linefeed_newline: bool,
+    // DECLRMM: while set, CSI s sets left/right margins (DECSLRM) instead of saving the cursor
+    left_right_margin_mode: bool,
+    encoding: Encoding,
     utf8_buffer: Vec<u8>,
+    // Line break for a CR that turns out not to be followed by LF
+    lone_cr_newline: bool,
+    cr_pending: bool,
     bell_pending: bool,
     title: Option<String>,
+    resize_request: Option<(usize, usize)>,
+    // Remote working directory reported with OSC 7
+    current_directory: Option<String>,
+    // Text a program asked to put on the clipboard with OSC 52
+    clipboard_write: Option<String>,
+    // Replies to status and attribute queries, to be sent back to the server
+    responses: Vec<Vec<u8>>,
+    // Cell size in pixels, for sizing inline images
+    cell_pixel_size: (f32, f32),
+    sixel_enabled: bool,
+    // Sixel image being received through DCS
+    sixel: Option<SixelDecoder>,
+    // Last graphic character printed, for REP
+    last_char: Option<char>,
 }
 
 impl Default for Vt100Mode {
@@ -40,18 +115,114 @@ impl Vt100Mode {
             cursor_visible: true,
             reverse_video: false,
             bracketed_paste: false,
+            mouse_tracking: MouseTracking::Off,
+            mouse_sgr: false,
+            alternate_screen: false,
+            insert_mode: false,
+            linefeed_newline: false,
+            left_right_margin_mode: false,
+            encoding: Encoding::default(),
             utf8_buffer: Vec::new(),
+            lone_cr_newline: false,
+            cr_pending: false,
             bell_pending: false,
             title: None,
+            resize_request: None,
+            current_directory: None,
+            clipboard_write: None,
+            responses: Vec::new(),
+            cell_pixel_size: (DEFAULT_CELL_WIDTH, DEFAULT_CELL_HEIGHT),
+            sixel_enabled: false,
+            sixel: None,
+            last_char: None,
+        }
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            self.utf8_buffer.clear();
+        }
+    }
+
+    /// Full reset (RIS): clear the screen and return every mode to its default,
+    /// as on a VT100 at power-on. Configured behavior such as the encoding and tab width is kept.
+    pub fn reset(&mut self, buffer: &mut TerminalBuffer) {
+        buffer.reset_style();
+        buffer.erase_in_display(2);
+        buffer.reset_scroll_region();
+        buffer.reset_margins();
+        // Leaving origin mode also homes the cursor to 0,0
+        buffer.set_origin_mode(false);
+        buffer.clear_saved_cursor();
+        buffer.reset_charsets();
+        self.cursor_keys_application = false;
+        self.auto_wrap = true;
+        self.cursor_visible = true;
+        self.reverse_video = false;
+        self.bracketed_paste = false;
+        self.mouse_tracking = MouseTracking::Off;
+        self.mouse_sgr = false;
+        self.alternate_screen = false;
+        self.insert_mode = false;
+        self.linefeed_newline = false;
+        self.left_right_margin_mode = false;
+        self.cr_pending = false;
+        self.utf8_buffer.clear();
+        self.sixel = None;
+        self.last_char = None;
+    }
+
+    pub fn set_cell_pixel_size(&mut self, width: f32, height: f32) {
+        if width > 0.0 && height > 0.0 {
+            self.cell_pixel_size = (width, height);
+        }
+    }
+
+    pub fn set_sixel_enabled(&mut self, enabled: bool) {
+        self.sixel_enabled = enabled;
+        if !enabled {
+            self.sixel = None;
+        }
+    }
+
+    fn cell_geometry(&self, buffer: &TerminalBuffer) -> CellGeometry {
+        CellGeometry {
+            cell_width: self.cell_pixel_size.0,
+            cell_height: self.cell_pixel_size.1,
+            cols: buffer.cols(),
+            rows: buffer.rows(),
+        }
+    }
+
+    /// Treat a CR that is not followed by LF as CRLF, for devices that misuse bare CR
+    pub fn set_lone_cr_newline(&mut self, enabled: bool) {
+        self.lone_cr_newline = enabled;
+        self.cr_pending &= enabled;
+    }
+
+    /// Complete a pending lone CR unless the next control is another line ending
+    fn resolve_pending_cr(&mut self, buffer: &mut TerminalBuffer, next_is_line_ending: bool) {
+        if std::mem::take(&mut self.cr_pending) && !next_is_line_ending {
+            buffer.new_line();
         }
     }
 
     pub fn process(&mut self, buffer: &mut TerminalBuffer, data: &[u8]) {
         for &byte in data {
+            // 8-bit encodings map each high byte straight to a character
+            if byte >= 0x80 && self.encoding != Encoding::Utf8 {
+                if let Some(ch) = self.decode_8bit(byte) {
+                    self.resolve_pending_cr(buffer, false);
+                    self.print(buffer, ch);
+                }
+                continue;
+            }
             if !self.utf8_buffer.is_empty() {
                 self.utf8_buffer.push(byte);
                 if let Some(ch) = self.try_decode_utf8() {
-                    buffer.put_char(ch);
+                    self.resolve_pending_cr(buffer, false);
+                    self.print(buffer, ch);
                 }
                 continue;
             }
@@ -68,6 +239,29 @@ impl Vt100Mode {
         }
     }
 
+    fn print(&mut self, buffer: &mut TerminalBuffer, ch: char) {
+        self.put_char(buffer, ch);
+        self.last_char = Some(ch);
+    }
+
+    /// Write a character, first making room for it in insert mode (IRM)
+    fn put_char(&self, buffer: &mut TerminalBuffer, ch: char) {
+        if self.insert_mode {
+            buffer.insert_chars(if is_wide_char(ch) { 2 } else { 1 });
+        }
+        buffer.put_char(ch);
+    }
+
+    fn decode_8bit(&self, byte: u8) -> Option<char> {
+        match self.encoding {
+            Encoding::Utf8 => None,
+            // 0x80-0x9F are C1 control codes in Latin-1; they have no glyph
+            Encoding::Latin1 if byte < 0xA0 => None,
+            Encoding::Latin1 => Some(byte as char),
+            Encoding::Cp437 => Some(CP437_HIGH[(byte - 0x80) as usize]),
+        }
+    }
+
     fn try_decode_utf8(&mut self) -> Option<char> {
         let bytes = &self.utf8_buffer;
         let expected_len = if bytes[0] < 0xE0 {
@@ -86,9 +280,11 @@ impl Vt100Mode {
     }
 
     fn handle_action(&mut self, buffer: &mut TerminalBuffer, action: AnsiAction) {
+        let is_line_ending = matches!(action, AnsiAction::Execute(0x0A | 0x0D));
+        self.resolve_pending_cr(buffer, is_line_ending);
         match action {
             AnsiAction::Print(ch) => {
-                buffer.put_char(ch);
+                self.print(buffer, ch);
             }
             AnsiAction::Execute(byte) => {
                 self.handle_execute(buffer, byte);
@@ -100,9 +296,26 @@ impl Vt100Mode {
                 self.handle_esc(buffer, &intermediates, final_byte);
             }
             AnsiAction::OscDispatch { params } => {
-                self.handle_osc(&params);
+                self.handle_osc(buffer, &params);
+            }
+            AnsiAction::DcsHook { intermediates, final_byte, .. } => {
+                // Other DCS strings (DECRQSS, tmux passthrough, ...) are ignored
+                if self.sixel_enabled && final_byte == 'q' && intermediates.is_empty() {
+                    self.sixel = Some(SixelDecoder::new());
+                }
+            }
+            AnsiAction::DcsPut(byte) => {
+                if let Some(sixel) = &mut self.sixel {
+                    sixel.put(byte);
+                }
+            }
+            AnsiAction::DcsUnhook => {
+                if let Some(sixel) = self.sixel.take() {
+                    if let Some(placed) = sixel.finish(self.cell_geometry(buffer)) {
+                        buffer.place_image(placed);
+                    }
+                }
             }
-            AnsiAction::DcsHook { .. } | AnsiAction::DcsPut(_) | AnsiAction::DcsUnhook => {}
         }
     }
 
@@ -112,81 +325,160 @@ impl Vt100Mode {
             0x08 => buffer.backspace(),
             0x09 => buffer.tab(),
             0x0A | 0x0B | 0x0C => buffer.new_line(),
-            0x0D => buffer.carriage_return(),
-            0x0E => {} // Shift Out
-            0x0F => {} // Shift In
+            0x0D => {
+                buffer.carriage_return();
+                self.cr_pending = self.lone_cr_newline;
+            }
+            0x0E => buffer.set_shift_out(true),
+            0x0F => buffer.set_shift_out(false),
             _ => {}
         }
     }
 
-    fn handle_csi(&mut self, buffer: &mut TerminalBuffer, params: &[u16], intermediates: &[u8], final_byte: char) {
+    fn handle_csi(&mut self, buffer: &mut TerminalBuffer, params: &[Option<u16>], intermediates: &[u8], final_byte: char) {
+        // An explicit value is used as sent, even 0; an omitted one takes the default
         let param = |i: usize, default: u16| -> u16 {
-            params.get(i).copied().filter(|&p| p != 0).unwrap_or(default)
+            params.get(i).copied().flatten().unwrap_or(default)
         };
-        if intermediates.first() == Some(&b'?') {
-            self.handle_dec_private_mode(buffer, params, final_byte);
-            return;
+        // Counts and 1-based positions treat 0 like an omitted parameter
+        let count = |i: usize| -> u16 { param(i, 1).max(1) };
+        // Mode and attribute lists read omitted entries as 0
+        let values: Vec<u16> = params.iter().map(|p| p.unwrap_or(0)).collect();
+        match (intermediates.first(), final_byte) {
+            // DECXCPR: cursor position report in the DEC private form
+            (Some(b'?'), 'n') => {
+                if values.first() == Some(&6) {
+                    let cursor = buffer.cursor_relative();
+                    self.respond(format!("\x1b[?{};{}R", cursor.row + 1, cursor.col + 1));
+                }
+                return;
+            }
+            (Some(b'?'), _) => {
+                self.handle_dec_private_mode(buffer, &values, final_byte);
+                return;
+            }
+            // Secondary DA: terminal type, firmware version and ROM cartridge
+            (Some(b'>'), 'c') => {
+                if param(0, 0) == 0 {
+                    self.respond(SECONDARY_DEVICE_ATTRIBUTES.to_string());
+                }
+                return;
+            }
+            // Other private sequences (e.g. xterm's CSI > 4 ; 1 m) must not be taken for the standard ones
+            (Some(b'<' | b'=' | b'>'), _) => return,
+            _ => {}
         }
         match final_byte {
-            '@' => buffer.insert_chars(param(0, 1) as usize),
-            'A' => buffer.move_cursor_up(param(0, 1) as usize),
-            'B' | 'e' => buffer.move_cursor_down(param(0, 1) as usize),
-            'C' | 'a' => buffer.move_cursor_right(param(0, 1) as usize),
-            'D' => buffer.move_cursor_left(param(0, 1) as usize),
+            '@' => buffer.insert_chars(count(0) as usize),
+            // Relative moves: CUU/VPB, CUD/VPR, CUF/HPR and CUB/HPB stop at the margins
+            'A' | 'k' => buffer.move_cursor_up(count(0) as usize),
+            'B' | 'e' => buffer.move_cursor_down(count(0) as usize),
+            'C' | 'a' => buffer.move_cursor_right(count(0) as usize),
+            'D' | 'j' => buffer.move_cursor_left(count(0) as usize),
             'E' => {
-                buffer.move_cursor_down(param(0, 1) as usize);
+                buffer.move_cursor_down(count(0) as usize);
                 buffer.carriage_return();
             }
             'F' => {
-                buffer.move_cursor_up(param(0, 1) as usize);
+                buffer.move_cursor_up(count(0) as usize);
                 buffer.carriage_return();
             }
-            'G' | '`' => {
-                let cursor = buffer.cursor();
-                buffer.set_cursor_position(cursor.row, (param(0, 1) as usize).saturating_sub(1));
-            }
+            'G' | '`' => buffer.set_cursor_col((count(0) as usize).saturating_sub(1)),
             'H' | 'f' => {
-                let row = (param(0, 1) as usize).saturating_sub(1);
-                let col = (param(1, 1) as usize).saturating_sub(1);
+                let row = (count(0) as usize).saturating_sub(1);
+                let col = (count(1) as usize).saturating_sub(1);
                 buffer.set_cursor_position(row, col);
             }
             'I' => {
-                for _ in 0..param(0, 1) {
+                // Every tab stop has been reached after one per column
+                for _ in 0..(count(0) as usize).min(buffer.cols()) {
                     buffer.tab();
                 }
             }
             'J' => buffer.erase_in_display(param(0, 0) as u8),
             'K' => buffer.erase_in_line(param(0, 0) as u8),
-            'L' => buffer.insert_lines(param(0, 1) as usize),
-            'M' => buffer.delete_lines(param(0, 1) as usize),
-            'P' => buffer.delete_chars(param(0, 1) as usize),
-            'S' => buffer.scroll_up(param(0, 1) as usize),
-            'T' => buffer.scroll_down(param(0, 1) as usize),
-            'X' => buffer.erase_chars(param(0, 1) as usize),
-            'd' => {
-                let cursor = buffer.cursor();
-                buffer.set_cursor_position((param(0, 1) as usize).saturating_sub(1), cursor.col);
-            }
-            'h' => self.handle_set_mode(buffer, params, true),
-            'l' => self.handle_set_mode(buffer, params, false),
+            'L' => buffer.insert_lines(count(0) as usize),
+            'M' => buffer.delete_lines(count(0) as usize),
+            'P' => buffer.delete_chars(count(0) as usize),
+            'S' => buffer.scroll_up(count(0) as usize),
+            'T' => buffer.scroll_down(count(0) as usize),
+            'X' => buffer.erase_chars(count(0) as usize),
+            'b' => {
+                // REP: more than a screenful of repeats would only overwrite itself
+                if let Some(ch) = self.last_char {
+                    let repeat = (count(0) as usize).min(buffer.cols() * buffer.rows());
+                    for _ in 0..repeat {
+                        self.put_char(buffer, ch);
+                    }
+                }
+            }
+            // Primary DA: a VT100 with advanced video
+            'c' if param(0, 0) == 0 => self.respond(PRIMARY_DEVICE_ATTRIBUTES.to_string()),
+            'd' => buffer.set_cursor_row((count(0) as usize).saturating_sub(1)),
+            'h' => self.handle_set_mode(buffer, &values, true),
+            'l' => self.handle_set_mode(buffer, &values, false),
             'm' => {
-                let params_vec: Vec<u16> = if params.is_empty() { vec![0] } else { params.to_vec() };
+                let params_vec: Vec<u16> = if values.is_empty() { vec![0] } else { values };
                 let mut style = buffer.current_style();
                 parse_sgr(&params_vec, &mut style, buffer.default_fg());
                 buffer.set_style(style);
             }
-            'n' => {} // Device status report - handled at SSH level
+            'n' => match param(0, 0) {
+                5 => self.respond("\x1b[0n".to_string()),
+                6 => {
+                    let cursor = buffer.cursor_relative();
+                    self.respond(format!("\x1b[{};{}R", cursor.row + 1, cursor.col + 1));
+                }
+                _ => {}
+            },
             'r' => {
-                let top = (param(0, 1) as usize).saturating_sub(1);
-                let bottom = (param(1, buffer.rows() as u16) as usize).saturating_sub(1);
+                let top = (count(0) as usize).saturating_sub(1);
+                // A bottom margin of 0 means the last row, like an omitted one
+                let bottom = match param(1, 0) {
+                    0 => buffer.rows(),
+                    bottom => bottom as usize,
+                }
+                .saturating_sub(1);
                 buffer.set_scroll_region(top, bottom);
+                // DECSTBM homes the cursor (to the region origin in origin mode)
+                buffer.set_cursor_position(0, 0);
+            }
+            // With DECLRMM on, CSI s is DECSLRM; a bare CSI s still saves the cursor (SCOSC)
+            's' if self.left_right_margin_mode && params.iter().any(Option::is_some) => {
+                let left = (count(0) as usize).saturating_sub(1);
+                let right = match param(1, 0) {
+                    0 => buffer.cols(),
+                    right => right as usize,
+                }
+                .saturating_sub(1);
+                buffer.set_margins(left, right);
+                // DECSLRM homes the cursor like DECSTBM
+                buffer.set_cursor_position(0, 0);
             }
             's' => buffer.save_cursor(),
+            't' => self.handle_window_op(buffer, params),
             'u' => buffer.restore_cursor(),
             _ => {}
         }
     }
 
+    fn handle_window_op(&mut self, buffer: &TerminalBuffer, params: &[Option<u16>]) {
+        // Only the resize-in-characters form is supported; reports and pixel sizes are ignored
+        if params.first() != Some(&Some(WINDOW_OP_RESIZE_CHARS)) {
+            return;
+        }
+        // An omitted or zero dimension keeps the current size
+        let rows = match params.get(1).copied().flatten() {
+            None | Some(0) => buffer.rows(),
+            Some(rows) => rows.clamp(MIN_RESIZE_ROWS, MAX_RESIZE_ROWS) as usize,
+        };
+        let cols = match params.get(2).copied().flatten() {
+            None | Some(0) => buffer.cols(),
+            Some(cols) => cols.clamp(MIN_RESIZE_COLS, MAX_RESIZE_COLS) as usize,
+        };
+        self.resize_request = Some((rows, cols));
+    }
+
     fn handle_dec_private_mode(&mut self, buffer: &mut TerminalBuffer, params: &[u16], final_byte: char) {
         let set = final_byte == 'h';
         for &param in params {
@@ -200,15 +492,33 @@ impl Vt100Mode {
                 MODE_AUTO_WRAP => self.auto_wrap = set,
                 MODE_AUTO_REPEAT => {}
                 MODE_CURSOR_VISIBLE => self.cursor_visible = set,
+                MODE_LEFT_RIGHT_MARGIN => {
+                    self.left_right_margin_mode = set;
+                    // Margins only exist while the mode is on
+                    if !set {
+                        buffer.reset_margins();
+                    }
+                }
                 MODE_BRACKETED_PASTE => self.bracketed_paste = set,
+                MODE_MOUSE_CLICKS | MODE_MOUSE_DRAGS | MODE_MOUSE_ALL_MOTION => {
+                    self.mouse_tracking = match param {
+                        _ if !set => MouseTracking::Off,
+                        MODE_MOUSE_CLICKS => MouseTracking::Clicks,
+                        MODE_MOUSE_DRAGS => MouseTracking::Drags,
+                        _ => MouseTracking::AllMotion,
+                    };
+                }
+                MODE_MOUSE_SGR => self.mouse_sgr = set,
                 47 | 1047 => {
                     // Alternate screen buffer - reset
+                    self.alternate_screen = set;
                     if !set {
                         buffer.erase_in_display(2);
                     }
                 }
                 1049 => {
                     // Alternate screen buffer with cursor save
+                    self.alternate_screen = set;
                     if set {
                         buffer.save_cursor();
                         buffer.erase_in_display(2);
@@ -225,11 +535,10 @@ impl Vt100Mode {
     fn handle_set_mode(&mut self, _buffer: &mut TerminalBuffer, params: &[u16], set: bool) {
         for &param in params {
             match param {
-                4 => {} // Insert mode
-                20 => {} // Automatic newline
+                MODE_INSERT => self.insert_mode = set,
+                MODE_LINEFEED_NEWLINE => self.linefeed_newline = set,
                 _ => {}
             }
-            let _ = set;
         }
     }
 
@@ -243,16 +552,9 @@ impl Vt100Mode {
                 buffer.new_line();
             }
             (None, 'M') => buffer.move_cursor_up(1),
-            (None, 'c') => {
-                buffer.erase_in_display(2);
-                buffer.set_cursor_position(0, 0);
-                buffer.reset_style();
-                buffer.reset_scroll_region();
-                self.cursor_keys_application = false;
-                self.auto_wrap = true;
-                self.cursor_visible = true;
-                self.reverse_video = false;
-            }
+            (None, 'c') => self.reset(buffer),
+            (Some(&b'('), designator) => buffer.designate_charset(0, Charset::from_designator(designator)),
+            (Some(&b')'), designator) => buffer.designate_charset(1, Charset::from_designator(designator)),
             (Some(&b'#'), '8') => {
                 // DEC Screen Alignment Test - fill screen with 'E'
                 let rows = buffer.rows();
@@ -269,7 +571,7 @@ impl Vt100Mode {
         }
     }
 
-    fn handle_osc(&mut self, params: &[String]) {
+    fn handle_osc(&mut self, buffer: &mut TerminalBuffer, params: &[String]) {
         if params.is_empty() {
             return;
         }
@@ -280,6 +582,44 @@ impl Vt100Mode {
                 }
             }
             "1" => {} // Icon name - ignored
+            "7" => {
+                // Working directory as file://host/path
+                if let Some(path) = parse_file_url(&params[1..].join(";")) {
+                    self.current_directory = Some(path);
+                }
+            }
+            "8" => {
+                // Hyperlink as 8;params;URI (the URI may contain ';'); an empty URI ends it
+                let url = params.get(2..).map(|uri| uri.join(";")).unwrap_or_default();
+                buffer.set_link((!url.is_empty()).then_some(url.as_str()));
+            }
+            "52" => {
+                // Clipboard as 52;targets;base64. Only the clipboard ("c") is honored, and
+                // queries ("?") are ignored so the clipboard can't be read without the user knowing.
+                let targets = params.get(1).map_or("", String::as_str);
+                let data = params.get(2).map_or("", String::as_str);
+                if targets.contains('c') && data != "?" {
+                    if let Some(bytes) = graphics::decode_base64(data) {
+                        self.clipboard_write = Some(String::from_utf8_lossy(&bytes).into_owned());
+                    }
+                }
+            }
+            "133" => {
+                // Shell integration: prompt starts and command output boundaries are tracked
+                match params.get(1).map(String::as_str) {
+                    Some("A") => buffer.mark_prompt(),
+                    Some("C") => buffer.mark_command_start(),
+                    Some("D") => buffer.mark_command_end(),
+                    _ => {}
+                }
+            }
+            "1337" => {
+                // iTerm2 inline image; the base64 payload never contains ';'
+                let geometry = self.cell_geometry(buffer);
+                if let Some(placed) = graphics::decode_iterm2_file(&params[1..].join(";"), geometry) {
+                    buffer.place_image(placed);
+                }
+            }
             _ => {}
         }
     }
@@ -292,10 +632,32 @@ impl Vt100Mode {
         std::mem::take(&mut self.bell_pending)
     }
 
+    pub fn current_directory(&self) -> Option<&str> {
+        self.current_directory.as_deref()
+    }
+
     pub fn take_title(&mut self) -> Option<String> {
         self.title.take()
     }
 
+    pub fn take_clipboard_write(&mut self) -> Option<String> {
+        self.clipboard_write.take()
+    }
+
+    /// Replies to device status and attribute queries, in the order they were asked
+    pub fn take_responses(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.responses)
+    }
+
+    fn respond(&mut self, response: String) {
+        self.responses.push(response.into_bytes());
+    }
+
+    /// Server-requested grid size as (rows, cols)
+    pub fn take_resize_request(&mut self) -> Option<(usize, usize)> {
+        self.resize_request.take()
+    }
+
     pub fn cursor_keys_application(&self) -> bool {
         self.cursor_keys_application
     }
@@ -304,8 +666,315 @@ impl Vt100Mode {
         self.bracketed_paste
     }
 
+    pub fn mouse_tracking(&self) -> MouseTracking {
+        self.mouse_tracking
+    }
+
+    /// Mouse reports use the SGR encoding (mode 1006)
+    pub fn mouse_sgr(&self) -> bool {
+        self.mouse_sgr
+    }
+
+    pub fn alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
     pub fn reverse_video(&self) -> bool {
         self.reverse_video
     }
+
+    pub fn auto_wrap(&self) -> bool {
+        self.auto_wrap
+    }
+
+    pub fn insert_mode(&self) -> bool {
+        self.insert_mode
+    }
+
+    pub fn linefeed_newline(&self) -> bool {
+        self.linefeed_newline
+    }
+}
+
+/// Path of a `file://host/path` URL with percent escapes decoded
+fn parse_file_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::Color32;
+
+    fn terminal(cols: usize, rows: usize) -> (Vt100Mode, TerminalBuffer) {
+        let mut buffer = TerminalBuffer::new(100, Color32::WHITE, Color32::BLACK);
+        buffer.resize(cols, rows);
+        (Vt100Mode::new(), buffer)
+    }
+
+    fn screen_line(buffer: &TerminalBuffer, row: usize) -> String {
+        buffer.get_line(buffer.scrollback_len() + row).map(|line| line.to_string()).unwrap_or_default()
+    }
+
+    fn cursor(buffer: &TerminalBuffer) -> (usize, usize) {
+        let cursor = buffer.cursor();
+        (cursor.row, cursor.col)
+    }
+
+    #[test]
+    fn printing_in_insert_mode_shifts_existing_text() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        vt.process(&mut buffer, b"xyz\x1b[H\x1b[4ha\x1b[3b");
+        assert_eq!(screen_line(&buffer, 0), "aaaaxyz");
+        assert_eq!(cursor(&buffer), (0, 4));
+    }
+
+    #[test]
+    fn decsc_decrc_restores_style_charsets_shift_and_origin_mode() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        // Origin mode in a 2;4 region, bold red, G0 line drawing, G1 ASCII, shifted out to G1
+        vt.process(&mut buffer, b"\x1b[2;4r\x1b[?6h\x1b[2;5H\x1b[1;31m\x1b(0\x1b)B\x0e\x1b7");
+        let saved_style = buffer.current_style();
+        // Change every saved field, then restore
+        vt.process(&mut buffer, b"\x1b[?6l\x1b[0;4;32m\x1b(B\x1b)0\x0f\x1b[5;1H\x1b8");
+        assert!(buffer.origin_mode());
+        assert_eq!(cursor(&buffer), (2, 4));
+        assert_eq!(buffer.current_style(), saved_style);
+        assert!(saved_style.bold && !saved_style.underline);
+        // Shifted out, so G1 prints ASCII; SI then prints G0 line drawing
+        vt.process(&mut buffer, b"q\x0fq");
+        assert_eq!(screen_line(&buffer, 2), "    q\u{2500}");
+    }
+
+    #[test]
+    fn cursor_reports_clamp_pending_wrap_column() {
+        let (mut vt, mut buffer) = terminal(10, 5);
+        vt.process(&mut buffer, b"0123456789\x1b[6n\x1b[?6n");
+        assert_eq!(vt.take_responses(), vec![b"\x1b[1;10R".to_vec(), b"\x1b[?1;10R".to_vec()]);
+    }
+
+    #[test]
+    fn cursor_report_in_origin_mode_clamps_to_right_margin() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[3;8s\x1b[?6h012345\x1b[6n");
+        assert_eq!(vt.take_responses(), vec![b"\x1b[1;6R".to_vec()]);
+    }
+
+    #[test]
+    fn bare_csi_s_saves_cursor_with_margin_mode_on() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[3;7H\x1b[s\x1b[H\x1b[u");
+        assert_eq!(cursor(&buffer), (2, 6));
+    }
+
+    #[test]
+    fn csi_s_with_parameters_sets_margins_with_margin_mode_on() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        vt.process(&mut buffer, b"\x1b[3;7H\x1b[?69h\x1b[5;10s");
+        // DECSLRM homes the cursor instead of saving it
+        assert_eq!(cursor(&buffer), (0, 0));
+        vt.process(&mut buffer, b"\x1b[1;5H\x1b[3D");
+        assert_eq!(cursor(&buffer), (0, 4));
+    }
+
+    #[test]
+    fn csi_s_with_parameters_saves_cursor_with_margin_mode_off() {
+        let (mut vt, mut buffer) = terminal(20, 5);
+        vt.process(&mut buffer, b"\x1b[3;7H\x1b[5;10s\x1b[H\x1b[u");
+        assert_eq!(cursor(&buffer), (2, 6));
+    }
+
+    #[test]
+    fn text_wraps_at_the_right_margin() {
+        let (mut vt, mut buffer) = terminal(10, 3);
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[3;6s\x1b[1;3Habcdef");
+        assert_eq!(screen_line(&buffer, 0), "  abcd");
+        assert_eq!(screen_line(&buffer, 1), "  ef");
+    }
+
+    #[test]
+    fn scrolling_moves_only_the_cells_between_margins() {
+        let (mut vt, mut buffer) = terminal(6, 3);
+        vt.process(&mut buffer, b"aaaaaa\r\nbbbbbb\r\ncccccc");
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[2;4s\x1b[3;2H\n");
+        assert_eq!(screen_line(&buffer, 0), "abbbaa");
+        assert_eq!(screen_line(&buffer, 1), "bcccbb");
+        assert_eq!(screen_line(&buffer, 2), "c   cc");
+    }
+
+    #[test]
+    fn insert_and_delete_chars_stop_at_the_right_margin() {
+        let (mut vt, mut buffer) = terminal(8, 2);
+        vt.process(&mut buffer, b"abcdefgh\x1b[?69h\x1b[2;5s\x1b[1;3H\x1b[@");
+        assert_eq!(screen_line(&buffer, 0), "ab cdfgh");
+        vt.process(&mut buffer, b"\x1b[1;2H\x1b[2P");
+        assert_eq!(screen_line(&buffer, 0), "acd  fgh");
+    }
+
+    #[test]
+    fn insert_and_delete_lines_stay_between_margins() {
+        let (mut vt, mut buffer) = terminal(4, 3);
+        vt.process(&mut buffer, b"aaaa\r\nbbbb\r\ncccc\x1b[?69h\x1b[2;3s\x1b[1;2H\x1b[L");
+        assert_eq!(screen_line(&buffer, 0), "a  a");
+        assert_eq!(screen_line(&buffer, 1), "baab");
+        assert_eq!(screen_line(&buffer, 2), "cbbc");
+        vt.process(&mut buffer, b"\x1b[1;2H\x1b[2M");
+        assert_eq!(screen_line(&buffer, 0), "abba");
+        assert_eq!(screen_line(&buffer, 1), "b  b");
+        assert_eq!(screen_line(&buffer, 2), "c  c");
+    }
+
+    #[test]
+    fn full_reset_returns_every_mode_to_its_default() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[3;8r\x1b[?69h\x1b[4;12s\x1b[5;5H\x1b7");
+        vt.process(&mut buffer, b"\x1b[?1h\x1b[?5h\x1b[?6h\x1b[?7l\x1b[?25l\x1b[?1002h\x1b[?1006h\x1b[?2004h\x1b[?1049h");
+        vt.process(&mut buffer, b"\x1b[4h\x1b[20h\x1b[1;4;31m\x1b(0\x1b)0\x0e");
+        vt.process(&mut buffer, b"\x1bc");
+        assert!(!vt.cursor_keys_application());
+        assert!(!vt.reverse_video());
+        assert!(vt.auto_wrap());
+        assert!(vt.cursor_visible());
+        assert_eq!(vt.mouse_tracking(), MouseTracking::Off);
+        assert!(!vt.mouse_sgr());
+        assert!(!vt.alternate_screen());
+        assert!(!vt.bracketed_paste());
+        assert!(!vt.insert_mode());
+        assert!(!vt.linefeed_newline());
+        assert!(!vt.left_right_margin_mode);
+        assert!(!buffer.origin_mode());
+        assert_eq!(buffer.scroll_region(), (0, 9));
+        assert_eq!(cursor(&buffer), (0, 0));
+        let style = buffer.current_style();
+        assert!(!style.bold && !style.underline);
+        assert_eq!(style.fg, Color32::WHITE);
+        // Nothing saved: DECRC homes the cursor
+        vt.process(&mut buffer, b"\x1b[3;3H\x1b8");
+        assert_eq!(cursor(&buffer), (0, 0));
+        // ASCII in G0 and G1, printing from G0, margins at the screen edges
+        vt.process(&mut buffer, b"q\x0eq\x0f\x1b[1;20Hxy");
+        assert_eq!(screen_line(&buffer, 0), format!("qq{}x", " ".repeat(17)));
+        assert_eq!(screen_line(&buffer, 1), "y");
+    }
+
+    #[test]
+    fn hpa_moves_to_an_absolute_column() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[3;5H\x1b[7`");
+        assert_eq!(cursor(&buffer), (2, 6));
+        // Omitted and 0 mean column 1; past the edge stops at the last column
+        vt.process(&mut buffer, b"\x1b[`");
+        assert_eq!(cursor(&buffer), (2, 0));
+        vt.process(&mut buffer, b"\x1b[5`\x1b[0`");
+        assert_eq!(cursor(&buffer), (2, 0));
+        vt.process(&mut buffer, b"\x1b[999`");
+        assert_eq!(cursor(&buffer), (2, 19));
+        // In origin mode the column counts from the left margin and stays inside the margins
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[5;10s\x1b[?6h\x1b[3`");
+        assert_eq!(cursor(&buffer), (0, 6));
+        vt.process(&mut buffer, b"\x1b[999`");
+        assert_eq!(cursor(&buffer), (0, 9));
+    }
+
+    #[test]
+    fn vpa_moves_to_an_absolute_row() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[3;5H\x1b[6d");
+        assert_eq!(cursor(&buffer), (5, 4));
+        vt.process(&mut buffer, b"\x1b[d");
+        assert_eq!(cursor(&buffer), (0, 4));
+        vt.process(&mut buffer, b"\x1b[4d\x1b[0d");
+        assert_eq!(cursor(&buffer), (0, 4));
+        vt.process(&mut buffer, b"\x1b[999d");
+        assert_eq!(cursor(&buffer), (9, 4));
+        // In origin mode the row counts from the top margin and stays inside the region
+        vt.process(&mut buffer, b"\x1b[3;6r\x1b[?6h\x1b[2d");
+        assert_eq!(cursor(&buffer), (3, 0));
+        vt.process(&mut buffer, b"\x1b[999d");
+        assert_eq!(cursor(&buffer), (5, 0));
+    }
+
+    #[test]
+    fn hpr_moves_right_by_a_count() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[2;3H\x1b[a");
+        assert_eq!(cursor(&buffer), (1, 3));
+        vt.process(&mut buffer, b"\x1b[0a");
+        assert_eq!(cursor(&buffer), (1, 4));
+        vt.process(&mut buffer, b"\x1b[5a");
+        assert_eq!(cursor(&buffer), (1, 9));
+        vt.process(&mut buffer, b"\x1b[999a");
+        assert_eq!(cursor(&buffer), (1, 19));
+        // Inside the side margins the move stops at the right margin
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[5;10s\x1b[1;6H\x1b[999a");
+        assert_eq!(cursor(&buffer), (0, 9));
+        vt.process(&mut buffer, b"\x1b[?6h\x1b[999a");
+        assert_eq!(cursor(&buffer), (0, 9));
+    }
+
+    #[test]
+    fn vpr_moves_down_by_a_count() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[2;3H\x1b[e");
+        assert_eq!(cursor(&buffer), (2, 2));
+        vt.process(&mut buffer, b"\x1b[0e");
+        assert_eq!(cursor(&buffer), (3, 2));
+        vt.process(&mut buffer, b"\x1b[3e");
+        assert_eq!(cursor(&buffer), (6, 2));
+        vt.process(&mut buffer, b"\x1b[999e");
+        assert_eq!(cursor(&buffer), (9, 2));
+        // Inside the scroll region the move stops at the bottom margin
+        vt.process(&mut buffer, b"\x1b[3;6r\x1b[4;1H\x1b[999e");
+        assert_eq!(cursor(&buffer), (5, 0));
+        vt.process(&mut buffer, b"\x1b[?6h\x1b[999e");
+        assert_eq!(cursor(&buffer), (5, 0));
+    }
+
+    #[test]
+    fn hpb_moves_left_by_a_count() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[2;15H\x1b[j");
+        assert_eq!(cursor(&buffer), (1, 13));
+        vt.process(&mut buffer, b"\x1b[0j");
+        assert_eq!(cursor(&buffer), (1, 12));
+        vt.process(&mut buffer, b"\x1b[5j");
+        assert_eq!(cursor(&buffer), (1, 7));
+        vt.process(&mut buffer, b"\x1b[999j");
+        assert_eq!(cursor(&buffer), (1, 0));
+        // Inside the side margins the move stops at the left margin
+        vt.process(&mut buffer, b"\x1b[?69h\x1b[5;10s\x1b[1;8H\x1b[999j");
+        assert_eq!(cursor(&buffer), (0, 4));
+        vt.process(&mut buffer, b"\x1b[?6h\x1b[3`\x1b[999j");
+        assert_eq!(cursor(&buffer), (0, 4));
+    }
+
+    #[test]
+    fn vpb_moves_up_by_a_count() {
+        let (mut vt, mut buffer) = terminal(20, 10);
+        vt.process(&mut buffer, b"\x1b[9;3H\x1b[k");
+        assert_eq!(cursor(&buffer), (7, 2));
+        vt.process(&mut buffer, b"\x1b[0k");
+        assert_eq!(cursor(&buffer), (6, 2));
+        vt.process(&mut buffer, b"\x1b[3k");
+        assert_eq!(cursor(&buffer), (3, 2));
+        vt.process(&mut buffer, b"\x1b[999k");
+        assert_eq!(cursor(&buffer), (0, 2));
+        // Inside the scroll region the move stops at the top margin
+        vt.process(&mut buffer, b"\x1b[3;6r\x1b[5;1H\x1b[999k");
+        assert_eq!(cursor(&buffer), (2, 0));
+        vt.process(&mut buffer, b"\x1b[?6h\x1b[3d\x1b[999k");
+        assert_eq!(cursor(&buffer), (2, 0));
+    }
+}